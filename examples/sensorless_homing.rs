@@ -6,13 +6,13 @@
 
 #![allow(unused)]
 
-use tmc2209_uart::{MicrostepResolution, Tmc2209};
+use tmc2209_uart::{HomingConfig, Tmc2209};
 
 /// Perform sensorless homing using StallGuard.
 ///
 /// # Important Notes
 ///
-/// 1. StallGuard works best in SpreadCycle mode
+/// 1. StallGuard4 only works in StealthChop mode
 /// 2. The motor must be moving above the TCOOLTHRS velocity
 /// 3. Use moderate speeds for reliable detection
 /// 4. Tune SGTHRS for your specific motor and mechanics
@@ -24,27 +24,18 @@ where
     let mut driver = Tmc2209::new(uart, 0);
 
     // =========================================================================
-    // Step 1: Basic Configuration
+    // Step 1-2: Homing current, microsteps and StallGuard
     // =========================================================================
 
-    // Set appropriate current for homing (usually lower than running current)
-    driver.set_current(12, 6, 4)?;
-
-    // Use lower microstep resolution for more reliable stall detection
-    driver.set_microsteps(MicrostepResolution::M16)?;
-
-    // =========================================================================
-    // Step 2: Configure StallGuard
-    // =========================================================================
-
-    // CRITICAL: Use SpreadCycle mode for StallGuard
-    driver.enable_spreadcycle()?;
-
-    // Configure stall detection
-    driver.configure_stall_detection(50)?;
-
-    // Set TCOOLTHRS - StallGuard is only active when TSTEP < TCOOLTHRS
-    driver.set_coolstep_threshold(0xFFFFF)?;
+    // Lower current and 16 microsteps for reliable stall detection,
+    // StealthChop, SGTHRS and TCOOLTHRS (StallGuard active when
+    // TSTEP < TCOOLTHRS), all validated and written in one call
+    let homing = HomingConfig {
+        stall_threshold: 50,
+        tcoolthrs: 0xFFFFF,
+        ..HomingConfig::new()
+    };
+    driver.prepare_homing(&homing)?;
 
     driver.set_enabled(true)?;
 
@@ -73,9 +64,6 @@ where
     // Step 4: Post-Homing Configuration
     // =========================================================================
 
-    // Switch back to StealthChop for quiet operation
-    driver.enable_stealthchop()?;
-
     // Restore normal running current
     driver.set_current(20, 10, 6)?;
 
@@ -92,7 +80,7 @@ where
 
     // Configure for homing
     driver.set_current(12, 6, 4)?;
-    driver.enable_spreadcycle()?;
+    driver.enable_stealthchop()?;
     driver.configure_stall_detection(50)?;
     driver.set_coolstep_threshold(0xFFFFF)?;
     driver.set_enabled(true)?;
@@ -0,0 +1,294 @@
+//! Several TMC2209 chips sharing one UART.
+//!
+//! Up to four chips can share a single-wire bus, told apart by the slave
+//! address strapped on MS1/MS2. Responses always carry the master address
+//! 0xFF rather than the slave address, so the bus runs strictly one
+//! transaction at a time: a response belongs to the slave whose request is
+//! in flight.
+//!
+//! If two chips share an address both answer a read. The bus detects the
+//! surplus bytes and reports [`Error::BusContention`].
+
+use crate::driver::Tmc2209;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::error::Error;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::registers::{ReadableRegister, WritableRegister};
+
+/// Several TMC2209 chips on one UART, addressed per transaction.
+///
+/// The UART must implement `ReadReady` so that bytes arriving after a
+/// complete response can be detected.
+pub struct Tmc2209Bus<U> {
+    driver: Tmc2209<U>,
+}
+
+impl<U> Tmc2209Bus<U> {
+    /// Create a bus on the given UART.
+    pub fn new(uart: U) -> Self {
+        Self {
+            driver: Tmc2209::new(uart, 0),
+        }
+    }
+
+    /// Get a reference to the underlying driver.
+    ///
+    /// Its slave address is the one used by the last transaction.
+    pub fn driver(&self) -> &Tmc2209<U> {
+        &self.driver
+    }
+
+    /// Get a mutable reference to the underlying driver.
+    ///
+    /// Use this for bus-wide settings such as echo handling or statistics.
+    pub fn driver_mut(&mut self) -> &mut Tmc2209<U> {
+        &mut self.driver
+    }
+
+    /// Release the UART peripheral.
+    pub fn release(self) -> U {
+        self.driver.release()
+    }
+}
+
+// ============================================================================
+// Blocking implementation
+// ============================================================================
+
+#[cfg(feature = "blocking")]
+impl<U, E> Tmc2209Bus<U>
+where
+    U: embedded_io::Read<Error = E>
+        + embedded_io::Write<Error = E>
+        + embedded_io::ReadReady<Error = E>,
+{
+    /// Read a register from the chip at `slave` (blocking).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidSlaveAddress` if `slave` is greater than 3 and
+    /// `Error::BusContention` if extra bytes followed the response. The
+    /// extra bytes are discarded so the next transaction starts clean.
+    pub fn read_register<R: ReadableRegister>(&mut self, slave: u8) -> Result<R, Error<E>> {
+        self.driver.select_slave(slave)?;
+        let result = self.driver.read_register::<R>();
+        self.check_contention()?;
+        result
+    }
+
+    /// Write a register on the chip at `slave` (blocking).
+    pub fn write_register<R: WritableRegister>(
+        &mut self,
+        slave: u8,
+        reg: &R,
+    ) -> Result<(), Error<E>> {
+        self.driver.select_slave(slave)?;
+        self.driver.write_register(reg)
+    }
+
+    /// Write the same register value to several chips (blocking).
+    ///
+    /// The protocol has no broadcast address, so this is sequential, not
+    /// truly simultaneous: all write requests are sent back to back and
+    /// the echoes are only drained at the end, which keeps the gap between
+    /// chips to one datagram time. Typically used to start several axes
+    /// together with one VACTUAL.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidSlaveAddress` without sending anything if any
+    /// address in `slaves` is greater than 3.
+    pub fn write_all_slaves<R: WritableRegister>(
+        &mut self,
+        reg: &R,
+        slaves: &[u8],
+    ) -> Result<(), Error<E>> {
+        self.driver.write_register_sequence(reg, slaves)
+    }
+
+    /// Discard any bytes left on the line and report them as contention.
+    fn check_contention(&mut self) -> Result<(), Error<E>> {
+        let uart = self.driver.uart_mut();
+        let mut buf = [0u8; 8];
+        let mut extra = false;
+        while uart.read_ready().map_err(Error::Uart)? {
+            if uart.read(&mut buf).map_err(Error::Uart)? == 0 {
+                break;
+            }
+            extra = true;
+        }
+        if extra {
+            Err(Error::BusContention)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// ============================================================================
+// Async implementation
+// ============================================================================
+
+#[cfg(feature = "async")]
+impl<U, E> Tmc2209Bus<U>
+where
+    U: embedded_io_async::Read<Error = E>
+        + embedded_io_async::Write<Error = E>
+        + embedded_io_async::ReadReady<Error = E>,
+{
+    /// Read a register from the chip at `slave` (async).
+    pub async fn read_register_async<R: ReadableRegister>(
+        &mut self,
+        slave: u8,
+    ) -> Result<R, Error<E>> {
+        self.driver.select_slave(slave)?;
+        let result = self.driver.read_register_async::<R>().await;
+        self.check_contention_async().await?;
+        result
+    }
+
+    /// Write a register on the chip at `slave` (async).
+    pub async fn write_register_async<R: WritableRegister>(
+        &mut self,
+        slave: u8,
+        reg: &R,
+    ) -> Result<(), Error<E>> {
+        self.driver.select_slave(slave)?;
+        self.driver.write_register_async(reg).await
+    }
+
+    /// Write the same register value to several chips (async).
+    ///
+    /// See [`write_all_slaves`](Self::write_all_slaves).
+    pub async fn write_all_slaves_async<R: WritableRegister>(
+        &mut self,
+        reg: &R,
+        slaves: &[u8],
+    ) -> Result<(), Error<E>> {
+        self.driver.write_register_sequence_async(reg, slaves).await
+    }
+
+    /// Discard any bytes left on the line and report them as contention (async).
+    async fn check_contention_async(&mut self) -> Result<(), Error<E>> {
+        let uart = self.driver.uart_mut();
+        let mut buf = [0u8; 8];
+        let mut extra = false;
+        while uart.read_ready().map_err(Error::Uart)? {
+            if uart.read(&mut buf).await.map_err(Error::Uart)? == 0 {
+                break;
+            }
+            extra = true;
+        }
+        if extra {
+            Err(Error::BusContention)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::datagram::WriteRequest;
+    use crate::mock::MockUart;
+    use crate::registers::{Address, Gconf, Ifcnt, Vactual};
+
+    #[test]
+    fn test_bus_read_write_slave() {
+        let mut uart = MockUart::new();
+        uart.slave = 2;
+        uart.regs[Address::Ifcnt as usize] = 7;
+        let mut bus = Tmc2209Bus::new(uart);
+
+        assert_eq!(bus.read_register::<Ifcnt>(2).unwrap().count(), 7);
+        assert_eq!(bus.driver().slave_addr(), 2);
+
+        let mut vactual = Vactual::new();
+        vactual.set_velocity(1000);
+        bus.write_register(2, &vactual).unwrap();
+        assert_eq!(bus.driver().uart().writes(), &[(0x22, 1000)]);
+
+        // Nobody answers at slave 1
+        assert_eq!(bus.read_register::<Ifcnt>(1), Err(Error::NoResponse));
+    }
+
+    #[test]
+    fn test_bus_address_collision() {
+        let mut uart = MockUart::new();
+        uart.slave = 1;
+        uart.duplicate_reply = true;
+        let mut bus = Tmc2209Bus::new(uart);
+
+        assert_eq!(bus.read_register::<Gconf>(1), Err(Error::BusContention));
+        assert_eq!(bus.driver().uart().pending_rx(), 0);
+
+        // The surplus bytes were drained, so the next read is clean
+        bus.driver_mut().uart_mut().duplicate_reply = false;
+        assert!(bus.read_register::<Gconf>(1).is_ok());
+    }
+
+    #[test]
+    fn test_bus_invalid_slave() {
+        let mut bus = Tmc2209Bus::new(MockUart::new());
+        assert_eq!(
+            bus.read_register::<Ifcnt>(4),
+            Err(Error::InvalidSlaveAddress(4))
+        );
+        assert!(bus.driver().uart().tx().is_empty());
+    }
+
+    #[test]
+    fn test_bus_write_all_slaves() {
+        let mut uart = MockUart::new();
+        uart.slave = 1;
+        let mut bus = Tmc2209Bus::new(uart);
+
+        let mut vactual = Vactual::new();
+        vactual.set_velocity(5000);
+        bus.write_all_slaves(&vactual, &[0, 1, 2]).unwrap();
+
+        let tx = bus.driver().uart().tx();
+        assert_eq!(tx.len(), 3 * 8);
+        for (i, slave) in [0u8, 1, 2].iter().enumerate() {
+            let expected = WriteRequest::new(*slave, Address::Vactual, 5000);
+            assert_eq!(&tx[i * 8..(i + 1) * 8], expected.as_bytes());
+        }
+        // The emulated chip at slave 1 took the write; all echoes were read
+        assert_eq!(bus.driver().uart().writes(), &[(0x22, 5000)]);
+        assert_eq!(bus.driver().uart().pending_rx(), 0);
+        assert_eq!(bus.driver().uart().read_calls, 3);
+        assert_eq!(bus.driver().slave_addr(), 2);
+        assert_eq!(bus.driver().last_written::<Vactual>(), Some(vactual));
+    }
+
+    #[test]
+    fn test_bus_write_all_slaves_stats() {
+        let mut bus = Tmc2209Bus::new(MockUart::new());
+        bus.driver_mut().enable_stats();
+        bus.driver_mut().set_verify_echo(true);
+
+        bus.write_all_slaves(&Vactual::new(), &[0, 1, 2]).unwrap();
+        assert_eq!(bus.driver().stats().writes, 3);
+
+        // Corrupt the echo of the second datagram
+        bus.driver_mut().uart_mut().corrupt_echo = Some(8 + 2);
+        assert_eq!(
+            bus.write_all_slaves(&Vactual::new(), &[0, 1, 2]),
+            Err(Error::EchoMismatch)
+        );
+        assert_eq!(bus.driver().stats().writes, 3 + 1);
+        // The third echo was drained
+        assert_eq!(bus.driver().uart().pending_rx(), 0);
+    }
+
+    #[test]
+    fn test_bus_write_all_slaves_invalid() {
+        let mut bus = Tmc2209Bus::new(MockUart::new());
+        assert_eq!(
+            bus.write_all_slaves(&Vactual::new(), &[0, 5, 1]),
+            Err(Error::InvalidSlaveAddress(5))
+        );
+        assert!(bus.driver().uart().tx().is_empty());
+    }
+}
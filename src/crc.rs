@@ -3,6 +3,9 @@
 //! The TMC2209 uses CRC-8 with polynomial 0x07 (reflected).
 //! This implementation uses the same lookup table as the official TMC-API.
 
+/// CRC polynomial used by genuine TMC2209 chips.
+pub const POLY: u8 = 0x07;
+
 /// CRC-8 lookup table for polynomial 0x07 (reflected).
 ///
 /// This table is taken directly from the official Trinamic TMC-API.
@@ -39,21 +42,95 @@ const CRC_TABLE: [u8; 256] = [
 ///
 /// The CRC-8 checksum byte
 pub fn compute(data: &[u8]) -> u8 {
-    let mut crc = 0u8;
+    let mut crc = Crc::new();
+    for &byte in data {
+        crc.update(byte);
+    }
+    crc.finalize()
+}
 
+/// Calculate the CRC-8 checksum with a custom polynomial.
+///
+/// Uses the bitwise algorithm from the TMC2209 datasheet (data bits taken
+/// LSB first). `compute_with(data, POLY)` equals `compute(data)`. Only
+/// needed for clones whose CRC differs from the genuine chip.
+pub fn compute_with(data: &[u8], poly: u8) -> u8 {
+    let mut crc = 0u8;
     for &byte in data {
-        crc = CRC_TABLE[(crc ^ byte) as usize];
+        let mut byte = byte;
+        for _ in 0..8 {
+            if (crc >> 7) ^ (byte & 0x01) != 0 {
+                crc = (crc << 1) ^ poly;
+            } else {
+                crc <<= 1;
+            }
+            byte >>= 1;
+        }
     }
+    crc
+}
 
-    // Bit-reverse the result (as done in the TMC-API)
-    // swap odd and even bits
-    crc = ((crc >> 1) & 0x55) | ((crc & 0x55) << 1);
-    // swap consecutive pairs
-    crc = ((crc >> 2) & 0x33) | ((crc & 0x33) << 2);
-    // swap nibbles
-    crc = ((crc >> 4) & 0x0F) | ((crc & 0x0F) << 4);
+/// Verify a complete message (including CRC byte) with a custom polynomial.
+pub fn verify_with(data: &[u8], poly: u8) -> bool {
+    if data.is_empty() {
+        return false;
+    }
+    let (payload, crc_byte) = data.split_at(data.len() - 1);
+    compute_with(payload, poly) == crc_byte[0]
+}
 
-    crc
+/// Incremental CRC-8 calculator.
+///
+/// Produces the same result as [`compute`] but accepts the data one byte
+/// at a time, so a sniffer can validate frames as bytes arrive without
+/// buffering and re-slicing them.
+///
+/// ```
+/// use tmc2209_uart::crc::{self, Crc};
+///
+/// let mut crc = Crc::new();
+/// for &byte in &[0x05, 0x00, 0x00] {
+///     crc.update(byte);
+/// }
+/// assert_eq!(crc.finalize(), crc::compute(&[0x05, 0x00, 0x00]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Crc {
+    state: u8,
+}
+
+impl Crc {
+    /// Create a calculator with an empty state.
+    pub const fn new() -> Self {
+        Self { state: 0 }
+    }
+
+    /// Feed one byte.
+    #[inline]
+    pub fn update(&mut self, byte: u8) {
+        self.state = CRC_TABLE[(self.state ^ byte) as usize];
+    }
+
+    /// Get the CRC of all bytes fed so far.
+    ///
+    /// The calculator is not consumed, so more bytes may be fed afterwards.
+    pub fn finalize(&self) -> u8 {
+        // Bit-reverse the result (as done in the TMC-API)
+        let mut crc = self.state;
+        // swap odd and even bits
+        crc = ((crc >> 1) & 0x55) | ((crc & 0x55) << 1);
+        // swap consecutive pairs
+        crc = ((crc >> 2) & 0x33) | ((crc & 0x33) << 2);
+        // swap nibbles
+        crc = ((crc >> 4) & 0x0F) | ((crc & 0x0F) << 4);
+        crc
+    }
+
+    /// Reset to the empty state.
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
 }
 
 /// Verify that the CRC of a complete message (including CRC byte) is valid.
@@ -93,4 +170,39 @@ mod tests {
         let crc = compute(&data);
         assert!(verify(&[0x05, 0x00, 0x80, 0x00, 0x00, 0x00, 0x40, crc]));
     }
+
+    #[test]
+    fn test_crc_incremental_matches_compute() {
+        // Expected values from the datasheet's bitwise algorithm
+        let vectors: [(&[u8], u8); 4] = [
+            (&[0x05, 0x00, 0x00], 0x48),
+            (&[0x05, 0x00, 0x80, 0x00, 0x00, 0x00, 0x40], 0x47),
+            (&[0x05, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x40], 0x8A),
+            (&[0x05, 0x03, 0xEC, 0x10, 0x00, 0x00, 0x53], 0x06),
+        ];
+        let mut crc = Crc::new();
+        for (data, expected) in vectors {
+            crc.reset();
+            for &byte in data {
+                crc.update(byte);
+            }
+            assert_eq!(crc.finalize(), expected);
+            assert_eq!(compute(data), expected);
+        }
+    }
+
+    #[test]
+    fn test_crc_custom_poly() {
+        let data = [0x05, 0x00, 0x80, 0x00, 0x00, 0x00, 0x40];
+        assert_eq!(compute_with(&data, POLY), compute(&data));
+        assert_eq!(compute_with(&[0x05, 0x00, 0x00], POLY), 0x48);
+
+        let custom = compute_with(&data, 0x31);
+        assert_ne!(custom, compute(&data));
+        let mut frame = [0u8; 8];
+        frame[..7].copy_from_slice(&data);
+        frame[7] = custom;
+        assert!(verify_with(&frame, 0x31));
+        assert!(!verify(&frame));
+    }
 }
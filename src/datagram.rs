@@ -21,6 +21,30 @@ pub const WRITE_BIT: u8 = 0x80;
 /// Address mask (7 bits).
 pub const ADDRESS_MASK: u8 = 0x7F;
 
+/// Encode a register address byte for a write request.
+///
+/// Masks the address to 7 bits and sets [`WRITE_BIT`].
+#[inline]
+pub const fn encode_write_addr(addr: u8) -> u8 {
+    (addr & ADDRESS_MASK) | WRITE_BIT
+}
+
+/// Decode a register address byte from a request.
+///
+/// Returns `(address, is_write)`.
+#[inline]
+pub const fn decode_addr(byte: u8) -> (u8, bool) {
+    (byte & ADDRESS_MASK, byte & WRITE_BIT != 0)
+}
+
+/// Check only the CRC byte of a raw 8-byte response.
+///
+/// Unlike [`ReadResponse::from_bytes`], the sync and master address bytes
+/// are not checked, so this also works on frames where those are wrong.
+pub fn response_crc_ok(bytes: &[u8; ReadResponse::LEN]) -> bool {
+    crc::verify(bytes)
+}
+
 /// Read request datagram (4 bytes).
 ///
 /// Format: `[SYNC, slave_addr, reg_addr, CRC]`
@@ -55,6 +79,34 @@ impl ReadRequest {
         Self { bytes }
     }
 
+    /// Serialize a read request directly into `buf`.
+    ///
+    /// Produces the same bytes as [`ReadRequest::new`] without building
+    /// the struct. Returns the number of bytes written ([`Self::LEN`]), or
+    /// [`Error::BufferTooSmall`].
+    pub fn write_to(slave_addr: u8, reg_addr: Address, buf: &mut [u8]) -> Result<usize, Error<()>> {
+        let buf = buf.get_mut(..Self::LEN).ok_or(Error::BufferTooSmall)?;
+        buf[0] = SYNC;
+        buf[1] = slave_addr;
+        buf[2] = reg_addr as u8;
+        buf[3] = crc::compute(&buf[..3]);
+        Ok(Self::LEN)
+    }
+
+    /// Recompute the CRC with a custom polynomial.
+    ///
+    /// See [`crc::compute_with`].
+    pub fn with_crc_poly(mut self, poly: u8) -> Self {
+        self.bytes[3] = crc::compute_with(&self.bytes[..3], poly);
+        self
+    }
+
+    /// Parse a read request from raw bytes, checking sync and CRC.
+    pub fn from_bytes<E>(bytes: [u8; Self::LEN]) -> Result<Self, Error<E>> {
+        validate_request(&bytes)?;
+        Ok(Self { bytes })
+    }
+
     /// Get the request as a byte slice.
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
@@ -110,7 +162,7 @@ impl WriteRequest {
         let mut bytes = [
             SYNC,
             slave_addr,
-            (reg_addr & ADDRESS_MASK) | WRITE_BIT,
+            encode_write_addr(reg_addr),
             data_bytes[0],
             data_bytes[1],
             data_bytes[2],
@@ -121,6 +173,40 @@ impl WriteRequest {
         Self { bytes }
     }
 
+    /// Serialize a write request directly into `buf`.
+    ///
+    /// Produces the same bytes as [`WriteRequest::new`] without building
+    /// the struct. Returns the number of bytes written ([`Self::LEN`]), or
+    /// [`Error::BufferTooSmall`].
+    pub fn write_to(
+        slave_addr: u8,
+        reg_addr: Address,
+        data: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error<()>> {
+        let buf = buf.get_mut(..Self::LEN).ok_or(Error::BufferTooSmall)?;
+        buf[0] = SYNC;
+        buf[1] = slave_addr;
+        buf[2] = encode_write_addr(reg_addr as u8);
+        buf[3..7].copy_from_slice(&data.to_be_bytes());
+        buf[7] = crc::compute(&buf[..7]);
+        Ok(Self::LEN)
+    }
+
+    /// Recompute the CRC with a custom polynomial.
+    ///
+    /// See [`crc::compute_with`].
+    pub fn with_crc_poly(mut self, poly: u8) -> Self {
+        self.bytes[7] = crc::compute_with(&self.bytes[..7], poly);
+        self
+    }
+
+    /// Parse a write request from raw bytes, checking sync and CRC.
+    pub fn from_bytes<E>(bytes: [u8; Self::LEN]) -> Result<Self, Error<E>> {
+        validate_request(&bytes)?;
+        Ok(Self { bytes })
+    }
+
     /// Get the request as a byte slice.
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
@@ -136,13 +222,21 @@ impl WriteRequest {
     /// Get the register address (without write bit).
     #[inline]
     pub fn reg_addr(&self) -> u8 {
-        self.bytes[2] & ADDRESS_MASK
+        decode_addr(self.bytes[2]).0
     }
 
     /// Get the data value.
+    ///
+    /// Data is sent MSB first (big-endian), as required by the TMC2209.
     #[inline]
     pub fn data(&self) -> u32 {
-        u32::from_be_bytes([self.bytes[3], self.bytes[4], self.bytes[5], self.bytes[6]])
+        u32::from_be_bytes(self.data_be_bytes())
+    }
+
+    /// Get the data bytes exactly as sent on the wire (MSB first).
+    #[inline]
+    pub fn data_be_bytes(&self) -> [u8; 4] {
+        [self.bytes[3], self.bytes[4], self.bytes[5], self.bytes[6]]
     }
 }
 
@@ -188,8 +282,15 @@ impl ReadResponse {
     ///
     /// The parsed response, or an error if validation fails.
     pub fn from_bytes<E>(bytes: [u8; Self::LEN]) -> Result<Self, Error<E>> {
+        Self::from_bytes_with_poly(bytes, crc::POLY)
+    }
+
+    /// Parse a read response whose CRC uses a custom polynomial.
+    ///
+    /// See [`crc::compute_with`].
+    pub fn from_bytes_with_poly<E>(bytes: [u8; Self::LEN], poly: u8) -> Result<Self, Error<E>> {
         let response = Self { bytes };
-        response.validate()?;
+        response.validate(poly)?;
         Ok(response)
     }
 
@@ -208,7 +309,7 @@ impl ReadResponse {
     }
 
     /// Validate the response structure.
-    fn validate<E>(&self) -> Result<(), Error<E>> {
+    fn validate<E>(&self, poly: u8) -> Result<(), Error<E>> {
         // Check sync byte
         if self.bytes[Self::SYNC_IDX] != SYNC {
             return Err(Error::InvalidSync);
@@ -220,7 +321,12 @@ impl ReadResponse {
         }
 
         // Check CRC
-        if !crc::verify(&self.bytes) {
+        let crc_ok = if poly == crc::POLY {
+            crc::verify(&self.bytes)
+        } else {
+            crc::verify_with(&self.bytes, poly)
+        };
+        if !crc_ok {
             return Err(Error::CrcMismatch);
         }
 
@@ -238,12 +344,30 @@ impl ReadResponse {
         Address::from_u8(self.reg_addr())
     }
 
+    /// Get the typed register address, or the raw byte if unknown.
+    pub fn address_or_raw(&self) -> Result<Address, u8> {
+        self.address().ok_or(self.reg_addr())
+    }
+
     /// Get the 32-bit data value.
+    ///
+    /// The chip sends data MSB first (big-endian); this is the register
+    /// value as documented in the datasheet.
     #[inline]
     pub fn data(&self) -> u32 {
         u32::from_be_bytes([self.bytes[3], self.bytes[4], self.bytes[5], self.bytes[6]])
     }
 
+    /// Get the data bytes interpreted as little-endian.
+    ///
+    /// This is [`data`](Self::data) with its bytes swapped. It is only
+    /// useful when porting code that assumed little-endian framing; the
+    /// register value itself is always [`data`](Self::data).
+    #[inline]
+    pub fn data_le(&self) -> u32 {
+        self.data().swap_bytes()
+    }
+
     /// Get the raw bytes of the response.
     #[inline]
     pub fn as_bytes(&self) -> &[u8; Self::LEN] {
@@ -263,27 +387,117 @@ impl AsRef<[u8]> for ReadResponse {
     }
 }
 
+/// Check the sync byte and CRC of a raw request datagram.
+fn validate_request<E>(bytes: &[u8]) -> Result<(), Error<E>> {
+    if bytes[0] != SYNC {
+        return Err(Error::InvalidSync);
+    }
+    if !crc::verify(bytes) {
+        return Err(Error::CrcMismatch);
+    }
+    Ok(())
+}
+
+/// A complete bus transaction as seen on the single wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Transaction {
+    /// Read request followed by the chip's response.
+    Read {
+        /// The read request sent by the master.
+        request: ReadRequest,
+        /// The response sent by the chip.
+        response: ReadResponse,
+    },
+    /// Write request (no response on the bus).
+    Write {
+        /// The write request sent by the master.
+        request: WriteRequest,
+    },
+}
+
+/// Parse a captured transaction from the single-wire bus.
+///
+/// `bytes` must start at the request's sync byte. A read is the 4-byte
+/// request followed by the 8-byte response; a write is the 8-byte request
+/// alone. Every datagram is CRC-checked and the response address must
+/// match the request. Bytes after the transaction are ignored.
+pub fn parse_transaction(bytes: &[u8]) -> Result<Transaction, Error<()>> {
+    if bytes.len() < ReadRequest::LEN {
+        return Err(Error::BufferTooSmall);
+    }
+    let (_, is_write) = decode_addr(bytes[2]);
+
+    if is_write {
+        if bytes.len() < WriteRequest::LEN {
+            return Err(Error::BufferTooSmall);
+        }
+        let mut raw = [0u8; WriteRequest::LEN];
+        raw.copy_from_slice(&bytes[..WriteRequest::LEN]);
+        let request = WriteRequest::from_bytes(raw)?;
+        return Ok(Transaction::Write { request });
+    }
+
+    let mut raw = [0u8; ReadRequest::LEN];
+    raw.copy_from_slice(&bytes[..ReadRequest::LEN]);
+    let request = ReadRequest::from_bytes(raw)?;
+    let response = ReadResponse::from_slice(&bytes[ReadRequest::LEN..])?;
+    if response.reg_addr() != request.reg_addr() {
+        return Err(Error::AddressMismatch {
+            expected: request.reg_addr(),
+            actual: response.reg_addr(),
+        });
+    }
+    Ok(Transaction::Read { request, response })
+}
+
 /// Response reader for non-blocking/streaming response parsing.
 ///
 /// This reader maintains state between read calls, allowing you to
 /// parse responses byte-by-byte or in chunks.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ResponseReader {
     /// Current index into the response buffer.
     index: usize,
     /// Buffer for accumulating response bytes.
     buffer: [u8; ReadResponse::LEN],
+    /// Bytes consumed since the last complete frame.
+    pending: usize,
+    /// CRC polynomial used to validate responses.
+    crc_poly: u8,
 }
 
+impl Default for ResponseReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The reader holds exactly one frame; responses are copied out by value
+const _: () = assert!(core::mem::size_of::<ReadResponse>() == ReadResponse::LEN);
+
 impl ResponseReader {
     /// Create a new response reader.
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            index: 0,
+            buffer: [0; ReadResponse::LEN],
+            pending: 0,
+            crc_poly: crc::POLY,
+        }
+    }
+
+    /// Set the CRC polynomial used to validate responses.
+    ///
+    /// Defaults to [`crc::POLY`].
+    pub fn set_crc_poly(&mut self, poly: u8) {
+        self.crc_poly = poly;
     }
 
     /// Reset the reader state.
     pub fn reset(&mut self) {
         self.index = 0;
+        self.pending = 0;
     }
 
     /// Feed bytes to the reader and attempt to parse a response.
@@ -293,9 +507,27 @@ impl ResponseReader {
     /// A tuple of (bytes_consumed, optional_response).
     /// The response is `Some` when a complete valid response is parsed.
     pub fn feed<E>(&mut self, bytes: &[u8]) -> (usize, Option<Result<ReadResponse, Error<E>>>) {
+        let (consumed, result) = self.parse(bytes);
+        if result.is_some() {
+            self.pending = 0;
+        } else {
+            self.pending = self.pending.saturating_add(consumed);
+        }
+        (consumed, result)
+    }
+
+    /// Parse bytes into the frame buffer.
+    fn parse<E>(&mut self, bytes: &[u8]) -> (usize, Option<Result<ReadResponse, Error<E>>>) {
         let mut consumed = 0;
         let mut remaining = bytes;
 
+        // `index` is always below LEN between calls. Should it ever be
+        // corrupted, drop the partial frame instead of panicking on the
+        // slice below.
+        if self.index >= ReadResponse::LEN {
+            self.index = 0;
+        }
+
         loop {
             // Looking for sync byte
             while self.index == 0 {
@@ -338,6 +570,7 @@ impl ResponseReader {
             }
 
             // Read remaining bytes
+            debug_assert!(self.index >= 2 && self.index < ReadResponse::LEN);
             let needed = ReadResponse::LEN - self.index;
             let available = remaining.len().min(needed);
 
@@ -349,7 +582,7 @@ impl ResponseReader {
             // Check if we have a complete response
             if self.index == ReadResponse::LEN {
                 self.index = 0;
-                let result = ReadResponse::from_bytes(self.buffer);
+                let result = ReadResponse::from_bytes_with_poly(self.buffer, self.crc_poly);
                 return (consumed, Some(result));
             }
 
@@ -357,16 +590,87 @@ impl ResponseReader {
         }
     }
 
+    /// Create a reader with arbitrary internal state, for exercising
+    /// recovery from states `feed` never produces.
+    #[cfg(test)]
+    fn with_state(index: usize, pending: usize) -> Self {
+        Self {
+            index,
+            pending,
+            ..Self::new()
+        }
+    }
+
+    /// Reset the reader and get its frame buffer to read a response into.
+    ///
+    /// Used with [`parse_frame_buffer`](Self::parse_frame_buffer) when a
+    /// whole response is read at once, avoiding a separate buffer.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn frame_buffer(&mut self) -> &mut [u8; ReadResponse::LEN] {
+        self.reset();
+        &mut self.buffer
+    }
+
+    /// Parse a response read into [`frame_buffer`](Self::frame_buffer).
+    ///
+    /// Gives the same result as feeding the eight bytes: a frame is only
+    /// complete if it starts at the first byte.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn parse_frame_buffer<E>(&mut self) -> Option<Result<ReadResponse, Error<E>>> {
+        if self.buffer[0] == SYNC && self.buffer[1] == MASTER_ADDR {
+            Some(ReadResponse::from_bytes_with_poly(
+                self.buffer,
+                self.crc_poly,
+            ))
+        } else {
+            self.pending = ReadResponse::LEN;
+            None
+        }
+    }
+
     /// Get the current number of bytes buffered.
     pub fn buffered(&self) -> usize {
         self.index
     }
+
+    /// Get the number of bytes consumed since the last complete frame.
+    ///
+    /// Unlike [`buffered`](Self::buffered), this also counts noise bytes
+    /// skipped while hunting for a sync byte.
+    pub fn pending_bytes(&self) -> usize {
+        self.pending
+    }
+
+    /// Check whether at least `threshold` bytes were consumed without
+    /// completing a frame.
+    ///
+    /// Callers typically [`reset`](Self::reset) the reader and retry the
+    /// request when this returns `true`.
+    pub fn timed_out(&self, threshold: usize) -> bool {
+        self.pending >= threshold
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_response_crc_ok() {
+        let mut bytes = [SYNC, MASTER_ADDR, 0x00, 0, 0, 0x01, 0xC0, 0];
+        bytes[7] = crc::compute(&bytes[..7]);
+        assert!(response_crc_ok(&bytes));
+
+        bytes[7] ^= 0x01;
+        assert!(!response_crc_ok(&bytes));
+
+        // Structural errors are not this function's concern
+        let mut bad_sync = [0x00, 0x12, 0x00, 0, 0, 0, 0, 0];
+        bad_sync[7] = crc::compute(&bad_sync[..7]);
+        assert!(response_crc_ok(&bad_sync));
+        assert!(ReadResponse::from_bytes::<()>(bad_sync).is_err());
+    }
+
     #[test]
     fn test_read_request() {
         let req = ReadRequest::new(0, Address::Gconf);
@@ -384,6 +688,151 @@ mod tests {
         assert_eq!(req.as_bytes().len(), 8);
     }
 
+    #[test]
+    fn test_write_to_matches_constructors() {
+        let mut buf = [0xAAu8; 10];
+        assert_eq!(
+            ReadRequest::write_to(2, Address::DrvStatus, &mut buf),
+            Ok(4)
+        );
+        assert_eq!(
+            &buf[..4],
+            ReadRequest::new(2, Address::DrvStatus).as_bytes()
+        );
+        assert_eq!(buf[4], 0xAA);
+
+        let n = WriteRequest::write_to(1, Address::Chopconf, 0x1000_0053, &mut buf).unwrap();
+        assert_eq!(n, 8);
+        assert_eq!(
+            &buf[..n],
+            WriteRequest::new(1, Address::Chopconf, 0x1000_0053).as_bytes()
+        );
+        assert_eq!(buf[8], 0xAA);
+    }
+
+    #[test]
+    fn test_write_to_buffer_too_small() {
+        let mut buf = [0u8; 7];
+        assert_eq!(
+            ReadRequest::write_to(0, Address::Gconf, &mut buf[..3]),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(
+            WriteRequest::write_to(0, Address::Gconf, 0, &mut buf),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(buf, [0; 7]);
+    }
+
+    #[test]
+    fn test_data_endianness() {
+        let req = WriteRequest::new(0, Address::Gconf, 0x1234_5678);
+        assert_eq!(req.data_be_bytes(), [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(&req.as_bytes()[3..7], &[0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(req.data(), 0x1234_5678);
+
+        let mut bytes = [SYNC, MASTER_ADDR, 0x00, 0x12, 0x34, 0x56, 0x78, 0x00];
+        bytes[7] = crc::compute(&bytes[..7]);
+        let resp = ReadResponse::from_bytes::<()>(bytes).unwrap();
+        assert_eq!(resp.data(), 0x1234_5678);
+        assert_eq!(resp.data_le(), 0x7856_3412);
+    }
+
+    #[test]
+    fn test_parse_frame_buffer() {
+        let mut frame = [SYNC, MASTER_ADDR, 0x6C, 0x10, 0x00, 0x00, 0x53, 0x00];
+        frame[7] = crc::compute(&frame[..7]);
+
+        let mut reader = ResponseReader::new();
+        *reader.frame_buffer() = frame;
+        let direct = reader.parse_frame_buffer::<()>().unwrap().unwrap();
+        let (_, fed) = ResponseReader::new().feed::<()>(&frame);
+        assert_eq!(direct, fed.unwrap().unwrap());
+        assert_eq!(direct.data(), 0x1000_0053);
+
+        // Misaligned: no complete frame, as with feed
+        let mut shifted = [0u8; 8];
+        shifted[1..].copy_from_slice(&frame[..7]);
+        *reader.frame_buffer() = shifted;
+        assert!(reader.parse_frame_buffer::<()>().is_none());
+        assert!(ResponseReader::new().feed::<()>(&shifted).1.is_none());
+
+        frame[7] ^= 0xFF;
+        *reader.frame_buffer() = frame;
+        assert_eq!(
+            reader.parse_frame_buffer::<()>(),
+            Some(Err(Error::CrcMismatch))
+        );
+    }
+
+    #[test]
+    fn test_address_or_raw() {
+        let mut bytes = [SYNC, MASTER_ADDR, 0x6C, 0, 0, 0, 0, 0];
+        bytes[7] = crc::compute(&bytes[..7]);
+        let resp = ReadResponse::from_bytes::<()>(bytes).unwrap();
+        assert_eq!(resp.address_or_raw(), Ok(Address::Chopconf));
+
+        bytes[2] = 0x50;
+        bytes[7] = crc::compute(&bytes[..7]);
+        let resp = ReadResponse::from_bytes::<()>(bytes).unwrap();
+        assert_eq!(resp.address_or_raw(), Err(0x50));
+    }
+
+    #[test]
+    fn test_encode_decode_addr() {
+        assert_eq!(encode_write_addr(0x6C), 0xEC);
+        assert_eq!(encode_write_addr(0xEC), 0xEC);
+        assert_eq!(decode_addr(0xEC), (0x6C, true));
+        assert_eq!(decode_addr(0x6C), (0x6C, false));
+
+        let req = WriteRequest::new(0, Address::Chopconf, 0);
+        assert_eq!(decode_addr(req.as_bytes()[2]), (0x6C, true));
+        let req = ReadRequest::new(0, Address::Chopconf);
+        assert_eq!(decode_addr(req.as_bytes()[2]), (0x6C, false));
+    }
+
+    #[test]
+    fn test_parse_read_transaction() {
+        let request = ReadRequest::new(0, Address::Ifcnt);
+        let mut response = [SYNC, MASTER_ADDR, 0x02, 0x00, 0x00, 0x00, 0x07, 0x00];
+        response[7] = crc::compute(&response[..7]);
+
+        let mut capture = [0u8; 12];
+        capture[..4].copy_from_slice(request.as_bytes());
+        capture[4..].copy_from_slice(&response);
+
+        match parse_transaction(&capture).unwrap() {
+            Transaction::Read {
+                request: req,
+                response: resp,
+            } => {
+                assert_eq!(req, request);
+                assert_eq!(resp.data(), 7);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Echo without a response
+        assert_eq!(
+            parse_transaction(request.as_bytes()),
+            Err(Error::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_parse_write_transaction() {
+        let request = WriteRequest::new(1, Address::Gconf, 0x0000_00C0);
+        assert_eq!(
+            parse_transaction(request.as_bytes()),
+            Ok(Transaction::Write { request })
+        );
+
+        let mut corrupt = [0u8; 8];
+        corrupt.copy_from_slice(request.as_bytes());
+        corrupt[5] ^= 0x01;
+        assert_eq!(parse_transaction(&corrupt), Err(Error::CrcMismatch));
+    }
+
     #[test]
     fn test_response_reader() {
         // Create a mock response
@@ -398,4 +847,50 @@ mod tests {
         let response = result.unwrap().unwrap();
         assert_eq!(response.data(), 0x00000040);
     }
+
+    #[test]
+    fn test_response_reader_pending_bytes() {
+        let mut response_bytes = [SYNC, MASTER_ADDR, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00];
+        response_bytes[7] = crc::compute(&response_bytes[..7]);
+
+        let mut reader = ResponseReader::new();
+        // Noise followed by a partial frame
+        let (_, result) = reader.feed::<()>(&[0x00, 0x11]);
+        assert!(result.is_none());
+        let (_, result) = reader.feed::<()>(&response_bytes[..5]);
+        assert!(result.is_none());
+        assert_eq!(reader.pending_bytes(), 7);
+        assert_eq!(reader.buffered(), 5);
+        assert!(reader.timed_out(7));
+        assert!(!reader.timed_out(8));
+
+        // Completing the frame clears the counter
+        let (_, result) = reader.feed::<()>(&response_bytes[5..]);
+        assert!(result.is_some());
+        assert_eq!(reader.pending_bytes(), 0);
+
+        reader.feed::<()>(&response_bytes[..3]);
+        reader.reset();
+        assert_eq!(reader.pending_bytes(), 0);
+        assert!(!reader.timed_out(1));
+    }
+
+    #[test]
+    fn test_response_reader_recovers_corrupt_index() {
+        let mut response_bytes = [SYNC, MASTER_ADDR, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00];
+        response_bytes[7] = crc::compute(&response_bytes[..7]);
+
+        for index in [ReadResponse::LEN, ReadResponse::LEN + 1, usize::MAX] {
+            let mut reader = ResponseReader::with_state(index, 3);
+            let (consumed, result) = reader.feed::<()>(&response_bytes);
+            assert_eq!(consumed, 8);
+            assert_eq!(result.unwrap().unwrap().data(), 0x40);
+            assert_eq!(reader.buffered(), 0);
+            assert_eq!(reader.pending_bytes(), 0);
+        }
+
+        let mut reader = ResponseReader::with_state(usize::MAX, 0);
+        assert_eq!(reader.feed::<()>(&[]), (0, None));
+        assert_eq!(reader.buffered(), 0);
+    }
 }
@@ -0,0 +1,167 @@
+//! Stall detection through the DIAG pin.
+//!
+//! The TMC2209 drives DIAG high when StallGuard detects a stall. Reading
+//! the pin directly is much faster than polling SG_RESULT over UART, so
+//! it is the preferred way to detect the end stop during sensorless homing.
+
+use embedded_hal::digital::InputPin;
+
+use crate::driver::Tmc2209;
+#[cfg(feature = "blocking")]
+use crate::error::Error;
+#[cfg(feature = "blocking")]
+use crate::registers::{Sgthrs, Tcoolthrs};
+
+/// TMC2209 driver paired with its DIAG pin.
+///
+/// Stall thresholds are still configured over UART; only the stall
+/// indication is read from the pin.
+pub struct Tmc2209WithDiag<U, P, D = fn()> {
+    driver: Tmc2209<U, D>,
+    diag: P,
+}
+
+impl<U, P, D> Tmc2209WithDiag<U, P, D> {
+    /// Pair a driver with the input pin connected to DIAG.
+    pub fn new(driver: Tmc2209<U, D>, diag: P) -> Self {
+        Self { driver, diag }
+    }
+
+    /// Get a reference to the UART driver.
+    pub fn driver(&self) -> &Tmc2209<U, D> {
+        &self.driver
+    }
+
+    /// Get a mutable reference to the UART driver.
+    pub fn driver_mut(&mut self) -> &mut Tmc2209<U, D> {
+        &mut self.driver
+    }
+
+    /// Release the driver and the DIAG pin.
+    pub fn release(self) -> (Tmc2209<U, D>, P) {
+        (self.driver, self.diag)
+    }
+}
+
+impl<U, P: InputPin, D> Tmc2209WithDiag<U, P, D> {
+    /// Check whether DIAG currently signals a stall.
+    pub fn is_stalled(&mut self) -> Result<bool, P::Error> {
+        self.diag.is_high()
+    }
+
+    /// Wait for DIAG to signal a stall.
+    ///
+    /// Samples the pin up to `max_polls` times, calling `delay` between
+    /// samples. Returns `true` as soon as a stall is seen, or `false` if
+    /// none occurred within the poll budget.
+    pub fn wait_for_stall(
+        &mut self,
+        max_polls: u32,
+        mut delay: impl FnMut(),
+    ) -> Result<bool, P::Error> {
+        for _ in 0..max_polls {
+            if self.diag.is_high()? {
+                return Ok(true);
+            }
+            delay();
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<U, P, E, D> Tmc2209WithDiag<U, P, D>
+where
+    U: embedded_io::Read<Error = E> + embedded_io::Write<Error = E>,
+    D: FnMut(),
+{
+    /// Configure StallGuard for DIAG-based stall detection.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - SGTHRS value (0-255, higher = more sensitive)
+    /// * `tcoolthrs` - TCOOLTHRS value; stall output is only active while
+    ///   TSTEP is below this
+    pub fn configure_stall(&mut self, threshold: u8, tcoolthrs: u32) -> Result<(), Error<E>> {
+        let mut sgthrs = Sgthrs::new();
+        sgthrs.set_threshold(threshold);
+        self.driver.write_register(&sgthrs)?;
+
+        let mut tcool = Tcoolthrs::new();
+        tcool.set_threshold(tcoolthrs);
+        self.driver.write_register(&tcool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+
+    /// Pin that goes high after a number of samples.
+    struct MockPin {
+        low_samples: u32,
+        samples: u32,
+    }
+
+    impl embedded_hal::digital::ErrorType for MockPin {
+        type Error = Infallible;
+    }
+
+    impl InputPin for MockPin {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            self.samples += 1;
+            Ok(self.samples > self.low_samples)
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    #[test]
+    fn test_wait_for_stall() {
+        let pin = MockPin {
+            low_samples: 3,
+            samples: 0,
+        };
+        let mut diag = Tmc2209WithDiag::new(Tmc2209::new((), 0), pin);
+
+        let mut delays = 0;
+        assert_eq!(diag.wait_for_stall(10, || delays += 1), Ok(true));
+        assert_eq!(delays, 3);
+        assert_eq!(diag.release().1.samples, 4);
+    }
+
+    #[test]
+    fn test_wait_for_stall_timeout() {
+        let pin = MockPin {
+            low_samples: 100,
+            samples: 0,
+        };
+        let mut diag = Tmc2209WithDiag::new(Tmc2209::new((), 0), pin);
+
+        assert_eq!(diag.wait_for_stall(5, || {}), Ok(false));
+        assert_eq!(diag.is_stalled(), Ok(false));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_configure_stall() {
+        use crate::mock::MockUart;
+        use crate::registers::Address;
+
+        let pin = MockPin {
+            low_samples: 0,
+            samples: 0,
+        };
+        let mut diag = Tmc2209WithDiag::new(Tmc2209::new(MockUart::new(), 0), pin);
+        diag.configure_stall(80, 400).unwrap();
+
+        let uart = diag.driver().uart();
+        assert_eq!(
+            uart.writes(),
+            &[(Address::Sgthrs as u8, 80), (Address::Tcoolthrs as u8, 400)]
+        );
+    }
+}
@@ -3,13 +3,329 @@
 //! This module provides the main `Tmc2209` struct for communicating with
 //! TMC2209 stepper motor drivers via UART.
 
-use crate::datagram::{ReadRequest, ReadResponse, ResponseReader, WriteRequest};
+use crate::datagram::{ReadRequest, ReadResponse, ResponseReader, WriteRequest, ADDRESS_MASK};
+#[cfg(any(feature = "blocking", feature = "async"))]
 use crate::error::Error;
 use crate::registers::{
-    Chopconf, Coolconf, DrvStatus, Gconf, Gstat, Ifcnt, IholdIrun, Ioin, MicrostepResolution,
-    Mscnt, Pwmconf, ReadableRegister, SgResult, Sgthrs, Tcoolthrs, Tpwmthrs, Tstep, Vactual,
+    Address, Chopconf, ChopconfDecoded, ChopperMode, ChopperWarnings, Coolconf, CurrentScale,
+    DrvStatus, FaultReport, FieldError, Gconf, GconfDecoded, Gstat, Ifcnt, IholdIrun, Ioin,
+    IoinState, MicrostepResolution, Mscnt, Mscuract, OtpConfirm, OtpField, OtpProg, OtpRead,
+    PwmAuto, Pwmconf, ReadableRegister, Register, Revision, SgResult, Sgthrs, Slaveconf,
+    StandstillMode, Tcoolthrs, ThermalStatus, Tpowerdown, Tpwmthrs, Tstep, Vactual,
     WritableRegister,
 };
+use crate::telemetry::MotorState;
+use crate::util::{
+    calculate_current_settings, coil_current_ma, cs_to_current, mscnt_shortest_delta,
+    recommend_stall_threshold, round_f32, velocity_to_tpwmthrs, velocity_to_vactual, VelocityState,
+    DEFAULT_FCLK,
+};
+
+/// Maximum number of SG_RESULT samples taken by `calibrate_stall_threshold`.
+#[cfg(any(feature = "blocking", feature = "async"))]
+const MAX_STALL_SAMPLES: usize = 64;
+
+/// Number of DRV_STATUS polls made by `disable_gracefully` before giving up.
+#[cfg(any(feature = "blocking", feature = "async"))]
+const STANDSTILL_POLLS: u32 = 100;
+
+/// Bus transaction statistics.
+///
+/// Collected only after [`Tmc2209::enable_stats`] is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BusStats {
+    /// Successful register reads.
+    pub reads: u32,
+    /// Successful register writes.
+    pub writes: u32,
+    /// Responses rejected due to a CRC mismatch.
+    pub crc_errors: u32,
+    /// Transactions that received no (or an incomplete) response.
+    pub no_response: u32,
+    /// Reads repeated under the retry policy set with
+    /// [`Tmc2209::set_read_retries`]; a retried read that then succeeds
+    /// also counts in `reads`.
+    pub retries: u32,
+}
+
+/// Cached values of registers that rarely or never change.
+#[derive(Debug, Clone, Copy, Default)]
+struct ReadCache {
+    /// Cached OTP_READ value, if caching is enabled.
+    otp: Option<u32>,
+    /// Cached IOIN value, if present.
+    ioin: Option<u32>,
+    /// Number of IOIN reads served from the cache per bus read (0 = off).
+    ioin_ttl: u32,
+    /// Remaining IOIN reads to serve from the cache.
+    ioin_left: u32,
+}
+
+impl ReadCache {
+    /// Look up a cached value, consuming one IOIN cache hit.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn get(&mut self, addr: Address) -> Option<u32> {
+        match addr {
+            Address::OtpRead => self.otp,
+            Address::Ioin if self.ioin_left > 0 => {
+                self.ioin_left -= 1;
+                self.ioin
+            }
+            _ => None,
+        }
+    }
+
+    /// Record a value read from the bus.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn store(&mut self, addr: Address, value: u32) {
+        if addr == Address::Ioin && self.ioin_ttl > 0 {
+            self.ioin = Some(value);
+            self.ioin_left = self.ioin_ttl;
+        }
+    }
+
+    /// Drop all cached values, keeping the configuration.
+    fn invalidate(&mut self) {
+        self.otp = None;
+        self.ioin = None;
+        self.ioin_left = 0;
+    }
+}
+
+/// Last values written to the configuration registers.
+///
+/// Write-only registers cannot be read back from the chip, so the driver
+/// remembers what it wrote. GSTAT and OTP_PROG are not recorded since
+/// writing them triggers an action rather than storing a setting.
+#[derive(Debug, Clone, Copy, Default)]
+struct Shadow {
+    values: [Option<u32>; Shadow::ADDRESSES.len()],
+}
+
+impl Shadow {
+    /// Registers tracked by the shadow, in slot order.
+    const ADDRESSES: [Address; 12] = [
+        Address::Gconf,
+        Address::Slaveconf,
+        Address::FactoryConf,
+        Address::IholdIrun,
+        Address::Tpowerdown,
+        Address::Tpwmthrs,
+        Address::Tcoolthrs,
+        Address::Vactual,
+        Address::Sgthrs,
+        Address::Coolconf,
+        Address::Chopconf,
+        Address::Pwmconf,
+    ];
+
+    /// Get the slot of a raw register address.
+    fn slot(addr: u8) -> Option<usize> {
+        Self::ADDRESSES.iter().position(|&a| a as u8 == addr)
+    }
+
+    /// Get the last value written to a register.
+    fn get(&self, addr: Address) -> Option<u32> {
+        Self::slot(addr as u8).and_then(|slot| self.values[slot])
+    }
+
+    /// Record a write to a raw register address.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn record(&mut self, addr: u8, value: u32) {
+        if let Some(slot) = Self::slot(addr) {
+            self.values[slot] = Some(value);
+        }
+    }
+
+    /// Forget all recorded values.
+    fn clear(&mut self) {
+        self.values = [None; Shadow::ADDRESSES.len()];
+    }
+}
+
+/// Result of a periodic [`Tmc2209::health_check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Health {
+    /// No reset and no fault.
+    Ok,
+    /// The chip was reset (GSTAT.reset) and lost its configuration.
+    NeedsReconfig,
+    /// A fault is present.
+    Fault {
+        /// The fault flags.
+        report: FaultReport,
+        /// The chip was also reset and lost its configuration, which must
+        /// be written again once the fault is handled.
+        reset: bool,
+    },
+}
+
+/// Result of [`Tmc2209::stability_check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StabilityReport {
+    /// Bit `i` is set if `REGISTERS[i]` read back differently.
+    unstable: u8,
+}
+
+impl StabilityReport {
+    /// Registers compared by the check.
+    ///
+    /// Registers that change on their own (GSTAT, IOIN, TSTEP, SG_RESULT,
+    /// MSCNT, MSCURACT, DRV_STATUS, PWM_SCALE, PWM_AUTO) are skipped.
+    pub const REGISTERS: [Address; 6] = [
+        Address::Gconf,
+        Address::Ifcnt,
+        Address::OtpRead,
+        Address::FactoryConf,
+        Address::Chopconf,
+        Address::Pwmconf,
+    ];
+
+    /// Check if every register read back the same value twice.
+    pub fn is_stable(&self) -> bool {
+        self.unstable == 0
+    }
+
+    /// Check if a register read back different values.
+    ///
+    /// Always false for registers not in [`REGISTERS`](Self::REGISTERS).
+    pub fn is_unstable(&self, addr: Address) -> bool {
+        self.unstable().any(|a| a == addr)
+    }
+
+    /// Iterate over the registers that read back different values.
+    pub fn unstable(&self) -> impl Iterator<Item = Address> + '_ {
+        Self::REGISTERS
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.unstable & (1 << i) != 0)
+            .map(|(_, addr)| *addr)
+    }
+
+    /// Record the two values read from `REGISTERS[index]`.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn compare(&mut self, index: usize, first: u32, second: u32) {
+        if first != second {
+            self.unstable |= 1 << index;
+        }
+    }
+}
+
+/// Settings applied by [`Tmc2209::prepare_homing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HomingConfig {
+    /// Run current during homing (0-31).
+    pub run_current: u8,
+    /// Hold current during homing (0-31).
+    pub hold_current: u8,
+    /// Delay before reducing to hold current (0-15).
+    pub hold_delay: u8,
+    /// Microstep resolution.
+    pub microsteps: MicrostepResolution,
+    /// StallGuard threshold (SGTHRS).
+    pub stall_threshold: u8,
+    /// TSTEP below which StallGuard is active (TCOOLTHRS, 1-0xFFFFF).
+    pub tcoolthrs: u32,
+}
+
+impl HomingConfig {
+    /// Moderate defaults: IRUN=12, IHOLD=6, 16 microsteps, SGTHRS=50,
+    /// StallGuard active at any velocity.
+    pub const fn new() -> Self {
+        Self {
+            run_current: 12,
+            hold_current: 6,
+            hold_delay: 4,
+            microsteps: MicrostepResolution::M16,
+            stall_threshold: 50,
+            tcoolthrs: 0xFFFFF,
+        }
+    }
+
+    /// Check the preconditions for stall detection.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        let currents_valid = self.run_current <= 31 && self.hold_current <= 31;
+        let tcoolthrs_valid = (1..=0xFFFFF).contains(&self.tcoolthrs);
+        if !currents_valid || !tcoolthrs_valid || self.hold_delay > 15 {
+            return Err(Error::InvalidArgument);
+        }
+        Ok(())
+    }
+}
+
+impl Default for HomingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Register values written by [`Tmc2209::apply_config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Config {
+    /// Global configuration.
+    pub gconf: Gconf,
+    /// Run and hold current.
+    pub ihold_irun: IholdIrun,
+    /// Delay before power down at standstill.
+    pub tpowerdown: Tpowerdown,
+    /// StealthChop upper velocity threshold.
+    pub tpwmthrs: Tpwmthrs,
+    /// CoolStep and StallGuard lower velocity threshold.
+    pub tcoolthrs: Tcoolthrs,
+    /// StallGuard threshold.
+    pub sgthrs: Sgthrs,
+    /// CoolStep configuration.
+    pub coolconf: Coolconf,
+    /// Chopper configuration.
+    pub chopconf: Chopconf,
+    /// StealthChop PWM configuration.
+    pub pwmconf: Pwmconf,
+}
+
+impl Config {
+    /// Create a configuration with every register at its reset value.
+    pub fn new() -> Self {
+        Self {
+            gconf: Gconf::new(),
+            ihold_irun: IholdIrun::new(),
+            tpowerdown: Tpowerdown::new(),
+            tpwmthrs: Tpwmthrs::new(),
+            tcoolthrs: Tcoolthrs::new(),
+            sgthrs: Sgthrs::new(),
+            coolconf: Coolconf::new(),
+            chopconf: Chopconf::new(),
+            pwmconf: Pwmconf::new(),
+        }
+    }
+
+    /// Get the registers in write order, GCONF first.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn registers(&self) -> [(Address, u32); 9] {
+        [
+            (Address::Gconf, self.gconf.raw()),
+            (Address::IholdIrun, self.ihold_irun.raw()),
+            (Address::Tpowerdown, self.tpowerdown.raw()),
+            (Address::Tpwmthrs, self.tpwmthrs.raw()),
+            (Address::Tcoolthrs, self.tcoolthrs.raw()),
+            (Address::Sgthrs, self.sgthrs.raw()),
+            (Address::Coolconf, self.coolconf.raw()),
+            (Address::Chopconf, self.chopconf.raw()),
+            (Address::Pwmconf, self.pwmconf.raw()),
+        ]
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// TMC2209 driver over UART.
 ///
@@ -20,7 +336,7 @@ use crate::registers::{
 /// # Type Parameters
 ///
 /// * `U` - UART peripheral type implementing `embedded_io::Read + embedded_io::Write`
-///         or `embedded_io_async::Read + embedded_io_async::Write`
+///   or `embedded_io_async::Read + embedded_io_async::Write`
 ///
 /// # Example (blocking)
 ///
@@ -37,13 +353,42 @@ use crate::registers::{
 /// irun.set_irun(16).set_ihold(8);
 /// driver.write_register(&irun)?;
 /// ```
-pub struct Tmc2209<U> {
+pub struct Tmc2209<U, D = fn()> {
     /// UART peripheral.
     uart: U,
     /// Slave address (0-3).
     slave_addr: u8,
     /// Response reader for parsing incoming data.
     reader: ResponseReader,
+    /// Bus statistics, if enabled.
+    stats: Option<BusStats>,
+    /// Read cache for OTP_READ and IOIN.
+    cache: ReadCache,
+    /// The bus echoes transmitted bytes (single-wire topology).
+    echo: bool,
+    /// Compare echoed bytes against the transmitted request.
+    verify_echo: bool,
+    /// Last values written to configuration registers.
+    shadow: Shadow,
+    /// Custom CRC polynomial, if not the genuine one.
+    crc_poly: Option<u8>,
+    /// Velocity limit in full steps/second for `set_velocity_sps`.
+    max_velocity: Option<f32>,
+    /// Delay called between transmitted bytes, if set.
+    inter_byte_delay: Option<D>,
+    /// TOFF written by `set_enabled(true)` when the driver is disabled.
+    default_toff: u8,
+    /// TSTEP below which `open_load_valid` trusts OLA/OLB.
+    open_load_tstep: u32,
+    /// Internal clock frequency used by the velocity conversions.
+    fclk: u32,
+    /// Number of times a failed register read is retried.
+    read_retries: u8,
+    /// Retry reads that fail with `AddressMismatch`.
+    retry_address_mismatch: bool,
+    /// Calls made to the UART's `read`, wrapping on overflow.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    uart_reads: usize,
 }
 
 impl<U> Tmc2209<U> {
@@ -63,9 +408,26 @@ impl<U> Tmc2209<U> {
             uart,
             slave_addr,
             reader: ResponseReader::new(),
+            stats: None,
+            cache: ReadCache::default(),
+            echo: true,
+            verify_echo: false,
+            shadow: Shadow::default(),
+            crc_poly: None,
+            max_velocity: None,
+            inter_byte_delay: None,
+            default_toff: 3,
+            open_load_tstep: 0xFFFFF,
+            fclk: DEFAULT_FCLK,
+            read_retries: 0,
+            retry_address_mismatch: false,
+            #[cfg(any(feature = "blocking", feature = "async"))]
+            uart_reads: 0,
         }
     }
+}
 
+impl<U, D> Tmc2209<U, D> {
     /// Get the slave address.
     pub fn slave_addr(&self) -> u8 {
         self.slave_addr
@@ -81,6 +443,23 @@ impl<U> Tmc2209<U> {
         self.slave_addr = addr;
     }
 
+    /// Switch to another chip on a shared bus.
+    ///
+    /// Cached reads and shadowed writes belong to the previous chip, so
+    /// they are dropped when the address changes.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn select_slave<E>(&mut self, addr: u8) -> Result<(), Error<E>> {
+        if addr > 3 {
+            return Err(Error::InvalidSlaveAddress(addr));
+        }
+        if addr != self.slave_addr {
+            self.slave_addr = addr;
+            self.cache.invalidate();
+            self.shadow.clear();
+        }
+        Ok(())
+    }
+
     /// Get a reference to the UART peripheral.
     pub fn uart(&self) -> &U {
         &self.uart
@@ -96,836 +475,5121 @@ impl<U> Tmc2209<U> {
         self.uart
     }
 
-    /// Create a read request for a register.
-    fn read_request<R: ReadableRegister>(&self) -> ReadRequest {
-        ReadRequest::new(self.slave_addr, R::ADDRESS)
-    }
-
-    /// Create a write request for a register.
-    fn write_request<R: WritableRegister>(&self, reg: &R) -> WriteRequest {
-        WriteRequest::new(self.slave_addr, R::ADDRESS, (*reg).into())
+    /// Set whether the bus echoes transmitted bytes.
+    ///
+    /// On the usual single-wire connection every byte sent also arrives on
+    /// RX and the driver skips it. Disable this for two-wire setups where
+    /// RX only sees the chip's responses; otherwise the driver would wait
+    /// for echo bytes that never come. Enabled by default.
+    pub fn set_echo_enabled(&mut self, enabled: bool) {
+        self.echo = enabled;
     }
-}
-
-// ============================================================================
-// Blocking API
-// ============================================================================
 
-#[cfg(feature = "blocking")]
-impl<U, E> Tmc2209<U>
-where
-    U: embedded_io::Read<Error = E> + embedded_io::Write<Error = E>,
-{
-    /// Read a register (blocking).
+    /// Set the TOFF used when `set_enabled(true)` re-enables the driver.
     ///
-    /// Sends a read request and waits for the response.
+    /// Defaults to 3.
     ///
-    /// # Type Parameters
+    /// # Errors
     ///
-    /// * `R` - Register type to read (must implement `ReadableRegister`)
+    /// Returns a [`FieldError`] if `toff` is not in 1..=15 (0 would keep
+    /// the driver disabled); the previous TOFF is kept.
+    pub fn set_default_toff(&mut self, toff: u8) -> Result<(), FieldError> {
+        if !(1..=15).contains(&toff) {
+            return Err(FieldError {
+                field: "toff",
+                value: toff as u32,
+                max: 15,
+            });
+        }
+        self.default_toff = toff;
+        Ok(())
+    }
+
+    /// Set the TSTEP threshold for [`open_load_valid`](Self::open_load_valid).
     ///
-    /// # Returns
+    /// OLA/OLB are only reported while TSTEP is below `tstep`, i.e. while
+    /// the motor moves faster than the velocity `tstep` corresponds to
+    /// (see [`velocity_to_tpwmthrs`]). Defaults to 0xFFFFF, which only
+    /// excludes standstill.
+    pub fn set_open_load_tstep_threshold(&mut self, tstep: u32) {
+        self.open_load_tstep = tstep;
+    }
+
+    /// Transmit requests one byte at a time with `delay` between bytes.
     ///
-    /// The register value, or an error if communication fails.
-    pub fn read_register<R: ReadableRegister>(&mut self) -> Result<R, Error<E>> {
-        let request = self.read_request::<R>();
+    /// A workaround for bit-banged software UARTs that lose framing on
+    /// back-to-back bytes. By default each request is sent with a single
+    /// `write_all`. The delay must have the driver's delay type; use
+    /// [`with_inter_byte_delay`](Self::with_inter_byte_delay) to install a
+    /// capturing closure.
+    pub fn set_inter_byte_delay(&mut self, delay: D) {
+        self.inter_byte_delay = Some(delay);
+    }
 
-        // Send the read request
-        self.uart
-            .write_all(request.as_bytes())
-            .map_err(Error::Uart)?;
-        self.uart.flush().map_err(Error::Uart)?;
+    /// Return the driver with `delay` called between transmitted bytes.
+    ///
+    /// Like [`set_inter_byte_delay`](Self::set_inter_byte_delay), but
+    /// accepts any `FnMut()`, e.g. a closure that owns a delay provider.
+    /// All other settings and shadowed values are kept.
+    pub fn with_inter_byte_delay<F: FnMut()>(self, delay: F) -> Tmc2209<U, F> {
+        Tmc2209 {
+            uart: self.uart,
+            slave_addr: self.slave_addr,
+            reader: self.reader,
+            stats: self.stats,
+            cache: self.cache,
+            echo: self.echo,
+            verify_echo: self.verify_echo,
+            shadow: self.shadow,
+            crc_poly: self.crc_poly,
+            max_velocity: self.max_velocity,
+            inter_byte_delay: Some(delay),
+            default_toff: self.default_toff,
+            open_load_tstep: self.open_load_tstep,
+            fclk: self.fclk,
+            read_retries: self.read_retries,
+            retry_address_mismatch: self.retry_address_mismatch,
+            #[cfg(any(feature = "blocking", feature = "async"))]
+            uart_reads: self.uart_reads,
+        }
+    }
 
-        // Read the response
-        // TMC2209 echoes back the request, then sends the response
-        // We need to skip the echo (4 bytes) and read the response (8 bytes)
-        let mut echo_buf = [0u8; 4];
-        self.read_exact(&mut echo_buf)?;
+    /// Send requests with a single `write_all` again.
+    pub fn clear_inter_byte_delay(&mut self) {
+        self.inter_byte_delay = None;
+    }
 
-        let response = self.read_response()?;
+    /// Set the velocity limit in full steps/second.
+    ///
+    /// `set_velocity_sps` rejects faster requests in either direction with
+    /// [`Error::VelocityLimited`]. Raw [`set_velocity`](Self::set_velocity)
+    /// writes are not checked. There is no limit by default.
+    pub fn set_max_velocity(&mut self, steps_per_sec: f32) {
+        self.max_velocity = Some(steps_per_sec.abs());
+    }
 
-        // Verify the register address matches
-        let expected_addr = R::ADDRESS as u8;
-        if response.reg_addr() != expected_addr {
-            return Err(Error::AddressMismatch {
-                expected: expected_addr,
-                actual: response.reg_addr(),
-            });
-        }
+    /// Remove the velocity limit.
+    pub fn clear_max_velocity(&mut self) {
+        self.max_velocity = None;
+    }
 
-        Ok(R::from(response.data()))
+    /// Get the velocity limit in full steps/second, if set.
+    pub fn max_velocity(&self) -> Option<f32> {
+        self.max_velocity
     }
 
-    /// Write a register (blocking).
-    ///
-    /// Sends a write request to update a register value.
-    ///
-    /// # Arguments
-    ///
-    /// * `reg` - Register value to write
-    ///
-    /// # Returns
+    /// Set the internal clock frequency in Hz.
     ///
-    /// `Ok(())` on success, or an error if communication fails.
-    pub fn write_register<R: WritableRegister>(&mut self, reg: &R) -> Result<(), Error<E>> {
-        let request = self.write_request(reg);
+    /// Used by the methods converting between full steps/second and
+    /// VACTUAL, TSTEP or the velocity thresholds. Defaults to
+    /// [`DEFAULT_FCLK`]; change it when the chip runs from an external
+    /// clock or a measured frequency is known.
+    pub fn set_fclk(&mut self, fclk: u32) {
+        self.fclk = fclk;
+    }
 
-        // Send the write request
-        self.uart
-            .write_all(request.as_bytes())
-            .map_err(Error::Uart)?;
-        self.uart.flush().map_err(Error::Uart)?;
+    /// Get the internal clock frequency in Hz.
+    pub fn fclk(&self) -> u32 {
+        self.fclk
+    }
 
-        // Read back the echo (8 bytes) - TMC2209 echoes write requests
-        let mut echo_buf = [0u8; 8];
-        self.read_exact(&mut echo_buf)?;
+    /// Set how many times a failed register read is retried.
+    ///
+    /// Reads of typed registers failing with a CRC mismatch, a missing
+    /// response or a bad sync or master address byte are sent again up to
+    /// `retries` times. Other errors are returned immediately and writes
+    /// are never retried. Defaults to 0.
+    pub fn set_read_retries(&mut self, retries: u8) {
+        self.read_retries = retries;
+    }
 
-        Ok(())
+    /// Get the number of times a failed register read is retried.
+    pub fn read_retries(&self) -> u8 {
+        self.read_retries
     }
 
-    /// Read a register by raw address (blocking).
+    /// Also retry reads whose response carries the wrong register address.
     ///
-    /// Use this when you need to read a register by its raw address value.
-    pub fn read_raw(&mut self, reg_addr: u8) -> Result<u32, Error<E>> {
-        let request = ReadRequest::from_raw_addr(self.slave_addr, reg_addr);
+    /// On a noisy bus an [`Error::AddressMismatch`] usually means a
+    /// misaligned response frame rather than a logic error. Only has an
+    /// effect together with [`set_read_retries`](Self::set_read_retries).
+    /// Disabled by default.
+    pub fn set_retry_address_mismatch(&mut self, enabled: bool) {
+        self.retry_address_mismatch = enabled;
+    }
 
-        self.uart
-            .write_all(request.as_bytes())
-            .map_err(Error::Uart)?;
-        self.uart.flush().map_err(Error::Uart)?;
+    /// Check if a failed read may be retried under the retry policy.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn is_retriable<E>(&self, error: &Error<E>) -> bool {
+        match error {
+            Error::CrcMismatch
+            | Error::NoResponse
+            | Error::InvalidSync
+            | Error::InvalidMasterAddress => true,
+            Error::AddressMismatch { .. } => self.retry_address_mismatch,
+            _ => false,
+        }
+    }
 
-        // Skip echo
-        let mut echo_buf = [0u8; 4];
-        self.read_exact(&mut echo_buf)?;
+    /// Discard the partial response and count the retry.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn begin_retry(&mut self) {
+        self.reader.reset();
+        if let Some(stats) = self.stats.as_mut() {
+            stats.retries = stats.retries.wrapping_add(1);
+        }
+    }
 
-        let response = self.read_response()?;
-        Ok(response.data())
+    /// Check a velocity against the configured limit.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn check_velocity<E>(&self, steps_per_sec: f32) -> Result<(), Error<E>> {
+        match self.max_velocity {
+            Some(max) if steps_per_sec.is_nan() || steps_per_sec.abs() > max => {
+                Err(Error::VelocityLimited)
+            }
+            _ => Ok(()),
+        }
     }
 
-    /// Write a register by raw address (blocking).
+    /// Set the CRC polynomial for requests and responses.
     ///
-    /// Use this when you need to write a register by its raw address value.
-    pub fn write_raw(&mut self, reg_addr: u8, data: u32) -> Result<(), Error<E>> {
-        let request = WriteRequest::from_raw(self.slave_addr, reg_addr, data);
-
-        self.uart
-            .write_all(request.as_bytes())
-            .map_err(Error::Uart)?;
-        self.uart.flush().map_err(Error::Uart)?;
-
-        // Read back echo
-        let mut echo_buf = [0u8; 8];
-        self.read_exact(&mut echo_buf)?;
+    /// Genuine chips use [`crc::POLY`](crate::crc::POLY); only change this
+    /// for clones that compute their CRC differently.
+    pub fn set_crc_poly(&mut self, poly: u8) {
+        self.crc_poly = if poly == crate::crc::POLY {
+            None
+        } else {
+            Some(poly)
+        };
+        self.reader.set_crc_poly(poly);
+    }
 
-        Ok(())
+    /// Enable or disable echo verification.
+    ///
+    /// When enabled, the echo of every request is compared with the bytes
+    /// sent and [`Error::EchoMismatch`] is returned on any difference. This
+    /// catches bus contention and wiring faults before the response is
+    /// parsed. The reply to a read whose echo mismatches is drained, so the
+    /// next transaction starts on an empty bus. Has no effect when the echo
+    /// is disabled with [`set_echo_enabled`](Self::set_echo_enabled).
+    /// Disabled by default.
+    pub fn set_verify_echo(&mut self, enabled: bool) {
+        self.verify_echo = enabled;
     }
 
-    /// Helper to read exact number of bytes.
-    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error<E>> {
-        let mut total_read = 0;
-        while total_read < buf.len() {
-            let n = self.uart.read(&mut buf[total_read..]).map_err(Error::Uart)?;
-            if n == 0 {
-                return Err(Error::NoResponse);
-            }
-            total_read += n;
+    /// Check the echo of a request if verification is enabled.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn check_echo<E>(&self, sent: &[u8], echo: &[u8]) -> Result<(), Error<E>> {
+        if self.verify_echo && sent != echo {
+            return Err(Error::EchoMismatch);
         }
         Ok(())
     }
 
-    /// Helper to read a complete response.
-    fn read_response(&mut self) -> Result<ReadResponse, Error<E>> {
-        self.reader.reset();
-        let mut buf = [0u8; 8];
-        self.read_exact(&mut buf)?;
-
-        let (_, result) = self.reader.feed(&buf);
-        result.ok_or(Error::NoResponse)?
+    /// Start collecting bus statistics.
+    ///
+    /// Counters are kept if statistics are already enabled.
+    pub fn enable_stats(&mut self) {
+        self.stats.get_or_insert_with(BusStats::default);
     }
 
-    // ========================================================================
-    // Convenience methods (blocking)
-    // ========================================================================
+    /// Stop collecting bus statistics and discard the counters.
+    pub fn disable_stats(&mut self) {
+        self.stats = None;
+    }
 
-    /// Check if the driver is communicating properly.
+    /// Get the bus statistics.
     ///
-    /// Reads IFCNT and verifies we get a valid response.
-    pub fn is_connected(&mut self) -> bool {
-        self.read_register::<Ifcnt>().is_ok()
+    /// Returns all-zero counters when statistics are disabled.
+    pub fn stats(&self) -> BusStats {
+        self.stats.unwrap_or_default()
     }
 
-    /// Get the interface transmission counter.
+    /// Reset the bus statistics counters to zero.
+    pub fn reset_stats(&mut self) {
+        if let Some(stats) = self.stats.as_mut() {
+            *stats = BusStats::default();
+        }
+    }
+
+    /// Get the last value written to a register through this driver.
     ///
-    /// This counter increments on each successful UART write.
-    /// Useful for verifying communication.
-    pub fn ifcnt(&mut self) -> Result<u8, Error<E>> {
-        let reg = self.read_register::<Ifcnt>()?;
-        Ok(reg.count())
+    /// Covers the configuration registers, including the write-only ones
+    /// the chip cannot report back (IHOLD_IRUN, VACTUAL, SGTHRS, COOLCONF,
+    /// the thresholds). Returns `None` if the register has not been written
+    /// since creation or the last [`reset_protocol_state`](Self::reset_protocol_state),
+    /// and always for GSTAT and OTP_PROG.
+    pub fn last_written<R: WritableRegister>(&self) -> Option<R> {
+        self.shadow.get(R::ADDRESS).map(R::from)
     }
 
-    /// Get the global status flags.
-    pub fn gstat(&mut self) -> Result<Gstat, Error<E>> {
-        self.read_register()
+    /// Build a [`Config`] from the register values written through this
+    /// driver.
+    ///
+    /// Registers that were never written keep their reset value. After a
+    /// reset has been detected, e.g. with
+    /// [`init_check_reset`](Self::init_check_reset), pass the result to
+    /// [`apply_config`](Self::apply_config) to restore the previous state.
+    pub fn export_config(&self) -> Config {
+        let mut cfg = Config::new();
+        if let Some(reg) = self.last_written() {
+            cfg.gconf = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.ihold_irun = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.tpowerdown = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.tpwmthrs = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.tcoolthrs = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.sgthrs = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.coolconf = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.chopconf = reg;
+        }
+        if let Some(reg) = self.last_written() {
+            cfg.pwmconf = reg;
+        }
+        cfg
     }
 
-    /// Clear the global status flags (write to clear).
-    pub fn clear_gstat(&mut self) -> Result<(), Error<E>> {
-        // Writing 1s clears the flags
-        let gstat = Gstat::from(0x07);
-        self.write_register(&gstat)
+    /// Check if the motor is under UART velocity control.
+    ///
+    /// Returns `true` if the last VACTUAL written through this driver is
+    /// non-zero, in which case the STEP input is ignored. VACTUAL is
+    /// write-only, so a value written by another master or lost to a chip
+    /// reset is not seen.
+    pub fn is_uart_motion_active(&self) -> bool {
+        self.last_written::<Vactual>()
+            .is_some_and(|vactual| vactual.is_active())
     }
 
-    /// Get the input pin states.
-    pub fn ioin(&mut self) -> Result<Ioin, Error<E>> {
-        self.read_register()
+    /// Get IRUN from the last IHOLD_IRUN write.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn written_irun<E>(&self) -> Result<u8, Error<E>> {
+        self.last_written::<IholdIrun>()
+            .map(|reg| reg.irun())
+            .ok_or(Error::UnknownValue(Address::IholdIrun as u8))
     }
 
-    /// Get the driver status.
-    pub fn drv_status(&mut self) -> Result<DrvStatus, Error<E>> {
-        self.read_register()
+    /// Get the StallGuard trip point (SGTHRS * 2) from the last SGTHRS write.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn written_stall_trip_point<E>(&self) -> Result<i16, Error<E>> {
+        self.last_written::<Sgthrs>()
+            .map(|reg| reg.threshold() as i16 * 2)
+            .ok_or(Error::UnknownValue(Address::Sgthrs as u8))
     }
 
-    /// Get the current step time (inverse of velocity).
-    pub fn tstep(&mut self) -> Result<u32, Error<E>> {
-        let reg = self.read_register::<Tstep>()?;
-        Ok(reg.tstep())
+    /// Get the last written COOLCONF, or its reset value.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn written_coolconf(&self) -> Coolconf {
+        self.last_written().unwrap_or_default()
     }
 
-    /// Get the StallGuard result.
-    pub fn sg_result(&mut self) -> Result<u16, Error<E>> {
-        let reg = self.read_register::<SgResult>()?;
-        Ok(reg.result())
+    /// Get the CoolStep current floor for `irun_cs` in milliamps.
+    ///
+    /// SEIMIN is taken from the last written COOLCONF.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn coolstep_floor_ma(&self, irun_cs: u8, rsense: f32, vsense: bool) -> u16 {
+        let divisor = if self.written_coolconf().seimin() {
+            4
+        } else {
+            2
+        };
+        cs_to_current(irun_cs, rsense, vsense) / divisor
     }
 
-    /// Get the microstep counter position (0-1023).
-    pub fn mscnt(&mut self) -> Result<u16, Error<E>> {
-        let reg = self.read_register::<Mscnt>()?;
-        Ok(reg.count())
+    /// Merge validated CoolStep dynamics into the last written COOLCONF.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn coolstep_dynamics<E>(&self, seup: u8, sedn: u8, seimin: bool) -> Result<Coolconf, Error<E>> {
+        let mut coolconf = self.written_coolconf();
+        coolconf
+            .try_set_seup(seup)
+            .and_then(|c| c.try_set_sedn(sedn))
+            .map_err(|_| Error::InvalidArgument)?
+            .set_seimin(seimin);
+        Ok(coolconf)
     }
 
-    /// Set the motor currents.
+    /// Get the last written IHOLD_IRUN with IHOLD set for a standstill mode.
     ///
-    /// # Arguments
+    /// Every mode but [`StandstillMode::Normal`] only takes effect with
+    /// IHOLD=0, so `hold_current` is ignored for those.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn standstill_current<E>(
+        &self,
+        hold_current: u8,
+        mode: StandstillMode,
+    ) -> Result<IholdIrun, Error<E>> {
+        let mut reg = self
+            .last_written::<IholdIrun>()
+            .ok_or(Error::UnknownValue(Address::IholdIrun as u8))?;
+        let ihold = match mode {
+            StandstillMode::Normal => hold_current,
+            _ => 0,
+        };
+        reg.try_set_ihold(ihold)
+            .map_err(|_| Error::InvalidArgument)?;
+        Ok(reg)
+    }
+
+    /// Put the protocol layer back into a known-good state.
     ///
-    /// * `run_current` - Run current (0-31)
-    /// * `hold_current` - Hold current (0-31)
-    /// * `hold_delay` - Delay before reducing to hold current (0-15)
-    pub fn set_current(
-        &mut self,
-        run_current: u8,
-        hold_current: u8,
-        hold_delay: u8,
-    ) -> Result<(), Error<E>> {
-        let mut reg = IholdIrun::new();
-        reg.set_irun(run_current)
-            .set_ihold(hold_current)
-            .set_iholddelay(hold_delay);
-        self.write_register(&reg)
+    /// Discards any partially received response, zeroes the bus
+    /// statistics, invalidates the read cache and forgets the shadowed
+    /// register values. Useful in error handlers before retrying after a
+    /// [`Error::CrcMismatch`] or similar.
+    pub fn reset_protocol_state(&mut self) {
+        self.reader.reset();
+        self.reset_stats();
+        self.invalidate_cache();
+        self.shadow.clear();
     }
 
-    /// Set the microstep resolution.
-    pub fn set_microsteps(&mut self, resolution: MicrostepResolution) -> Result<(), Error<E>> {
-        let mut chopconf = self.read_register::<Chopconf>()?;
-        chopconf.set_microstep_resolution(resolution);
-        self.write_register(&chopconf)
+    /// Serve IOIN from a cache for `reads` calls after each bus read.
+    ///
+    /// Cached IOIN values may be stale: pin changes are only seen when the
+    /// cache expires. Pass 0 to disable IOIN caching.
+    pub fn set_ioin_cache(&mut self, reads: u32) {
+        self.cache.ioin_ttl = reads;
+        self.cache.ioin = None;
+        self.cache.ioin_left = 0;
     }
 
-    /// Enable or disable the driver.
+    /// Drop all cached register values.
     ///
-    /// When TOFF=0, the driver is disabled.
-    pub fn set_enabled(&mut self, enabled: bool) -> Result<(), Error<E>> {
-        let mut chopconf = self.read_register::<Chopconf>()?;
-        if enabled {
-            // Use default TOFF=3 if currently disabled
-            if chopconf.toff() == 0 {
-                chopconf.set_toff(3);
-            }
-        } else {
-            chopconf.set_toff(0);
-        }
-        self.write_register(&chopconf)
+    /// OTP_READ caching stays disabled until `cache_otp` is called again.
+    pub fn invalidate_cache(&mut self) {
+        self.cache.invalidate();
     }
 
-    /// Set velocity for internal motion controller (VACTUAL).
-    ///
-    /// # Arguments
-    ///
-    /// * `velocity` - Velocity value (signed, 23-bit range)
-    ///   - Positive: Forward motion
-    ///   - Negative: Reverse motion
-    ///   - 0: Stop
-    pub fn set_velocity(&mut self, velocity: i32) -> Result<(), Error<E>> {
-        let mut reg = Vactual::new();
-        reg.set_velocity(velocity);
-        self.write_register(&reg)
+    /// Update the bus statistics with the outcome of a transaction.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn record<T, E>(&mut self, write: bool, result: &Result<T, Error<E>>) {
+        let Some(stats) = self.stats.as_mut() else {
+            return;
+        };
+        let counter = match result {
+            Ok(_) if write => &mut stats.writes,
+            Ok(_) => &mut stats.reads,
+            Err(Error::CrcMismatch) => &mut stats.crc_errors,
+            Err(Error::NoResponse) => &mut stats.no_response,
+            Err(_) => return,
+        };
+        *counter = counter.wrapping_add(1);
     }
 
-    /// Stop the motor (set VACTUAL to 0).
-    pub fn stop(&mut self) -> Result<(), Error<E>> {
-        self.set_velocity(0)
+    /// Create a read request for a register.
+    fn read_request<R: ReadableRegister>(&self) -> ReadRequest {
+        ReadRequest::new(self.slave_addr, R::ADDRESS)
     }
 
-    /// Set the StallGuard threshold.
+    /// Create a write request for a register.
+    fn write_request<R: WritableRegister>(&self, reg: &R) -> WriteRequest {
+        WriteRequest::new(self.slave_addr, R::ADDRESS, (*reg).into())
+    }
+
+    /// Create a write request for a register on another slave, with the
+    /// configured CRC polynomial applied.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn slave_write_request<R: WritableRegister>(&self, slave: u8, reg: &R) -> WriteRequest {
+        let request = WriteRequest::new(slave, R::ADDRESS, (*reg).into());
+        self.crc_poly
+            .map_or(request, |poly| request.with_crc_poly(poly))
+    }
+
+    /// Check the slave addresses of a write sequence before sending any.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn check_slaves<E>(slaves: &[u8]) -> Result<(), Error<E>> {
+        match slaves.iter().find(|&&slave| slave > 3) {
+            Some(&slave) => Err(Error::InvalidSlaveAddress(slave)),
+            None => Ok(()),
+        }
+    }
+
+    /// Update statistics and state after a write sequence.
     ///
-    /// Higher values make stall detection more sensitive.
-    pub fn set_stall_threshold(&mut self, threshold: u8) -> Result<(), Error<E>> {
-        let mut reg = Sgthrs::new();
-        reg.set_threshold(threshold);
-        self.write_register(&reg)
+    /// Each of the `confirmed` datagrams that went out (and whose echo
+    /// matched, if echoes are read) counts as one write; a failure is
+    /// counted once. The last slave becomes the selected one, and on
+    /// success the value is shadowed for it.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn finish_write_sequence<R: WritableRegister, E>(
+        &mut self,
+        reg: &R,
+        slaves: &[u8],
+        confirmed: usize,
+        result: Result<(), Error<E>>,
+    ) -> Result<(), Error<E>> {
+        for _ in 0..confirmed {
+            self.record::<(), E>(true, &Ok(()));
+        }
+        if result.is_err() {
+            self.record(true, &result);
+        }
+        if let Some(&last) = slaves.last() {
+            self.select_slave::<E>(last)?;
+            if result.is_ok() {
+                self.shadow.record(R::ADDRESS as u8, (*reg).into());
+            }
+        }
+        result
     }
 
-    /// Enable StealthChop mode.
-    pub fn enable_stealthchop(&mut self) -> Result<(), Error<E>> {
-        let mut gconf = self.read_register::<Gconf>()?;
-        gconf.set_en_spreadcycle(false);
-        self.write_register(&gconf)
+    /// Create a write request from a raw address, rejecting known read-only registers.
+    ///
+    /// Unknown addresses are permitted.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn write_request_raw<E>(&self, reg_addr: u8, data: u32) -> Result<WriteRequest, Error<E>> {
+        let reg_addr = reg_addr & ADDRESS_MASK;
+        if let Some(addr) = Address::from_u8(reg_addr) {
+            if !addr.is_writable() {
+                return Err(Error::NotWritable(reg_addr));
+            }
+        }
+        Ok(WriteRequest::from_raw(self.slave_addr, reg_addr, data))
     }
+}
 
-    /// Enable SpreadCycle mode.
-    pub fn enable_spreadcycle(&mut self) -> Result<(), Error<E>> {
-        let mut gconf = self.read_register::<Gconf>()?;
-        gconf.set_en_spreadcycle(true);
-        self.write_register(&gconf)
+/// Compare the active chopper mode against the expected one.
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn check_chopper_mode<E>(expected: ChopperMode, actual: ChopperMode) -> Result<(), Error<E>> {
+    if actual != expected {
+        return Err(Error::UnexpectedChopperMode { expected, actual });
     }
+    Ok(())
+}
 
-    /// Check if motor is in standstill.
-    pub fn is_standstill(&mut self) -> Result<bool, Error<E>> {
-        let status = self.drv_status()?;
-        Ok(status.stst())
+/// Compute IHOLD from IRUN and a hold fraction.
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn hold_current_from_fraction<E>(run_current: u8, hold_fraction: f32) -> Result<u8, Error<E>> {
+    if !(0.0..=1.0).contains(&hold_fraction) {
+        return Err(Error::InvalidArgument);
     }
+    let run_current = run_current.min(31);
+    Ok(round_f32(run_current as f32 * hold_fraction) as u8)
+}
 
-    /// Check if overtemperature warning is active.
-    pub fn is_overtemperature_warning(&mut self) -> Result<bool, Error<E>> {
-        let status = self.drv_status()?;
-        Ok(status.otpw())
+/// Convert a StealthChop/StallGuard velocity band to (TPWMTHRS, TCOOLTHRS).
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn velocity_bands<E>(
+    stealthchop_max_sps: f32,
+    stallguard_min_sps: f32,
+    microsteps: u16,
+    fclk: u32,
+) -> Result<(u32, u32), Error<E>> {
+    let tpwmthrs = velocity_to_tpwmthrs(stealthchop_max_sps, microsteps, fclk);
+    let tcoolthrs = velocity_to_tpwmthrs(stallguard_min_sps, microsteps, fclk);
+    if tpwmthrs < tcoolthrs {
+        Ok((tpwmthrs, tcoolthrs))
+    } else {
+        Err(Error::InvalidThresholdOrder)
     }
+}
 
-    /// Check if overtemperature shutdown is active.
-    pub fn is_overtemperature_shutdown(&mut self) -> Result<bool, Error<E>> {
-        let status = self.drv_status()?;
-        Ok(status.ot())
+/// Compare a written register with its read-back value.
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn verify_readback<R: Register, E>(written: R, actual: R) -> Result<(), Error<E>> {
+    let (expected, actual): (u32, u32) = (written.into(), actual.into());
+    if (expected ^ actual) & R::FIELD_MASK == 0 {
+        Ok(())
+    } else {
+        Err(Error::VerifyMismatch { expected, actual })
     }
+}
 
-    // ========================================================================
-    // CoolStep and StallGuard methods (blocking)
-    // ========================================================================
+// ============================================================================
+// Blocking API
+// ============================================================================
 
-    /// Enable CoolStep adaptive current control.
+#[cfg(feature = "blocking")]
+impl<U, E, D> Tmc2209<U, D>
+where
+    U: embedded_io::Read<Error = E> + embedded_io::Write<Error = E>,
+    D: FnMut(),
+{
+    /// Read a register (blocking).
     ///
-    /// CoolStep automatically reduces motor current when load is low,
-    /// saving power and reducing heat.
+    /// Sends a read request and waits for the response.
     ///
-    /// # Arguments
+    /// # Type Parameters
     ///
-    /// * `semin` - Minimum StallGuard value for current increase (1-15, 0 disables)
-    /// * `semax` - Hysteresis for current decrease (0-15)
+    /// * `R` - Register type to read (must implement `ReadableRegister`)
     ///
-    /// # Example
+    /// # Returns
     ///
-    /// ```ignore
-    /// // Enable CoolStep with moderate sensitivity
-    /// driver.enable_coolstep(4, 2)?;
-    /// ```
-    pub fn enable_coolstep(&mut self, semin: u8, semax: u8) -> Result<(), Error<E>> {
-        let mut coolconf = Coolconf::new();
-        coolconf
-            .set_semin(semin.min(15))
-            .set_semax(semax.min(15))
-            .set_seup(0)  // +1 current step
-            .set_sedn(0); // -32 current step
-        self.write_register(&coolconf)
+    /// The register value, or an error if communication fails. Failed
+    /// reads are retried as configured with
+    /// [`set_read_retries`](Self::set_read_retries).
+    pub fn read_register<R: ReadableRegister>(&mut self) -> Result<R, Error<E>> {
+        if let Some(value) = self.cache.get(R::ADDRESS) {
+            return Ok(R::from(value));
+        }
+        let mut attempt = 0;
+        loop {
+            match self.read_register_once::<R>() {
+                Err(e) if attempt < self.read_retries && self.is_retriable(&e) => {
+                    attempt += 1;
+                    self.begin_retry();
+                }
+                result => return result,
+            }
+        }
     }
 
-    /// Disable CoolStep.
-    pub fn disable_coolstep(&mut self) -> Result<(), Error<E>> {
-        let coolconf = Coolconf::new(); // semin=0 disables CoolStep
-        self.write_register(&coolconf)
+    /// Perform a single register read and check the response address.
+    fn read_register_once<R: ReadableRegister>(&mut self) -> Result<R, Error<E>> {
+        let request = self.read_request::<R>();
+        let response = self.read_datagram(&request)?;
+
+        // Verify the register address matches
+        let expected_addr = R::ADDRESS as u8;
+        if response.reg_addr() != expected_addr {
+            return Err(Error::AddressMismatch {
+                expected: expected_addr,
+                actual: response.reg_addr(),
+            });
+        }
+
+        self.cache.store(R::ADDRESS, response.data());
+        Ok(R::from(response.data()))
     }
 
-    /// Set the CoolStep velocity threshold (TCOOLTHRS).
+    /// Read a register without verifying the response address (blocking).
     ///
-    /// CoolStep and StallGuard are only active when TSTEP < TCOOLTHRS.
-    /// Below this velocity, CoolStep and stall detection are disabled.
+    /// Decodes whatever data arrives as `R`, even if the response carries a
+    /// different register address. Sync, master address and CRC are still checked.
     ///
-    /// # Arguments
+    /// This is intended for advanced/debug use, such as bus sniffing or chips
+    /// with nonstandard echo behavior. Prefer [`read_register`](Self::read_register).
+    pub fn read_register_lenient<R: ReadableRegister>(&mut self) -> Result<R, Error<E>> {
+        let request = self.read_request::<R>();
+        let response = self.read_datagram(&request)?;
+        Ok(R::from(response.data()))
+    }
+
+    /// Read a register and count the UART reads it took (blocking).
     ///
-    /// * `threshold` - TSTEP threshold value (0 = disabled, 0xFFFFF = always active)
-    pub fn set_coolstep_threshold(&mut self, threshold: u32) -> Result<(), Error<E>> {
-        let mut tcoolthrs = Tcoolthrs::new();
-        tcoolthrs.set_threshold(threshold);
-        self.write_register(&tcoolthrs)
+    /// Returns the register together with the number of calls made to the
+    /// UART's `read` to receive the echo and the response, including any
+    /// retries. Each call returns at least one byte, so with echo a single
+    /// attempt takes 2 to 12 calls; every retry (see
+    /// [`set_read_retries`](Self::set_read_retries)) can add as many again.
+    /// High counts point at bytes arriving one at a time, e.g. from a slow
+    /// bus or single-byte DMA. A read served from the cache counts 0.
+    pub fn read_register_counted<R: ReadableRegister>(&mut self) -> Result<(R, usize), Error<E>> {
+        let start = self.uart_reads;
+        let reg = self.read_register::<R>()?;
+        Ok((reg, self.uart_reads.wrapping_sub(start)))
     }
 
-    /// Set the StealthChop velocity threshold (TPWMTHRS).
+    /// Write a register (blocking).
     ///
-    /// Above this velocity, the driver switches from StealthChop to SpreadCycle.
+    /// Sends a write request to update a register value.
     ///
     /// # Arguments
     ///
-    /// * `threshold` - TSTEP threshold value (0 = only SpreadCycle, 0xFFFFF = only StealthChop)
-    pub fn set_stealthchop_threshold(&mut self, threshold: u32) -> Result<(), Error<E>> {
-        let mut tpwmthrs = Tpwmthrs::new();
-        tpwmthrs.set_threshold(threshold);
-        self.write_register(&tpwmthrs)
+    /// * `reg` - Register value to write
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or an error if communication fails.
+    pub fn write_register<R: WritableRegister>(&mut self, reg: &R) -> Result<(), Error<E>> {
+        let request = self.write_request(reg);
+        self.write_datagram(&request)
     }
 
-    // ========================================================================
-    // Sensorless homing methods (blocking)
-    // ========================================================================
-
-    /// Configure stall detection for sensorless homing.
+    /// Write a register and verify it by reading it back (blocking).
     ///
-    /// This sets up the StallGuard feature to detect when the motor
-    /// hits an endstop or obstacle.
+    /// Only the bits in [`Register::FIELD_MASK`] are compared, so reserved
+    /// bits that read back differently are not reported. Write-clear
+    /// registers such as GSTAT do not read back what was written.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `threshold` - StallGuard threshold (0-255, higher = more sensitive)
+    /// Returns `Error::VerifyMismatch` if the read-back value differs.
+    pub fn write_register_readback<R>(&mut self, reg: &R) -> Result<(), Error<E>>
+    where
+        R: ReadableRegister + WritableRegister,
+    {
+        self.write_register(reg)?;
+        let actual = self.read_register::<R>()?;
+        verify_readback(*reg, actual)
+    }
+
+    /// Read a register by raw address (blocking).
     ///
-    /// # Note
+    /// Use this when you need to read a register by its raw address value.
+    pub fn read_raw(&mut self, reg_addr: u8) -> Result<u32, Error<E>> {
+        let request = ReadRequest::from_raw_addr(self.slave_addr, reg_addr);
+        let response = self.read_datagram(&request)?;
+        Ok(response.data())
+    }
+
+    /// Write a register by raw address (blocking).
     ///
-    /// For sensorless homing to work properly:
-    /// - Use SpreadCycle mode (not StealthChop)
-    /// - Set appropriate TCOOLTHRS (stall detection only works above this velocity)
-    /// - Move at a consistent, moderate speed
+    /// Use this when you need to write a register by its raw address value.
     ///
-    /// # Example
+    /// # Errors
     ///
-    /// ```ignore
-    /// // Configure for sensorless homing
-    /// driver.configure_stall_detection(50)?;
-    /// driver.enable_spreadcycle()?;
-    /// driver.set_coolstep_threshold(0)?; // Enable at all velocities
+    /// Returns `Error::NotWritable` for known read-only registers.
+    /// Unknown addresses are sent as-is.
+    pub fn write_raw(&mut self, reg_addr: u8, data: u32) -> Result<(), Error<E>> {
+        let request = self.write_request_raw(reg_addr, data)?;
+        self.write_datagram(&request)
+    }
+
+    /// Send a raw datagram and read the raw reply (blocking).
     ///
-    /// // Move towards endstop
-    /// driver.set_velocity(-1000)?;
+    /// `request` is transmitted exactly as given. Nothing is checked: no
+    /// CRC is added, the echo is discarded without comparing it, and the
+    /// reply is stored without validating sync, address or CRC. Bus
+    /// statistics, the read cache and the shadow registers are not
+    /// updated. Use [`ReadResponse::from_bytes`] to validate the reply if
+    /// needed.
     ///
-    /// // Poll for stall
-    /// loop {
-    ///     if driver.is_stalled()? {
-    ///         driver.stop()?;
-    ///         break;
-    ///     }
-    /// }
-    /// ```
-    pub fn configure_stall_detection(&mut self, threshold: u8) -> Result<(), Error<E>> {
-        // Set StallGuard threshold
-        self.set_stall_threshold(threshold)?;
-
-        // Enable DIAG output for stall indication
-        let mut gconf = self.read_register::<Gconf>()?;
-        gconf.set_diag0_stall(true);
-        self.write_register(&gconf)?;
-
+    /// With echo handling enabled, the echo of the request is read first.
+    /// A 4-byte read request is then answered with 8 bytes stored in
+    /// `response`. An 8-byte write request has no reply, so `response` is
+    /// left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidArgument` if `request` is not 4 or 8 bytes
+    /// long and `Error::NoResponse` if the echo or reply is incomplete.
+    pub fn transact_raw(&mut self, request: &[u8], response: &mut [u8; 8]) -> Result<(), Error<E>> {
+        if request.len() != ReadRequest::LEN && request.len() != WriteRequest::LEN {
+            return Err(Error::InvalidArgument);
+        }
+        self.transmit(request)?;
+        if self.echo {
+            let mut echo_buf = [0u8; WriteRequest::LEN];
+            self.read_exact(&mut echo_buf[..request.len()])?;
+        }
+        if request.len() == ReadRequest::LEN {
+            self.read_exact(response)?;
+        }
         Ok(())
     }
 
-    /// Check if the motor is currently stalled.
-    ///
-    /// Returns true if the StallGuard result is below the threshold.
-    /// Only valid when motor is moving and TSTEP < TCOOLTHRS.
-    pub fn is_stalled(&mut self) -> Result<bool, Error<E>> {
-        let sg_result = self.sg_result()?;
-        // SG_RESULT of 0 indicates stall
-        Ok(sg_result == 0)
+    /// Send a read request and return the validated response.
+    fn read_datagram(&mut self, request: &ReadRequest) -> Result<ReadResponse, Error<E>> {
+        let result = self.send_read(request);
+        self.record(false, &result);
+        result
     }
 
-    /// Get the current load indicator from StallGuard.
-    ///
-    /// Returns a value from 0 (high load/stall) to 510 (no load).
-    /// Useful for load monitoring and stall detection tuning.
-    pub fn load_indicator(&mut self) -> Result<u16, Error<E>> {
-        self.sg_result()
+    /// Send a write request and consume its echo.
+    fn write_datagram(&mut self, request: &WriteRequest) -> Result<(), Error<E>> {
+        let result = self.send_write(request);
+        self.record(true, &result);
+        if result.is_ok() {
+            self.shadow.record(request.reg_addr(), request.data());
+        }
+        result
     }
 
-    // ========================================================================
-    // PWM and StealthChop configuration (blocking)
-    // ========================================================================
+    /// Perform the UART exchange for a read request.
+    fn send_read(&mut self, request: &ReadRequest) -> Result<ReadResponse, Error<E>> {
+        let request = &self
+            .crc_poly
+            .map_or(*request, |poly| request.with_crc_poly(poly));
+        // Send the read request
+        self.transmit(request.as_bytes())?;
 
-    /// Configure StealthChop PWM settings.
+        // Read the response
+        // TMC2209 echoes back the request, then sends the response
+        // We need to skip the echo (4 bytes) and read the response (8 bytes)
+        if self.echo {
+            let mut echo_buf = [0u8; ReadRequest::LEN];
+            self.read_exact(&mut echo_buf)?;
+            if let Err(e) = self.check_echo(request.as_bytes(), &echo_buf) {
+                // Drain the reply so it is not taken for the next response
+                let mut reply = [0u8; ReadResponse::LEN];
+                let _ = self.read_exact(&mut reply);
+                return Err(e);
+            }
+        }
+
+        self.read_response()
+    }
+
+    /// Write a register on several slaves in rapid succession.
     ///
-    /// # Arguments
+    /// All requests are transmitted back to back before any echo is read.
+    /// Used by [`Tmc2209Bus::write_all_slaves`](crate::Tmc2209Bus::write_all_slaves).
+    pub(crate) fn write_register_sequence<R: WritableRegister>(
+        &mut self,
+        reg: &R,
+        slaves: &[u8],
+    ) -> Result<(), Error<E>> {
+        Self::check_slaves(slaves)?;
+        let mut confirmed = 0;
+        let result = self.send_write_sequence(reg, slaves, &mut confirmed);
+        self.finish_write_sequence(reg, slaves, confirmed, result)
+    }
+
+    /// Perform the UART exchange for a write sequence.
     ///
-    /// * `pwm_ofs` - PWM amplitude offset (0-255)
-    /// * `pwm_grad` - PWM amplitude gradient (0-255)
-    /// * `autoscale` - Enable automatic current scaling
-    /// * `autograd` - Enable automatic gradient adaptation
-    pub fn configure_stealthchop(
+    /// Counts the datagrams that went out and, if echoes are read, were
+    /// echoed intact in `confirmed`.
+    fn send_write_sequence<R: WritableRegister>(
         &mut self,
-        pwm_ofs: u8,
-        pwm_grad: u8,
-        autoscale: bool,
-        autograd: bool,
+        reg: &R,
+        slaves: &[u8],
+        confirmed: &mut usize,
     ) -> Result<(), Error<E>> {
-        let mut pwmconf = self.read_register::<Pwmconf>()?;
-        pwmconf
-            .set_pwm_ofs(pwm_ofs)
-            .set_pwm_grad(pwm_grad)
-            .set_pwm_autoscale(autoscale)
-            .set_pwm_autograd(autograd);
-        self.write_register(&pwmconf)
+        for &slave in slaves {
+            let request = self.slave_write_request(slave, reg);
+            self.transmit(request.as_bytes())?;
+            if !self.echo {
+                *confirmed += 1;
+            }
+        }
+        if self.echo {
+            for (i, &slave) in slaves.iter().enumerate() {
+                let request = self.slave_write_request(slave, reg);
+                let mut echo_buf = [0u8; WriteRequest::LEN];
+                self.read_exact(&mut echo_buf)?;
+                if let Err(e) = self.check_echo(request.as_bytes(), &echo_buf) {
+                    // Drain the echoes of the requests still in flight
+                    for _ in i + 1..slaves.len() {
+                        if self.read_exact(&mut echo_buf).is_err() {
+                            break;
+                        }
+                    }
+                    return Err(e);
+                }
+                *confirmed += 1;
+            }
+        }
+        Ok(())
     }
 
-    /// Set VSENSE for current sense resistor scaling.
+    /// Perform the UART exchange for a write request.
+    fn send_write(&mut self, request: &WriteRequest) -> Result<(), Error<E>> {
+        let request = &self
+            .crc_poly
+            .map_or(*request, |poly| request.with_crc_poly(poly));
+        // Send the write request
+        self.transmit(request.as_bytes())?;
+
+        // Read back the echo (8 bytes) - TMC2209 echoes write requests
+        if self.echo {
+            let mut echo_buf = [0u8; WriteRequest::LEN];
+            self.read_exact(&mut echo_buf)?;
+            self.check_echo(request.as_bytes(), &echo_buf)?;
+        }
+
+        Ok(())
+    }
+
+    /// Helper to read exact number of bytes.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error<E>> {
+        Self::read_exact_from(&mut self.uart, buf, &mut self.uart_reads)
+    }
+
+    /// Read exactly `buf.len()` bytes from `uart`, counting `read` calls.
     ///
-    /// # Arguments
+    /// Takes the UART alone so the buffer may borrow another field.
+    fn read_exact_from(uart: &mut U, buf: &mut [u8], calls: &mut usize) -> Result<(), Error<E>> {
+        let mut total_read = 0;
+        while total_read < buf.len() {
+            *calls = calls.wrapping_add(1);
+            let n = uart.read(&mut buf[total_read..]).map_err(Error::Uart)?;
+            if n == 0 {
+                return Err(Error::NoResponse);
+            }
+            total_read += n;
+        }
+        Ok(())
+    }
+
+    /// Transmit a request, byte by byte if an inter-byte delay is set.
+    fn transmit(&mut self, bytes: &[u8]) -> Result<(), Error<E>> {
+        match self.inter_byte_delay.as_mut() {
+            None => self.uart.write_all(bytes).map_err(Error::Uart)?,
+            Some(delay) => {
+                for (i, byte) in bytes.iter().enumerate() {
+                    if i > 0 {
+                        delay();
+                    }
+                    self.uart
+                        .write_all(core::slice::from_ref(byte))
+                        .map_err(Error::Uart)?;
+                }
+            }
+        }
+        self.uart.flush().map_err(Error::Uart)
+    }
+
+    /// Helper to read a complete response.
+    fn read_response(&mut self) -> Result<ReadResponse, Error<E>> {
+        // Read straight into the reader's buffer to avoid a second copy
+        Self::read_exact_from(
+            &mut self.uart,
+            self.reader.frame_buffer(),
+            &mut self.uart_reads,
+        )?;
+        self.reader.parse_frame_buffer().ok_or(Error::NoResponse)?
+    }
+
+    // ========================================================================
+    // Convenience methods (blocking)
+    // ========================================================================
+
+    /// Check if the driver is communicating properly.
     ///
-    /// * `high_sensitivity` - true for high sensitivity (low current range),
-    ///                        false for low sensitivity (high current range)
-    pub fn set_vsense(&mut self, high_sensitivity: bool) -> Result<(), Error<E>> {
+    /// Reads IFCNT and verifies we get a valid response.
+    pub fn is_connected(&mut self) -> bool {
+        self.read_register::<Ifcnt>().is_ok()
+    }
+
+    /// Get the interface transmission counter.
+    ///
+    /// This counter increments on each successful UART write.
+    /// Useful for verifying communication.
+    pub fn ifcnt(&mut self) -> Result<u8, Error<E>> {
+        let reg = self.read_register::<Ifcnt>()?;
+        Ok(reg.count())
+    }
+
+    /// Get the number of writes counted since an earlier IFCNT reading.
+    ///
+    /// Reads IFCNT and returns `current - previous`, wrapping at 256.
+    pub fn ifcnt_delta_since(&mut self, previous: u8) -> Result<u8, Error<E>> {
+        Ok(self.ifcnt()?.wrapping_sub(previous))
+    }
+
+    /// Get the global status flags.
+    pub fn gstat(&mut self) -> Result<Gstat, Error<E>> {
+        self.read_register()
+    }
+
+    /// Clear the global status flags (write to clear).
+    pub fn clear_gstat(&mut self) -> Result<(), Error<E>> {
+        // Writing 1s clears the flags
+        let gstat = Gstat::from(0x07);
+        self.write_register(&gstat)
+    }
+
+    /// Find the slave address the chip answers at.
+    ///
+    /// Reads IFCNT at addresses 0 to 3 and switches the driver to the
+    /// first one that returns a valid response. Use this during bring-up
+    /// when MS1/MS2 may be strapped differently than expected. Since the
+    /// scan stops at the first answer, only a single chip should be on the
+    /// bus. The probes are addressed directly, so shadowed register values
+    /// and cached reads are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoResponse` if no address answers and a UART error
+    /// as soon as one occurs. The previous address is kept in both cases.
+    pub fn detect_slave_address(&mut self) -> Result<u8, Error<E>> {
+        for addr in 0..=3 {
+            let request = ReadRequest::new(addr, Address::Ifcnt);
+            match self.read_datagram(&request) {
+                Ok(response) if response.reg_addr() == Address::Ifcnt as u8 => {
+                    self.slave_addr = addr;
+                    return Ok(addr);
+                }
+                Err(Error::Uart(e)) => return Err(Error::Uart(e)),
+                _ => {}
+            }
+        }
+        Err(Error::NoResponse)
+    }
+
+    /// Check for and clear the GSTAT reset flag.
+    ///
+    /// Returns `true` if the chip was reset since the flag was last
+    /// cleared (fresh power-up or a supply glitch), in which case the
+    /// configuration must be written again. Only the reset flag is
+    /// cleared; drv_err is left for fault handling.
+    pub fn init_check_reset(&mut self) -> Result<bool, Error<E>> {
+        let reset = self.gstat()?.reset();
+        if reset {
+            let mut clear = Gstat::default();
+            clear.clear_reset();
+            self.write_register(&clear)?;
+        }
+        Ok(reset)
+    }
+
+    /// Check if the short protections are disabled.
+    ///
+    /// Returns `(diss2g, diss2vs)` from CHOPCONF. Either bit being set
+    /// leaves the power stage unprotected against shorts to GND or VS.
+    pub fn protections_disabled(&mut self) -> Result<(bool, bool), Error<E>> {
+        let chopconf = self.read_register::<Chopconf>()?;
+        Ok((chopconf.diss2g(), chopconf.diss2vs()))
+    }
+
+    /// Re-enable the short to GND and short to VS protections.
+    ///
+    /// Clears CHOPCONF.diss2g and diss2vs. CHOPCONF is only written if
+    /// one of them was set.
+    pub fn safe_protections(&mut self) -> Result<(), Error<E>> {
         let mut chopconf = self.read_register::<Chopconf>()?;
-        chopconf.set_vsense(high_sensitivity);
-        self.write_register(&chopconf)
+        if chopconf.diss2g() || chopconf.diss2vs() {
+            chopconf.set_diss2g(false).set_diss2vs(false);
+            self.write_register(&chopconf)?;
+        }
+        Ok(())
     }
 
-    /// Configure chopper settings for optimal performance.
+    /// Put the driver under UART control.
     ///
-    /// # Arguments
+    /// Sets GCONF.pdn_disable (so PDN_UART works as a UART pin and not as
+    /// a standstill current input) and GCONF.mstep_reg_select (microstep
+    /// resolution from MRES instead of the MS1/MS2 pins), checks that the
+    /// write was counted by IFCNT, then clears GSTAT.
     ///
-    /// * `toff` - Off-time (1-15, 0 disables driver)
-    /// * `hstrt` - Hysteresis start (0-7)
-    /// * `hend` - Hysteresis end (0-15)
-    /// * `tbl` - Comparator blank time (0-3)
-    pub fn configure_chopper(
-        &mut self,
-        toff: u8,
-        hstrt: u8,
-        hend: u8,
-        tbl: u8,
-    ) -> Result<(), Error<E>> {
+    /// Safe to call repeatedly.
+    pub fn init_uart_control(&mut self) -> Result<(), Error<E>> {
+        let before = self.ifcnt()?;
+        let mut gconf = self.read_register::<Gconf>()?;
+        gconf.set_pdn_disable(true).set_mstep_reg_select(true);
+        self.write_register(&gconf)?;
+        if self.ifcnt()? == before {
+            return Err(Error::WriteNotAcknowledged(Address::Gconf as u8));
+        }
+        self.clear_gstat()
+    }
+
+    /// Set the UART reply delay (SLAVECONF.SENDDELAY).
+    ///
+    /// `senddelay` is clamped to 0-15. Use at least 2 when several chips
+    /// share the bus, so the master can release the line before the reply.
+    pub fn set_reply_delay(&mut self, senddelay: u8) -> Result<(), Error<E>> {
+        let mut reg = Slaveconf::default();
+        reg.set_senddelay(senddelay.min(15));
+        self.write_register(&reg)
+    }
+
+    /// Set the UART reply delay to at least `bit_times` bit times.
+    ///
+    /// See [`Slaveconf::senddelay_for_bit_times`].
+    pub fn set_reply_delay_bit_times(&mut self, bit_times: u16) -> Result<(), Error<E>> {
+        self.set_reply_delay(Slaveconf::senddelay_for_bit_times(bit_times))
+    }
+
+    /// Apply a conservative profile for quiet operation.
+    ///
+    /// Writes, in order:
+    /// 1. GCONF: pdn_disable and mstep_reg_select (UART control), StealthChop
+    /// 2. IHOLD_IRUN: `run_ma` RMS, half of it as hold current
+    /// 3. TPOWERDOWN: 20 (at least 2 is needed for StealthChop tuning)
+    /// 4. PWMCONF: default offset and gradient, autoscale and autograd on
+    /// 5. CHOPCONF: 16 microsteps with interpolation, VSENSE for `run_ma`,
+    ///    TOFF from [`set_default_toff`](Self::set_default_toff), HSTRT=4,
+    ///    HEND=1, TBL=2. This enables the driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] without writing anything if
+    /// `run_ma` cannot be reached with `rsense`.
+    pub fn quiet_setup(&mut self, run_ma: u16, rsense: f32) -> Result<(), Error<E>> {
+        let (cs, vsense) =
+            calculate_current_settings(run_ma, rsense).ok_or(Error::InvalidArgument)?;
+
+        let mut gconf = self.read_register::<Gconf>()?;
+        gconf
+            .set_pdn_disable(true)
+            .set_mstep_reg_select(true)
+            .set_en_spreadcycle(false);
+        self.write_register(&gconf)?;
+
+        self.set_current(cs, cs / 2, 8)?;
+        self.write_register(&Tpowerdown::new())?;
+        self.configure_stealthchop(36, 14, true, true)?;
+
         let mut chopconf = self.read_register::<Chopconf>()?;
         chopconf
-            .set_toff(toff.min(15))
-            .set_hstrt(hstrt.min(7))
-            .set_hend(hend.min(15))
-            .set_tbl(tbl.min(3));
+            .set_toff(self.default_toff)
+            .set_hstrt(4)
+            .set_hend(1)
+            .set_tbl(2)
+            .set_vsense(vsense)
+            .set_microstep_resolution(MicrostepResolution::M16)
+            .set_intpol(true);
         self.write_register(&chopconf)
     }
 
-    /// Enable interpolation to 256 microsteps.
-    ///
-    /// When enabled, the driver interpolates between microsteps
-    /// for smoother motion, even at lower microstep settings.
-    pub fn set_interpolation(&mut self, enabled: bool) -> Result<(), Error<E>> {
-        let mut chopconf = self.read_register::<Chopconf>()?;
-        chopconf.set_intpol(enabled);
-        self.write_register(&chopconf)
+    /// Check the chip for resets and faults.
+    ///
+    /// Reads GSTAT and DRV_STATUS. A reset that comes with a fault is
+    /// reported in [`Health::Fault`], since clearing the latched fault
+    /// (e.g. with [`clear_gstat`](Self::clear_gstat)) also clears the reset
+    /// flag. The reset flag is cleared after it has been reported so the
+    /// next check is clean; the fault flags are left for fault handling.
+    pub fn health_check(&mut self) -> Result<Health, Error<E>> {
+        let gstat = self.gstat()?;
+        let drv_status = self.drv_status()?;
+
+        let report = FaultReport::new(gstat, drv_status);
+        let reset = gstat.reset();
+        if reset {
+            let mut clear = Gstat::default();
+            clear.clear_reset();
+            self.write_register(&clear)?;
+        }
+        if report.has_fault() {
+            return Ok(Health::Fault { report, reset });
+        }
+        if reset {
+            return Ok(Health::NeedsReconfig);
+        }
+        Ok(Health::Ok)
+    }
+
+    /// Read each static register twice and report differing values.
+    ///
+    /// On a healthy bus [`StabilityReport::REGISTERS`] always read back
+    /// the same; a difference points to corrupted transfers that still
+    /// passed the CRC check, or to a second chip answering. The read cache
+    /// is bypassed.
+    pub fn stability_check(&mut self) -> Result<StabilityReport, Error<E>> {
+        let mut report = StabilityReport::default();
+        for (index, addr) in StabilityReport::REGISTERS.iter().enumerate() {
+            let first = self.read_raw(*addr as u8)?;
+            let second = self.read_raw(*addr as u8)?;
+            report.compare(index, first, second);
+        }
+        Ok(report)
+    }
+
+    /// Read OTP_READ once and serve later reads from a cache.
+    ///
+    /// OTP memory cannot change during operation, so the cached value is
+    /// always valid. Subsequent `read_register::<OtpRead>()` calls do not
+    /// touch the bus until [`invalidate_cache`](Self::invalidate_cache).
+    pub fn cache_otp(&mut self) -> Result<OtpRead, Error<E>> {
+        let otp = self.read_register::<OtpRead>()?;
+        self.cache.otp = Some(otp.raw());
+        Ok(otp)
+    }
+
+    /// Permanently program one OTP bit.
+    ///
+    /// Reads OTP_READ first and does nothing if the bit is already set.
+    /// Otherwise writes OTP_PROG with the field's byte and bit and the
+    /// magic value, calls `delay`, which must wait at least 10 ms for
+    /// programming to complete, and reads OTP_READ back. A value cached by
+    /// [`cache_otp`](Self::cache_otp) is refreshed with the new contents.
+    ///
+    /// **Warning:** programmed bits can never be cleared.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::VerifyMismatch`] if the bit is not set in the
+    /// read-back value.
+    pub fn program_otp_field(
+        &mut self,
+        field: OtpField,
+        _confirm: OtpConfirm,
+        delay: impl FnOnce(),
+    ) -> Result<(), Error<E>> {
+        let before = self.read_register::<OtpRead>()?.raw();
+        let mask = field.otp_read_mask();
+        if before & mask != 0 {
+            return Ok(());
+        }
+        self.write_register(&OtpProg::for_field(field))?;
+        delay();
+        let cached = self.cache.otp.take().is_some();
+        let after = self.read_register::<OtpRead>()?.raw();
+        if cached {
+            self.cache.otp = Some(after);
+        }
+        if after & mask == 0 {
+            return Err(Error::VerifyMismatch {
+                expected: before | mask,
+                actual: after,
+            });
+        }
+        Ok(())
+    }
+
+    /// Get the input pin states.
+    pub fn ioin(&mut self) -> Result<Ioin, Error<E>> {
+        self.read_register()
+    }
+
+    /// Read all input pin states and the IC version as a snapshot.
+    pub fn read_ioin(&mut self) -> Result<IoinState, Error<E>> {
+        let reg = self.read_register::<Ioin>()?;
+        Ok(reg.state())
+    }
+
+    /// Read CHOPCONF and check it for common mistakes.
+    ///
+    /// See [`Chopconf::warnings`].
+    pub fn chopper_warnings(&mut self) -> Result<ChopperWarnings, Error<E>> {
+        Ok(self.read_register::<Chopconf>()?.warnings())
+    }
+
+    /// Read GCONF and decode all fields.
+    pub fn read_gconf_decoded(&mut self) -> Result<GconfDecoded, Error<E>> {
+        Ok(self.read_register::<Gconf>()?.decode())
+    }
+
+    /// Read CHOPCONF and decode all fields.
+    pub fn read_chopconf(&mut self) -> Result<ChopconfDecoded, Error<E>> {
+        Ok(self.read_register::<Chopconf>()?.decode())
+    }
+
+    /// Read the silicon revision from IOIN.
+    pub fn revision(&mut self) -> Result<Revision, Error<E>> {
+        Ok(self.read_register::<Ioin>()?.revision())
+    }
+
+    /// Get the driver status.
+    pub fn drv_status(&mut self) -> Result<DrvStatus, Error<E>> {
+        self.read_register()
+    }
+
+    /// Read the temperature flags from DRV_STATUS.
+    pub fn thermal_status(&mut self) -> Result<ThermalStatus, Error<E>> {
+        Ok(self.read_register::<DrvStatus>()?.thermal())
+    }
+
+    /// Get the current step time (inverse of velocity).
+    pub fn tstep(&mut self) -> Result<u32, Error<E>> {
+        let reg = self.read_register::<Tstep>()?;
+        Ok(reg.tstep())
+    }
+
+    /// Read TSTEP and interpret it as a velocity.
+    ///
+    /// The microstep resolution is taken from CHOPCONF.MRES and the clock
+    /// frequency from [`set_fclk`](Self::set_fclk).
+    pub fn velocity_state(&mut self) -> Result<VelocityState, Error<E>> {
+        let tstep = self.read_register::<Tstep>()?;
+        if tstep.is_standstill() {
+            return Ok(VelocityState::Standstill);
+        }
+        let microsteps = self
+            .read_register::<Chopconf>()?
+            .microstep_resolution()
+            .microsteps();
+        Ok(VelocityState::from_tstep(
+            tstep.value(),
+            microsteps,
+            self.fclk,
+        ))
+    }
+
+    /// Get the StallGuard result.
+    pub fn sg_result(&mut self) -> Result<u16, Error<E>> {
+        let reg = self.read_register::<SgResult>()?;
+        Ok(reg.result())
+    }
+
+    /// Read the motor load and the actual current scale.
+    ///
+    /// Returns `(sg_result, cs_actual)` from SG_RESULT and DRV_STATUS,
+    /// read with two datagrams.
+    pub fn load_and_current(&mut self) -> Result<(u16, u8), Error<E>> {
+        let load = self.read_register::<SgResult>()?.result();
+        let current = self.read_register::<DrvStatus>()?.cs_actual();
+        Ok((load, current))
+    }
+
+    /// Read the instantaneous coil currents in milliamps.
+    ///
+    /// Reads MSCURACT, DRV_STATUS (CS_ACTUAL) and CHOPCONF (VSENSE) and
+    /// returns `(coil_a, coil_b)`. The three reads are not atomic and the
+    /// scaling is approximate, see [`coil_current_ma`].
+    pub fn coil_currents_ma(&mut self, rsense: f32) -> Result<(i16, i16), Error<E>> {
+        let mscuract = self.read_register::<Mscuract>()?;
+        let cs = self.read_register::<DrvStatus>()?.cs_actual();
+        let vsense = self.read_register::<Chopconf>()?.vsense();
+        Ok((
+            coil_current_ma(mscuract.cur_a(), cs, rsense, vsense),
+            coil_current_ma(mscuract.cur_b(), cs, rsense, vsense),
+        ))
+    }
+
+    /// Get how far CoolStep has reduced the current below IRUN.
+    ///
+    /// Returns `configured_irun - CS_ACTUAL` (saturating at 0) from
+    /// DRV_STATUS. Staying at 0 under light load suggests raising SEMIN or
+    /// SEMAX; a large value at high load means CoolStep backs off too much.
+    pub fn coolstep_headroom(&mut self, configured_irun: u8) -> Result<u8, Error<E>> {
+        let cs_actual = self.read_register::<DrvStatus>()?.cs_actual();
+        Ok(configured_irun.saturating_sub(cs_actual))
+    }
+
+    /// Get how far CoolStep has reduced the current below the written IRUN.
+    ///
+    /// Like [`coolstep_headroom`](Self::coolstep_headroom), with IRUN taken
+    /// from the value last written to IHOLD_IRUN by this driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownValue`] if IHOLD_IRUN has not been written
+    /// through this driver yet.
+    pub fn coolstep_headroom_shadowed(&mut self) -> Result<u8, Error<E>> {
+        let irun = self.written_irun()?;
+        self.coolstep_headroom(irun)
+    }
+
+    /// Get the lowest current CoolStep can reduce the motor current to.
+    ///
+    /// Returns 1/2 (SEIMIN=0) or 1/4 (SEIMIN=1) of the RMS current for
+    /// `irun_cs`, in milliamps. SEIMIN is taken from the value last written
+    /// to COOLCONF by this driver (its reset value if never written) and
+    /// VSENSE is read from CHOPCONF.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `irun_cs` is above 31.
+    pub fn coolstep_min_current_ma(&mut self, irun_cs: u8, rsense: f32) -> Result<u16, Error<E>> {
+        if irun_cs > 31 {
+            return Err(Error::InvalidArgument);
+        }
+        let vsense = self.read_register::<Chopconf>()?.vsense();
+        Ok(self.coolstep_floor_ma(irun_cs, rsense, vsense))
+    }
+
+    /// Get the microstep counter position (0-1023).
+    pub fn mscnt(&mut self) -> Result<u16, Error<E>> {
+        let reg = self.read_register::<Mscnt>()?;
+        Ok(reg.count())
+    }
+
+    /// Get the microstep position as a fraction of the electrical period.
+    ///
+    /// Returns MSCNT / 1024, from 0.0 up to but excluding 1.0.
+    pub fn microstep_phase(&mut self) -> Result<f32, Error<E>> {
+        Ok(self.read_register::<Mscnt>()?.phase())
+    }
+
+    /// Read the electrical state of the motor in one call.
+    ///
+    /// Reads MSCURACT, MSCNT, TSTEP, SG_RESULT and DRV_STATUS (five
+    /// datagrams) and bundles them into a [`MotorState`]. `fclk` is the
+    /// internal clock frequency used to convert TSTEP to a velocity.
+    pub fn motor_state(&mut self, fclk: u32) -> Result<MotorState, Error<E>> {
+        let mscuract = self.read_register::<Mscuract>()?;
+        let mscnt = self.read_register::<Mscnt>()?;
+        let tstep = self.read_register::<Tstep>()?;
+        let sg_result = self.read_register::<SgResult>()?;
+        let drv_status = self.read_register::<DrvStatus>()?;
+        Ok(MotorState::from_registers(
+            mscuract, mscnt, tstep, sg_result, drv_status, fclk,
+        ))
+    }
+
+    /// Set the motor currents.
+    ///
+    /// # Arguments
+    ///
+    /// * `run_current` - Run current (0-31)
+    /// * `hold_current` - Hold current (0-31)
+    /// * `hold_delay` - Delay before reducing to hold current (0-15)
+    pub fn set_current(
+        &mut self,
+        run_current: u8,
+        hold_current: u8,
+        hold_delay: u8,
+    ) -> Result<(), Error<E>> {
+        let mut reg = IholdIrun::new();
+        reg.set_irun(run_current)
+            .set_ihold(hold_current)
+            .set_iholddelay(hold_delay);
+        self.write_register(&reg)
+    }
+
+    /// Set motor currents from validated [`CurrentScale`] values.
+    ///
+    /// Same as [`set_current`](Self::set_current), but the current values
+    /// cannot be out of range.
+    pub fn set_current_scaled(
+        &mut self,
+        run_current: CurrentScale,
+        hold_current: CurrentScale,
+        hold_delay: u8,
+    ) -> Result<(), Error<E>> {
+        self.set_current(run_current.value(), hold_current.value(), hold_delay)
+    }
+
+    /// Set the motor currents with the hold current as a fraction of run current.
+    ///
+    /// IHOLD is `round(run_current * hold_fraction)`, so it can never exceed
+    /// IRUN.
+    ///
+    /// # Arguments
+    ///
+    /// * `run_current` - Run current (0-31)
+    /// * `hold_fraction` - Hold current relative to run current (0.0-1.0)
+    /// * `hold_delay` - Delay before reducing to hold current (0-15)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `hold_fraction` is outside
+    /// `0.0..=1.0`.
+    pub fn set_current_with_hold_fraction(
+        &mut self,
+        run_current: u8,
+        hold_fraction: f32,
+        hold_delay: u8,
+    ) -> Result<(), Error<E>> {
+        let hold_current = hold_current_from_fraction(run_current, hold_fraction)?;
+        self.set_current(run_current, hold_current, hold_delay)
+    }
+
+    /// Run `f` with a temporarily boosted run current.
+    ///
+    /// IHOLD_IRUN is write-only, so the current setting is taken from the
+    /// value last written by this driver. IRUN is set to `run_current`
+    /// (clamped to 0-31) while `f` runs, and the original IHOLD_IRUN is
+    /// restored afterwards even if `f` fails. An error from `f` takes
+    /// precedence over an error from the restore.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownValue`] if IHOLD_IRUN has not been written
+    /// through this driver yet.
+    pub fn with_boosted_current<F>(&mut self, run_current: u8, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error<E>>,
+    {
+        let original = self
+            .last_written::<IholdIrun>()
+            .ok_or(Error::UnknownValue(Address::IholdIrun as u8))?;
+
+        let mut boosted = original;
+        boosted.set_irun(run_current.min(31));
+        self.write_register(&boosted)?;
+
+        let result = f(self);
+        let restored = self.write_register(&original);
+        result.and(restored)
+    }
+
+    /// Set the microstep resolution.
+    pub fn set_microsteps(&mut self, resolution: MicrostepResolution) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register::<Chopconf>()?;
+        chopconf.set_microstep_resolution(resolution);
+        self.write_register(&chopconf)
+    }
+
+    /// Get the configured microstep resolution and whether interpolation is on.
+    ///
+    /// Returns `(microsteps, intpol)`. With INTPOL set the chip interpolates
+    /// every step to 256 microsteps internally, whatever the MRES setting.
+    pub fn effective_microsteps(&mut self) -> Result<(u16, bool), Error<E>> {
+        let chopconf = self.read_register::<Chopconf>()?;
+        Ok((
+            chopconf.microstep_resolution().microsteps(),
+            chopconf.intpol(),
+        ))
+    }
+
+    /// Set the microstep resolution and report the resulting position jump.
+    ///
+    /// Changing MRES while the motor is energized can move the microstep
+    /// table index. MSCNT is read before and after the change and the
+    /// signed difference (in 1/256 full-step units) is returned so the
+    /// caller can correct its own step counter.
+    pub fn set_microsteps_aligned(
+        &mut self,
+        resolution: MicrostepResolution,
+    ) -> Result<i16, Error<E>> {
+        let before = self.mscnt()?;
+        self.set_microsteps(resolution)?;
+        let after = self.mscnt()?;
+        Ok(mscnt_shortest_delta(before, after))
+    }
+
+    /// Enable or disable the driver.
+    ///
+    /// When TOFF=0, the driver is disabled. Enabling a disabled driver
+    /// writes the TOFF set with [`set_default_toff`](Self::set_default_toff)
+    /// (3 unless changed).
+    pub fn set_enabled(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register::<Chopconf>()?;
+        if enabled {
+            // Use the default TOFF if currently disabled
+            if chopconf.toff() == 0 {
+                chopconf.set_toff(self.default_toff);
+            }
+        } else {
+            chopconf.set_toff(0);
+        }
+        self.write_register(&chopconf)
+    }
+
+    /// Check that the driver stage is enabled by reading back CHOPCONF.
+    ///
+    /// Returns `true` if TOFF is non-zero. Only the register setting is
+    /// checked; a driver held off by the ENN pin still reports `true`.
+    pub fn confirm_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_register::<Chopconf>()?.toff() != 0)
+    }
+
+    /// Set velocity for internal motion controller (VACTUAL).
+    ///
+    /// # Arguments
+    ///
+    /// * `velocity` - Velocity value (signed, 23-bit range)
+    ///   - Positive: Forward motion
+    ///   - Negative: Reverse motion
+    ///   - 0: Stop
+    pub fn set_velocity(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        let mut reg = Vactual::new();
+        reg.set_velocity(velocity);
+        self.write_register(&reg)
+    }
+
+    /// Calibrate the StallGuard threshold from free-running samples.
+    ///
+    /// Spins the motor at `velocity` (VACTUAL) without load, reads SG_RESULT
+    /// `samples` times, writes the SGTHRS recommended by
+    /// [`recommend_stall_threshold`] and stops the motor. Returns the value
+    /// written.
+    ///
+    /// The motor must be able to turn freely and StallGuard must be active
+    /// at this velocity (see TCOOLTHRS).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidArgument` if `samples` is 0 or greater than 64.
+    pub fn calibrate_stall_threshold(
+        &mut self,
+        velocity: i32,
+        samples: usize,
+    ) -> Result<u8, Error<E>> {
+        let mut buf = [0u16; MAX_STALL_SAMPLES];
+        if samples == 0 || samples > buf.len() {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_velocity(velocity)?;
+        let mut sampled = Ok(());
+        for slot in buf[..samples].iter_mut() {
+            match self.read_register::<SgResult>() {
+                Ok(reg) => *slot = reg.result(),
+                Err(e) => {
+                    sampled = Err(e);
+                    break;
+                }
+            }
+        }
+        let stopped = self.stop();
+        sampled?;
+        stopped?;
+        let threshold = recommend_stall_threshold(&buf[..samples]).unwrap_or(0);
+        self.set_stall_threshold(threshold)?;
+        Ok(threshold)
+    }
+
+    /// Set VACTUAL and confirm that the motor started moving.
+    ///
+    /// Polls TSTEP up to `settle_polls` times, calling `delay` between
+    /// polls, until it leaves the standstill value. A velocity of 0 is
+    /// written without polling.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidArgument` if `settle_polls` is 0; nothing is
+    /// written. Returns `Error::MotionFailedToStart` if TSTEP still reads
+    /// standstill after the last poll, e.g. because the driver is disabled
+    /// (TOFF=0). VACTUAL is left as written.
+    pub fn start_motion(
+        &mut self,
+        velocity: i32,
+        settle_polls: u32,
+        mut delay: impl FnMut(),
+    ) -> Result<(), Error<E>> {
+        if settle_polls == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_velocity(velocity)?;
+        if velocity == 0 {
+            return Ok(());
+        }
+        for poll in 0..settle_polls {
+            if poll > 0 {
+                delay();
+            }
+            if !self.read_register::<Tstep>()?.is_standstill() {
+                return Ok(());
+            }
+        }
+        Err(Error::MotionFailedToStart)
+    }
+
+    /// Set the VACTUAL velocity in full steps/second.
+    ///
+    /// The microstep resolution is taken from CHOPCONF.MRES and the clock
+    /// frequency from [`set_fclk`](Self::set_fclk). Fails with
+    /// [`Error::VelocityLimited`] if the velocity exceeds the limit set
+    /// by [`set_max_velocity`](Self::set_max_velocity).
+    pub fn set_velocity_sps(&mut self, steps_per_sec: f32) -> Result<(), Error<E>> {
+        self.check_velocity(steps_per_sec)?;
+        let microsteps = self
+            .read_register::<Chopconf>()?
+            .microstep_resolution()
+            .microsteps();
+        self.set_velocity(velocity_to_vactual(steps_per_sec, microsteps, self.fclk))
+    }
+
+    /// Set the VACTUAL velocity in revolutions per minute.
+    ///
+    /// `full_steps_per_rev` is the motor's full steps per revolution,
+    /// usually 200 (1.8°) or 400 (0.9°). The velocity is converted to full
+    /// steps/second and written with
+    /// [`set_velocity_sps`](Self::set_velocity_sps).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidArgument` if `full_steps_per_rev` is 0.
+    pub fn set_velocity_rpm(&mut self, rpm: f32, full_steps_per_rev: u16) -> Result<(), Error<E>> {
+        if full_steps_per_rev == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_velocity_sps(rpm * full_steps_per_rev as f32 / 60.0)
+    }
+
+    /// Measure the velocity in revolutions per minute from TSTEP.
+    ///
+    /// Returns 0 at standstill. TSTEP carries no direction, so the result
+    /// is never negative.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidArgument` if `full_steps_per_rev` is 0.
+    pub fn measured_rpm(&mut self, full_steps_per_rev: u16) -> Result<f32, Error<E>> {
+        if full_steps_per_rev == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        Ok(match self.velocity_state()? {
+            VelocityState::Standstill => 0.0,
+            VelocityState::Moving { sps } => sps * 60.0 / full_steps_per_rev as f32,
+        })
+    }
+
+    /// Stop the motor (set VACTUAL to 0).
+    pub fn stop(&mut self) -> Result<(), Error<E>> {
+        self.set_velocity(0)
+    }
+
+    /// Stop the motor and disable the driver without a jolt.
+    ///
+    /// Writes VACTUAL=0 and polls DRV_STATUS.stst, calling `delay` between
+    /// polls, until the motor is at standstill. The run current is then
+    /// lowered to the hold current and TOFF set to 0. The current is only
+    /// lowered if IHOLD_IRUN was written through this driver, since the
+    /// register is write-only.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::StandstillTimeout` if standstill is not reached
+    /// within 100 polls. The driver is left enabled in that case.
+    pub fn disable_gracefully(&mut self, mut delay: impl FnMut()) -> Result<(), Error<E>> {
+        self.stop()?;
+        let mut standstill = false;
+        for poll in 0..STANDSTILL_POLLS {
+            if poll > 0 {
+                delay();
+            }
+            if self.drv_status()?.stst() {
+                standstill = true;
+                break;
+            }
+        }
+        if !standstill {
+            return Err(Error::StandstillTimeout);
+        }
+        if let Some(mut current) = self.last_written::<IholdIrun>() {
+            current.set_irun(current.ihold());
+            self.write_register(&current)?;
+        }
+        self.set_enabled(false)
+    }
+
+    /// Set the StallGuard threshold.
+    ///
+    /// Higher values make stall detection more sensitive.
+    pub fn set_stall_threshold(&mut self, threshold: u8) -> Result<(), Error<E>> {
+        let mut reg = Sgthrs::new();
+        reg.set_threshold(threshold);
+        self.write_register(&reg)
+    }
+
+    /// Enable StealthChop mode.
+    pub fn enable_stealthchop(&mut self) -> Result<(), Error<E>> {
+        let mut gconf = self.read_register::<Gconf>()?;
+        gconf.set_en_spreadcycle(false);
+        self.write_register(&gconf)
+    }
+
+    /// Enable SpreadCycle mode.
+    pub fn enable_spreadcycle(&mut self) -> Result<(), Error<E>> {
+        let mut gconf = self.read_register::<Gconf>()?;
+        gconf.set_en_spreadcycle(true);
+        self.write_register(&gconf)
+    }
+
+    /// Enable or disable the STEP input multistep filter.
+    ///
+    /// Recommended when driving via STEP/DIR above
+    /// [`MULTISTEP_FILT_THRESHOLD_HZ`](crate::util::MULTISTEP_FILT_THRESHOLD_HZ),
+    /// see [`microstep_frequency_hz`](crate::util::microstep_frequency_hz).
+    pub fn set_multistep_filter(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        let mut gconf = self.read_register::<Gconf>()?;
+        gconf.set_multistep_filt(enabled);
+        self.write_register(&gconf)
+    }
+
+    /// Check if the multistep filter is enabled.
+    pub fn multistep_filter(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_register::<Gconf>()?.multistep_filt())
+    }
+
+    /// Check if motor is in standstill.
+    pub fn is_standstill(&mut self) -> Result<bool, Error<E>> {
+        let status = self.drv_status()?;
+        Ok(status.stst())
+    }
+
+    /// Check if overtemperature warning is active.
+    pub fn is_overtemperature_warning(&mut self) -> Result<bool, Error<E>> {
+        let status = self.drv_status()?;
+        Ok(status.otpw())
+    }
+
+    /// Check if overtemperature shutdown is active.
+    pub fn is_overtemperature_shutdown(&mut self) -> Result<bool, Error<E>> {
+        let status = self.drv_status()?;
+        Ok(status.ot())
+    }
+
+    // ========================================================================
+    // CoolStep and StallGuard methods (blocking)
+    // ========================================================================
+
+    /// Enable CoolStep adaptive current control.
+    ///
+    /// CoolStep automatically reduces motor current when load is low,
+    /// saving power and reducing heat.
+    ///
+    /// SEUP, SEDN and SEIMIN are kept from the last COOLCONF written
+    /// through this driver, see
+    /// [`configure_coolstep_dynamics`](Self::configure_coolstep_dynamics).
+    ///
+    /// # Arguments
+    ///
+    /// * `semin` - Minimum StallGuard value for current increase (1-15, 0 disables)
+    /// * `semax` - Hysteresis for current decrease (0-15)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Enable CoolStep with moderate sensitivity
+    /// driver.enable_coolstep(4, 2)?;
+    /// ```
+    pub fn enable_coolstep(&mut self, semin: u8, semax: u8) -> Result<(), Error<E>> {
+        let mut coolconf = self.written_coolconf();
+        coolconf.set_semin(semin.min(15)).set_semax(semax.min(15));
+        self.write_register(&coolconf)
+    }
+
+    /// Configure how fast CoolStep adapts the current.
+    ///
+    /// COOLCONF is write-only, so SEMIN and SEMAX are taken from the last
+    /// COOLCONF written through this driver (0, i.e. CoolStep disabled, if
+    /// none was written).
+    ///
+    /// # Arguments
+    ///
+    /// * `seup` - Current increment per step: 1, 2, 4 or 8 (0-3)
+    /// * `sedn` - StallGuard readings per current decrement: 32, 8, 2 or 1 (0-3)
+    /// * `seimin` - Lower limit is 1/4 of IRUN instead of 1/2
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidArgument` without writing if `seup` or `sedn`
+    /// is above 3.
+    pub fn configure_coolstep_dynamics(
+        &mut self,
+        seup: u8,
+        sedn: u8,
+        seimin: bool,
+    ) -> Result<(), Error<E>> {
+        let coolconf = self.coolstep_dynamics(seup, sedn, seimin)?;
+        self.write_register(&coolconf)
+    }
+
+    /// Disable CoolStep.
+    pub fn disable_coolstep(&mut self) -> Result<(), Error<E>> {
+        let coolconf = Coolconf::new(); // semin=0 disables CoolStep
+        self.write_register(&coolconf)
+    }
+
+    /// Set the CoolStep velocity threshold (TCOOLTHRS).
+    ///
+    /// CoolStep and StallGuard are only active when TSTEP < TCOOLTHRS.
+    /// Below this velocity, CoolStep and stall detection are disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - TSTEP threshold value (0 = disabled, 0xFFFFF = always active)
+    pub fn set_coolstep_threshold(&mut self, threshold: u32) -> Result<(), Error<E>> {
+        let mut tcoolthrs = Tcoolthrs::new();
+        tcoolthrs.set_threshold(threshold);
+        self.write_register(&tcoolthrs)
+    }
+
+    /// Set the StealthChop velocity threshold (TPWMTHRS).
+    ///
+    /// Above this velocity, the driver switches from StealthChop to SpreadCycle.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - TSTEP threshold value (0 = only SpreadCycle, 0xFFFFF = only StealthChop)
+    pub fn set_stealthchop_threshold(&mut self, threshold: u32) -> Result<(), Error<E>> {
+        let mut tpwmthrs = Tpwmthrs::new();
+        tpwmthrs.set_threshold(threshold);
+        self.write_register(&tpwmthrs)
+    }
+
+    /// Set TPWMTHRS and TCOOLTHRS from velocities in full steps/second.
+    ///
+    /// StealthChop is used up to `stealthchop_max_sps` and StallGuard is
+    /// active from `stallguard_min_sps`, so sensorless operation works
+    /// between the two. Both are converted to TSTEP values using the
+    /// microstep resolution from CHOPCONF.MRES and the clock frequency from
+    /// [`set_fclk`](Self::set_fclk). Since TSTEP is inversely
+    /// proportional to velocity, the band requires TPWMTHRS < TCOOLTHRS,
+    /// i.e. `stallguard_min_sps < stealthchop_max_sps`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidThresholdOrder` without writing anything if
+    /// the thresholds would be equal or inverted.
+    pub fn configure_velocity_bands(
+        &mut self,
+        stealthchop_max_sps: f32,
+        stallguard_min_sps: f32,
+    ) -> Result<(), Error<E>> {
+        let microsteps = self
+            .read_register::<Chopconf>()?
+            .microstep_resolution()
+            .microsteps();
+        let (tpwmthrs, tcoolthrs) = velocity_bands(
+            stealthchop_max_sps,
+            stallguard_min_sps,
+            microsteps,
+            self.fclk,
+        )?;
+        self.set_stealthchop_threshold(tpwmthrs)?;
+        self.set_coolstep_threshold(tcoolthrs)
+    }
+
+    // ========================================================================
+    // Sensorless homing methods (blocking)
+    // ========================================================================
+
+    /// Configure stall detection for sensorless homing.
+    ///
+    /// This sets up the StallGuard feature to detect when the motor
+    /// hits an endstop or obstacle.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - StallGuard threshold (0-255, higher = more sensitive)
+    ///
+    /// # Note
+    ///
+    /// For sensorless homing to work properly:
+    /// - Use SpreadCycle mode (not StealthChop)
+    /// - Set appropriate TCOOLTHRS (stall detection only works above this velocity)
+    /// - Move at a consistent, moderate speed
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Configure for sensorless homing
+    /// driver.configure_stall_detection(50)?;
+    /// driver.enable_spreadcycle()?;
+    /// driver.set_coolstep_threshold(0)?; // Enable at all velocities
+    ///
+    /// // Move towards endstop
+    /// driver.set_velocity(-1000)?;
+    ///
+    /// // Poll for stall
+    /// loop {
+    ///     if driver.is_stalled()? {
+    ///         driver.stop()?;
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    pub fn configure_stall_detection(&mut self, threshold: u8) -> Result<(), Error<E>> {
+        // Set StallGuard threshold
+        self.set_stall_threshold(threshold)?;
+
+        // Enable DIAG output for stall indication
+        let mut gconf = self.read_register::<Gconf>()?;
+        gconf.set_diag0_stall(true);
+        self.write_register(&gconf)?;
+
+        Ok(())
+    }
+
+    /// Apply a complete sensorless homing configuration.
+    ///
+    /// Writes, in order, IHOLD_IRUN, CHOPCONF (microsteps), GCONF
+    /// (StealthChop and stall output on DIAG), SGTHRS and TCOOLTHRS. The
+    /// driver is not enabled and no motion is started.
+    ///
+    /// StallGuard4 only works in StealthChop, so SpreadCycle is turned
+    /// off. TPWMTHRS is left alone and must not switch to SpreadCycle at
+    /// the homing velocity.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] without writing anything if
+    /// TCOOLTHRS is 0 (StallGuard would never be active) or above
+    /// 0xFFFFF, or a current setting is out of range.
+    pub fn prepare_homing(&mut self, cfg: &HomingConfig) -> Result<(), Error<E>> {
+        cfg.validate()?;
+        self.set_current(cfg.run_current, cfg.hold_current, cfg.hold_delay)?;
+        self.set_microsteps(cfg.microsteps)?;
+
+        let mut gconf = self.read_register::<Gconf>()?;
+        gconf.set_en_spreadcycle(false).set_diag0_stall(true);
+        self.write_register(&gconf)?;
+
+        self.set_stall_threshold(cfg.stall_threshold)?;
+        self.set_coolstep_threshold(cfg.tcoolthrs)
+    }
+
+    /// Write a complete register configuration.
+    ///
+    /// GCONF is written first, then IHOLD_IRUN, TPOWERDOWN, TPWMTHRS,
+    /// TCOOLTHRS, SGTHRS, COOLCONF, CHOPCONF and PWMCONF. Stops at the
+    /// first failed write.
+    pub fn apply_config(&mut self, cfg: &Config) -> Result<(), Error<E>> {
+        for (addr, value) in cfg.registers() {
+            self.write_raw(addr as u8, value)?;
+        }
+        Ok(())
+    }
+
+    /// Check if the motor is currently stalled.
+    ///
+    /// Returns true if the StallGuard result is below the threshold.
+    /// Only valid when motor is moving and TSTEP < TCOOLTHRS.
+    pub fn is_stalled(&mut self) -> Result<bool, Error<E>> {
+        let sg_result = self.sg_result()?;
+        // SG_RESULT of 0 indicates stall
+        Ok(sg_result == 0)
+    }
+
+    /// Get the current load indicator from StallGuard.
+    ///
+    /// Returns a value from 0 (high load/stall) to 510 (no load).
+    /// Useful for load monitoring and stall detection tuning.
+    pub fn load_indicator(&mut self) -> Result<u16, Error<E>> {
+        self.sg_result()
+    }
+
+    /// Get how far SG_RESULT is above the stall trip point.
+    ///
+    /// Returns `SG_RESULT - SGTHRS * 2`, with SGTHRS taken from the value
+    /// last written by this driver. A zero or negative margin means the
+    /// stall output is asserted, since it trips at SG_RESULT <= 2 * SGTHRS.
+    /// Like [`is_stalled`](Self::is_stalled), this is only meaningful while
+    /// moving with TSTEP < TCOOLTHRS.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownValue`] if SGTHRS has not been written
+    /// through this driver yet.
+    pub fn stall_margin(&mut self) -> Result<i16, Error<E>> {
+        let trip_point = self.written_stall_trip_point()?;
+        Ok(self.sg_result()? as i16 - trip_point)
+    }
+
+    /// Read the open load flags if the motor moves fast enough to trust them.
+    ///
+    /// OLA/OLB may be set falsely at standstill and low velocity. Returns
+    /// `None` without reading DRV_STATUS unless TSTEP is below the
+    /// threshold set by
+    /// [`set_open_load_tstep_threshold`](Self::set_open_load_tstep_threshold),
+    /// otherwise `Some((ola, olb))`.
+    pub fn open_load_valid(&mut self) -> Result<Option<(bool, bool)>, Error<E>> {
+        let tstep = self.read_register::<Tstep>()?.tstep();
+        if tstep >= self.open_load_tstep {
+            return Ok(None);
+        }
+        let status = self.drv_status()?;
+        Ok(Some((status.ola(), status.olb())))
+    }
+
+    // ========================================================================
+    // PWM and StealthChop configuration (blocking)
+    // ========================================================================
+
+    /// Configure StealthChop PWM settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `pwm_ofs` - PWM amplitude offset (0-255)
+    /// * `pwm_grad` - PWM amplitude gradient (0-255)
+    /// * `autoscale` - Enable automatic current scaling
+    /// * `autograd` - Enable automatic gradient adaptation
+    pub fn configure_stealthchop(
+        &mut self,
+        pwm_ofs: u8,
+        pwm_grad: u8,
+        autoscale: bool,
+        autograd: bool,
+    ) -> Result<(), Error<E>> {
+        let mut pwmconf = self.read_register::<Pwmconf>()?;
+        pwmconf
+            .set_pwm_ofs(pwm_ofs)
+            .set_pwm_grad(pwm_grad)
+            .set_pwm_autoscale(autoscale)
+            .set_pwm_autograd(autograd);
+        self.write_register(&pwmconf)
+    }
+
+    /// Set the StealthChop PWM regulation limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `pwm_reg` - Maximum PWM amplitude change per half wave (0-15, clamped)
+    /// * `pwm_lim` - PWM amplitude limit when switching on (0-15, clamped)
+    pub fn set_pwm_regulation(&mut self, pwm_reg: u8, pwm_lim: u8) -> Result<(), Error<E>> {
+        let mut pwmconf = self.read_register::<Pwmconf>()?;
+        pwmconf
+            .set_pwm_reg(pwm_reg.min(15))
+            .set_pwm_lim(pwm_lim.min(15));
+        self.write_register(&pwmconf)
+    }
+
+    /// Freeze the learned StealthChop tuning into PWMCONF.
+    ///
+    /// Copies PWM_OFS_AUTO and PWM_GRAD_AUTO from PWM_AUTO into PWM_OFS and
+    /// PWM_GRAD and clears pwm_autoscale and pwm_autograd, so the motor
+    /// starts with the same tuning after every power cycle. Call this once
+    /// automatic tuning has settled.
+    pub fn freeze_stealthchop_tuning(&mut self) -> Result<(), Error<E>> {
+        let auto = self.read_register::<PwmAuto>()?;
+        let mut pwmconf = self.read_register::<Pwmconf>()?;
+        pwmconf
+            .set_pwm_ofs(auto.pwm_ofs_auto())
+            .set_pwm_grad(auto.pwm_grad_auto())
+            .set_pwm_autoscale(false)
+            .set_pwm_autograd(false);
+        self.write_register(&pwmconf)
+    }
+
+    /// Set the hold current and standstill mode together.
+    ///
+    /// PWMCONF.FREEWHEEL only takes effect with IHOLD=0, so for every mode
+    /// but [`StandstillMode::Normal`] IHOLD is forced to 0 and
+    /// `hold_current` is ignored. With `Normal` the motor holds with
+    /// IHOLD=`hold_current` (0-31). IRUN and IHOLDDELAY are kept from the
+    /// value last written to IHOLD_IRUN by this driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownValue`] if IHOLD_IRUN has not been written
+    /// through this driver yet, or [`Error::InvalidArgument`] if
+    /// `hold_current` is above 31 with `Normal`.
+    pub fn set_standstill_behavior(
+        &mut self,
+        hold_current: u8,
+        mode: StandstillMode,
+    ) -> Result<(), Error<E>> {
+        let ihold_irun = self.standstill_current(hold_current, mode)?;
+        let mut pwmconf = self.read_register::<Pwmconf>()?;
+        pwmconf.set_standstill_mode(mode);
+        self.write_register(&pwmconf)?;
+        self.write_register(&ihold_irun)
+    }
+
+    /// Set VSENSE for current sense resistor scaling.
+    ///
+    /// # Arguments
+    ///
+    /// * `high_sensitivity` - true for high sensitivity (low current range),
+    ///   false for low sensitivity (high current range)
+    pub fn set_vsense(&mut self, high_sensitivity: bool) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register::<Chopconf>()?;
+        chopconf.set_vsense(high_sensitivity);
+        self.write_register(&chopconf)
+    }
+
+    /// Configure chopper settings for optimal performance.
+    ///
+    /// # Arguments
+    ///
+    /// * `toff` - Off-time (1-15, 0 disables driver)
+    /// * `hstrt` - Hysteresis start (0-7)
+    /// * `hend` - Hysteresis end (0-15)
+    /// * `tbl` - Comparator blank time (0-3)
+    pub fn configure_chopper(
+        &mut self,
+        toff: u8,
+        hstrt: u8,
+        hend: u8,
+        tbl: u8,
+    ) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register::<Chopconf>()?;
+        chopconf
+            .set_toff(toff.min(15))
+            .set_hstrt(hstrt.min(7))
+            .set_hend(hend.min(15))
+            .set_tbl(tbl.min(3));
+        self.write_register(&chopconf)
+    }
+
+    /// Enable interpolation to 256 microsteps.
+    ///
+    /// When enabled, the driver interpolates between microsteps
+    /// for smoother motion, even at lower microstep settings.
+    pub fn set_interpolation(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register::<Chopconf>()?;
+        chopconf.set_intpol(enabled);
+        self.write_register(&chopconf)
+    }
+
+    /// Read the actual motor current scale being used.
+    ///
+    /// This reflects CoolStep adjustments if enabled.
+    /// Returns a value from 0-31.
+    pub fn actual_current_scale(&mut self) -> Result<u8, Error<E>> {
+        let status = self.drv_status()?;
+        Ok(status.cs_actual())
+    }
+
+    /// Check if the driver is currently in StealthChop mode.
+    pub fn is_stealthchop_active(&mut self) -> Result<bool, Error<E>> {
+        let status = self.drv_status()?;
+        Ok(status.stealth())
+    }
+
+    /// Check that the driver runs in the expected chopper mode.
+    ///
+    /// Reads DRV_STATUS.stealth. With TPWMTHRS set, the chip switches
+    /// between StealthChop and SpreadCycle by velocity, so a wrong
+    /// threshold shows up here as an unexpected mode (and as motor noise).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedChopperMode`] if the active mode differs
+    /// from `expected`.
+    pub fn verify_expected_mode(&mut self, expected: ChopperMode) -> Result<(), Error<E>> {
+        let actual = self.drv_status()?.chopper_mode();
+        check_chopper_mode(expected, actual)
+    }
+
+    /// Get a comprehensive status summary.
+    ///
+    /// Returns a tuple of (errors_present, warnings_present, is_running).
+    pub fn status_summary(&mut self) -> Result<(bool, bool, bool), Error<E>> {
+        let status = self.drv_status()?;
+
+        let errors = status.has_error();
+        let warnings = status.otpw() || status.open_load_detected();
+        let running = !status.stst();
+
+        Ok((errors, warnings, running))
+    }
+}
+
+// ============================================================================
+// Async API
+// ============================================================================
+
+#[cfg(feature = "async")]
+impl<U, E, D> Tmc2209<U, D>
+where
+    U: embedded_io_async::Read<Error = E> + embedded_io_async::Write<Error = E>,
+    D: FnMut(),
+{
+    /// Read a register (async).
+    ///
+    /// Sends a read request and waits for the response.
+    pub async fn read_register_async<R: ReadableRegister>(&mut self) -> Result<R, Error<E>> {
+        if let Some(value) = self.cache.get(R::ADDRESS) {
+            return Ok(R::from(value));
+        }
+        let mut attempt = 0;
+        loop {
+            match self.read_register_once_async::<R>().await {
+                Err(e) if attempt < self.read_retries && self.is_retriable(&e) => {
+                    attempt += 1;
+                    self.begin_retry();
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Perform a single register read and check the response address (async).
+    async fn read_register_once_async<R: ReadableRegister>(&mut self) -> Result<R, Error<E>> {
+        let request = self.read_request::<R>();
+        let response = self.read_datagram_async(&request).await?;
+
+        // Verify the register address matches
+        let expected_addr = R::ADDRESS as u8;
+        if response.reg_addr() != expected_addr {
+            return Err(Error::AddressMismatch {
+                expected: expected_addr,
+                actual: response.reg_addr(),
+            });
+        }
+
+        self.cache.store(R::ADDRESS, response.data());
+        Ok(R::from(response.data()))
+    }
+
+    /// Read a register without verifying the response address (async).
+    ///
+    /// Intended for advanced/debug use only.
+    pub async fn read_register_lenient_async<R: ReadableRegister>(
+        &mut self,
+    ) -> Result<R, Error<E>> {
+        let request = self.read_request::<R>();
+        let response = self.read_datagram_async(&request).await?;
+        Ok(R::from(response.data()))
+    }
+
+    /// Read a register and count the UART reads it took (async).
+    ///
+    /// See [`read_register_counted`](Self::read_register_counted).
+    pub async fn read_register_counted_async<R: ReadableRegister>(
+        &mut self,
+    ) -> Result<(R, usize), Error<E>> {
+        let start = self.uart_reads;
+        let reg = self.read_register_async::<R>().await?;
+        Ok((reg, self.uart_reads.wrapping_sub(start)))
+    }
+
+    /// Write a register (async).
+    ///
+    /// Sends a write request to update a register value.
+    pub async fn write_register_async<R: WritableRegister>(
+        &mut self,
+        reg: &R,
+    ) -> Result<(), Error<E>> {
+        let request = self.write_request(reg);
+        self.write_datagram_async(&request).await
+    }
+
+    /// Write a register and verify it by reading it back (async).
+    ///
+    /// See [`Tmc2209::write_register_readback`].
+    pub async fn write_register_readback_async<R>(&mut self, reg: &R) -> Result<(), Error<E>>
+    where
+        R: ReadableRegister + WritableRegister,
+    {
+        self.write_register_async(reg).await?;
+        let actual = self.read_register_async::<R>().await?;
+        verify_readback(*reg, actual)
+    }
+
+    /// Read a register by raw address (async).
+    pub async fn read_raw_async(&mut self, reg_addr: u8) -> Result<u32, Error<E>> {
+        let request = ReadRequest::from_raw_addr(self.slave_addr, reg_addr);
+        let response = self.read_datagram_async(&request).await?;
+        Ok(response.data())
+    }
+
+    /// Write a register by raw address (async).
+    ///
+    /// Returns `Error::NotWritable` for known read-only registers.
+    pub async fn write_raw_async(&mut self, reg_addr: u8, data: u32) -> Result<(), Error<E>> {
+        let request = self.write_request_raw(reg_addr, data)?;
+        self.write_datagram_async(&request).await
+    }
+
+    /// Send a raw datagram and read the raw reply (async).
+    ///
+    /// See [`transact_raw`](Self::transact_raw).
+    pub async fn transact_raw_async(
+        &mut self,
+        request: &[u8],
+        response: &mut [u8; 8],
+    ) -> Result<(), Error<E>> {
+        if request.len() != ReadRequest::LEN && request.len() != WriteRequest::LEN {
+            return Err(Error::InvalidArgument);
+        }
+        self.transmit_async(request).await?;
+        if self.echo {
+            let mut echo_buf = [0u8; WriteRequest::LEN];
+            self.read_exact_async(&mut echo_buf[..request.len()])
+                .await?;
+        }
+        if request.len() == ReadRequest::LEN {
+            self.read_exact_async(response).await?;
+        }
+        Ok(())
+    }
+
+    /// Send a read request and return the validated response (async).
+    async fn read_datagram_async(
+        &mut self,
+        request: &ReadRequest,
+    ) -> Result<ReadResponse, Error<E>> {
+        let result = self.send_read_async(request).await;
+        self.record(false, &result);
+        result
+    }
+
+    /// Send a write request and consume its echo (async).
+    async fn write_datagram_async(&mut self, request: &WriteRequest) -> Result<(), Error<E>> {
+        let result = self.send_write_async(request).await;
+        self.record(true, &result);
+        if result.is_ok() {
+            self.shadow.record(request.reg_addr(), request.data());
+        }
+        result
+    }
+
+    /// Perform the UART exchange for a read request (async).
+    async fn send_read_async(&mut self, request: &ReadRequest) -> Result<ReadResponse, Error<E>> {
+        let request = &self
+            .crc_poly
+            .map_or(*request, |poly| request.with_crc_poly(poly));
+        // Send the read request
+        self.transmit_async(request.as_bytes()).await?;
+
+        // Skip the echo (4 bytes)
+        if self.echo {
+            let mut echo_buf = [0u8; ReadRequest::LEN];
+            self.read_exact_async(&mut echo_buf).await?;
+            if let Err(e) = self.check_echo(request.as_bytes(), &echo_buf) {
+                // Drain the reply so it is not taken for the next response
+                let mut reply = [0u8; ReadResponse::LEN];
+                let _ = self.read_exact_async(&mut reply).await;
+                return Err(e);
+            }
+        }
+
+        // Read the response
+        self.read_response_async().await
+    }
+
+    /// Write a register on several slaves in rapid succession (async).
+    pub(crate) async fn write_register_sequence_async<R: WritableRegister>(
+        &mut self,
+        reg: &R,
+        slaves: &[u8],
+    ) -> Result<(), Error<E>> {
+        Self::check_slaves(slaves)?;
+        let mut confirmed = 0;
+        let result = self
+            .send_write_sequence_async(reg, slaves, &mut confirmed)
+            .await;
+        self.finish_write_sequence(reg, slaves, confirmed, result)
+    }
+
+    /// Perform the UART exchange for a write sequence (async).
+    ///
+    /// Counts the datagrams that went out and, if echoes are read, were
+    /// echoed intact in `confirmed`.
+    async fn send_write_sequence_async<R: WritableRegister>(
+        &mut self,
+        reg: &R,
+        slaves: &[u8],
+        confirmed: &mut usize,
+    ) -> Result<(), Error<E>> {
+        for &slave in slaves {
+            let request = self.slave_write_request(slave, reg);
+            self.transmit_async(request.as_bytes()).await?;
+            if !self.echo {
+                *confirmed += 1;
+            }
+        }
+        if self.echo {
+            for (i, &slave) in slaves.iter().enumerate() {
+                let request = self.slave_write_request(slave, reg);
+                let mut echo_buf = [0u8; WriteRequest::LEN];
+                self.read_exact_async(&mut echo_buf).await?;
+                if let Err(e) = self.check_echo(request.as_bytes(), &echo_buf) {
+                    // Drain the echoes of the requests still in flight
+                    for _ in i + 1..slaves.len() {
+                        if self.read_exact_async(&mut echo_buf).await.is_err() {
+                            break;
+                        }
+                    }
+                    return Err(e);
+                }
+                *confirmed += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Perform the UART exchange for a write request (async).
+    async fn send_write_async(&mut self, request: &WriteRequest) -> Result<(), Error<E>> {
+        let request = &self
+            .crc_poly
+            .map_or(*request, |poly| request.with_crc_poly(poly));
+        // Send the write request
+        self.transmit_async(request.as_bytes()).await?;
+
+        // Read back the echo (8 bytes)
+        if self.echo {
+            let mut echo_buf = [0u8; WriteRequest::LEN];
+            self.read_exact_async(&mut echo_buf).await?;
+            self.check_echo(request.as_bytes(), &echo_buf)?;
+        }
+
+        Ok(())
+    }
+
+    /// Helper to read exact number of bytes (async).
+    async fn read_exact_async(&mut self, buf: &mut [u8]) -> Result<(), Error<E>> {
+        Self::read_exact_from_async(&mut self.uart, buf, &mut self.uart_reads).await
+    }
+
+    /// Read exactly `buf.len()` bytes from `uart`, counting `read` calls
+    /// (async).
+    async fn read_exact_from_async(
+        uart: &mut U,
+        buf: &mut [u8],
+        calls: &mut usize,
+    ) -> Result<(), Error<E>> {
+        let mut total_read = 0;
+        while total_read < buf.len() {
+            *calls = calls.wrapping_add(1);
+            let n = uart
+                .read(&mut buf[total_read..])
+                .await
+                .map_err(Error::Uart)?;
+            if n == 0 {
+                return Err(Error::NoResponse);
+            }
+            total_read += n;
+        }
+        Ok(())
+    }
+
+    /// Transmit a request, byte by byte if an inter-byte delay is set (async).
+    async fn transmit_async(&mut self, bytes: &[u8]) -> Result<(), Error<E>> {
+        match self.inter_byte_delay.as_mut() {
+            None => self.uart.write_all(bytes).await.map_err(Error::Uart)?,
+            Some(delay) => {
+                for (i, byte) in bytes.iter().enumerate() {
+                    if i > 0 {
+                        delay();
+                    }
+                    self.uart
+                        .write_all(core::slice::from_ref(byte))
+                        .await
+                        .map_err(Error::Uart)?;
+                }
+            }
+        }
+        self.uart.flush().await.map_err(Error::Uart)
+    }
+
+    /// Helper to read a complete response (async).
+    async fn read_response_async(&mut self) -> Result<ReadResponse, Error<E>> {
+        Self::read_exact_from_async(
+            &mut self.uart,
+            self.reader.frame_buffer(),
+            &mut self.uart_reads,
+        )
+        .await?;
+        self.reader.parse_frame_buffer().ok_or(Error::NoResponse)?
+    }
+
+    // ========================================================================
+    // Convenience methods (async)
+    // ========================================================================
+
+    /// Check if the driver is communicating properly (async).
+    pub async fn is_connected_async(&mut self) -> bool {
+        self.read_register_async::<Ifcnt>().await.is_ok()
+    }
+
+    /// Get the interface transmission counter (async).
+    pub async fn ifcnt_async(&mut self) -> Result<u8, Error<E>> {
+        let reg = self.read_register_async::<Ifcnt>().await?;
+        Ok(reg.count())
+    }
+
+    /// Get the number of writes counted since an earlier IFCNT reading (async).
+    pub async fn ifcnt_delta_since_async(&mut self, previous: u8) -> Result<u8, Error<E>> {
+        Ok(self.ifcnt_async().await?.wrapping_sub(previous))
+    }
+
+    /// Get the driver status (async).
+    pub async fn drv_status_async(&mut self) -> Result<DrvStatus, Error<E>> {
+        self.read_register_async().await
+    }
+
+    /// Clear the global status flags (async).
+    pub async fn clear_gstat_async(&mut self) -> Result<(), Error<E>> {
+        // Writing 1s clears the flags
+        let gstat = Gstat::from(0x07);
+        self.write_register_async(&gstat).await
+    }
+
+    /// Read the motor load and the actual current scale (async).
+    pub async fn load_and_current_async(&mut self) -> Result<(u16, u8), Error<E>> {
+        let load = self.read_register_async::<SgResult>().await?.result();
+        let current = self.read_register_async::<DrvStatus>().await?.cs_actual();
+        Ok((load, current))
+    }
+
+    /// Read the instantaneous coil currents in milliamps (async).
+    pub async fn coil_currents_ma_async(&mut self, rsense: f32) -> Result<(i16, i16), Error<E>> {
+        let mscuract = self.read_register_async::<Mscuract>().await?;
+        let cs = self.read_register_async::<DrvStatus>().await?.cs_actual();
+        let vsense = self.read_register_async::<Chopconf>().await?.vsense();
+        Ok((
+            coil_current_ma(mscuract.cur_a(), cs, rsense, vsense),
+            coil_current_ma(mscuract.cur_b(), cs, rsense, vsense),
+        ))
+    }
+
+    /// Get how far CoolStep has reduced the current below IRUN (async).
+    pub async fn coolstep_headroom_async(&mut self, configured_irun: u8) -> Result<u8, Error<E>> {
+        let cs_actual = self.read_register_async::<DrvStatus>().await?.cs_actual();
+        Ok(configured_irun.saturating_sub(cs_actual))
+    }
+
+    /// Get how far CoolStep has reduced the current below the written IRUN
+    /// (async).
+    pub async fn coolstep_headroom_shadowed_async(&mut self) -> Result<u8, Error<E>> {
+        let irun = self.written_irun()?;
+        self.coolstep_headroom_async(irun).await
+    }
+
+    /// Get the lowest current CoolStep can reduce the motor current to
+    /// (async).
+    ///
+    /// See [`coolstep_min_current_ma`](Self::coolstep_min_current_ma).
+    pub async fn coolstep_min_current_ma_async(
+        &mut self,
+        irun_cs: u8,
+        rsense: f32,
+    ) -> Result<u16, Error<E>> {
+        if irun_cs > 31 {
+            return Err(Error::InvalidArgument);
+        }
+        let vsense = self.read_register_async::<Chopconf>().await?.vsense();
+        Ok(self.coolstep_floor_ma(irun_cs, rsense, vsense))
+    }
+
+    /// Get the microstep position as a fraction of the electrical period (async).
+    pub async fn microstep_phase_async(&mut self) -> Result<f32, Error<E>> {
+        Ok(self.read_register_async::<Mscnt>().await?.phase())
+    }
+
+    /// Read the electrical state of the motor in one call (async).
+    ///
+    /// See [`motor_state`](Self::motor_state).
+    pub async fn motor_state_async(&mut self, fclk: u32) -> Result<MotorState, Error<E>> {
+        let mscuract = self.read_register_async::<Mscuract>().await?;
+        let mscnt = self.read_register_async::<Mscnt>().await?;
+        let tstep = self.read_register_async::<Tstep>().await?;
+        let sg_result = self.read_register_async::<SgResult>().await?;
+        let drv_status = self.read_register_async::<DrvStatus>().await?;
+        Ok(MotorState::from_registers(
+            mscuract, mscnt, tstep, sg_result, drv_status, fclk,
+        ))
+    }
+
+    /// Read the temperature flags from DRV_STATUS (async).
+    pub async fn thermal_status_async(&mut self) -> Result<ThermalStatus, Error<E>> {
+        Ok(self.read_register_async::<DrvStatus>().await?.thermal())
+    }
+
+    /// Find the slave address the chip answers at (async).
+    ///
+    /// See [`detect_slave_address`](Self::detect_slave_address).
+    pub async fn detect_slave_address_async(&mut self) -> Result<u8, Error<E>> {
+        for addr in 0..=3 {
+            let request = ReadRequest::new(addr, Address::Ifcnt);
+            match self.read_datagram_async(&request).await {
+                Ok(response) if response.reg_addr() == Address::Ifcnt as u8 => {
+                    self.slave_addr = addr;
+                    return Ok(addr);
+                }
+                Err(Error::Uart(e)) => return Err(Error::Uart(e)),
+                _ => {}
+            }
+        }
+        Err(Error::NoResponse)
+    }
+
+    /// Check for and clear the GSTAT reset flag (async).
+    pub async fn init_check_reset_async(&mut self) -> Result<bool, Error<E>> {
+        let reset = self.read_register_async::<Gstat>().await?.reset();
+        if reset {
+            let mut clear = Gstat::default();
+            clear.clear_reset();
+            self.write_register_async(&clear).await?;
+        }
+        Ok(reset)
+    }
+
+    /// Check if the short protections are disabled (async).
+    pub async fn protections_disabled_async(&mut self) -> Result<(bool, bool), Error<E>> {
+        let chopconf = self.read_register_async::<Chopconf>().await?;
+        Ok((chopconf.diss2g(), chopconf.diss2vs()))
+    }
+
+    /// Re-enable the short to GND and short to VS protections (async).
+    pub async fn safe_protections_async(&mut self) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register_async::<Chopconf>().await?;
+        if chopconf.diss2g() || chopconf.diss2vs() {
+            chopconf.set_diss2g(false).set_diss2vs(false);
+            self.write_register_async(&chopconf).await?;
+        }
+        Ok(())
+    }
+
+    /// Read all input pin states and the IC version as a snapshot (async).
+    pub async fn read_ioin_async(&mut self) -> Result<IoinState, Error<E>> {
+        let reg = self.read_register_async::<Ioin>().await?;
+        Ok(reg.state())
+    }
+
+    /// Set the UART reply delay (SLAVECONF.SENDDELAY) (async).
+    pub async fn set_reply_delay_async(&mut self, senddelay: u8) -> Result<(), Error<E>> {
+        let mut reg = Slaveconf::default();
+        reg.set_senddelay(senddelay.min(15));
+        self.write_register_async(&reg).await
+    }
+
+    /// Set the UART reply delay to at least `bit_times` bit times (async).
+    pub async fn set_reply_delay_bit_times_async(
+        &mut self,
+        bit_times: u16,
+    ) -> Result<(), Error<E>> {
+        self.set_reply_delay_async(Slaveconf::senddelay_for_bit_times(bit_times))
+            .await
+    }
+
+    /// Read CHOPCONF and check it for common mistakes (async).
+    pub async fn chopper_warnings_async(&mut self) -> Result<ChopperWarnings, Error<E>> {
+        Ok(self.read_register_async::<Chopconf>().await?.warnings())
+    }
+
+    /// Read GCONF and decode all fields (async).
+    pub async fn read_gconf_decoded_async(&mut self) -> Result<GconfDecoded, Error<E>> {
+        Ok(self.read_register_async::<Gconf>().await?.decode())
+    }
+
+    /// Read CHOPCONF and decode all fields (async).
+    pub async fn read_chopconf_async(&mut self) -> Result<ChopconfDecoded, Error<E>> {
+        Ok(self.read_register_async::<Chopconf>().await?.decode())
+    }
+
+    /// Read the silicon revision from IOIN (async).
+    pub async fn revision_async(&mut self) -> Result<Revision, Error<E>> {
+        Ok(self.read_register_async::<Ioin>().await?.revision())
+    }
+
+    /// Read OTP_READ once and serve later reads from a cache (async).
+    pub async fn cache_otp_async(&mut self) -> Result<OtpRead, Error<E>> {
+        let otp = self.read_register_async::<OtpRead>().await?;
+        self.cache.otp = Some(otp.raw());
+        Ok(otp)
+    }
+
+    /// Permanently program one OTP bit (async).
+    ///
+    /// `delay` returns a future that must complete no earlier than 10 ms.
+    /// See [`program_otp_field`](Self::program_otp_field).
+    pub async fn program_otp_field_async<F>(
+        &mut self,
+        field: OtpField,
+        _confirm: OtpConfirm,
+        delay: impl FnOnce() -> F,
+    ) -> Result<(), Error<E>>
+    where
+        F: core::future::Future<Output = ()>,
+    {
+        let before = self.read_register_async::<OtpRead>().await?.raw();
+        let mask = field.otp_read_mask();
+        if before & mask != 0 {
+            return Ok(());
+        }
+        self.write_register_async(&OtpProg::for_field(field))
+            .await?;
+        delay().await;
+        let cached = self.cache.otp.take().is_some();
+        let after = self.read_register_async::<OtpRead>().await?.raw();
+        if cached {
+            self.cache.otp = Some(after);
+        }
+        if after & mask == 0 {
+            return Err(Error::VerifyMismatch {
+                expected: before | mask,
+                actual: after,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the chip for resets and faults (async).
+    ///
+    /// See [`health_check`](Self::health_check).
+    pub async fn health_check_async(&mut self) -> Result<Health, Error<E>> {
+        let gstat = self.read_register_async::<Gstat>().await?;
+        let drv_status = self.read_register_async::<DrvStatus>().await?;
+
+        let report = FaultReport::new(gstat, drv_status);
+        let reset = gstat.reset();
+        if reset {
+            let mut clear = Gstat::default();
+            clear.clear_reset();
+            self.write_register_async(&clear).await?;
+        }
+        if report.has_fault() {
+            return Ok(Health::Fault { report, reset });
+        }
+        if reset {
+            return Ok(Health::NeedsReconfig);
+        }
+        Ok(Health::Ok)
+    }
+
+    /// Read each static register twice and report differing values (async).
+    ///
+    /// See [`stability_check`](Self::stability_check).
+    pub async fn stability_check_async(&mut self) -> Result<StabilityReport, Error<E>> {
+        let mut report = StabilityReport::default();
+        for (index, addr) in StabilityReport::REGISTERS.iter().enumerate() {
+            let first = self.read_raw_async(*addr as u8).await?;
+            let second = self.read_raw_async(*addr as u8).await?;
+            report.compare(index, first, second);
+        }
+        Ok(report)
+    }
+
+    /// Read TSTEP and interpret it as a velocity (async).
+    pub async fn velocity_state_async(&mut self) -> Result<VelocityState, Error<E>> {
+        let tstep = self.read_register_async::<Tstep>().await?;
+        if tstep.is_standstill() {
+            return Ok(VelocityState::Standstill);
+        }
+        let microsteps = self
+            .read_register_async::<Chopconf>()
+            .await?
+            .microstep_resolution()
+            .microsteps();
+        Ok(VelocityState::from_tstep(
+            tstep.value(),
+            microsteps,
+            self.fclk,
+        ))
+    }
+
+    /// Put the driver under UART control (async).
+    ///
+    /// See [`init_uart_control`](Self::init_uart_control).
+    pub async fn init_uart_control_async(&mut self) -> Result<(), Error<E>> {
+        let before = self.ifcnt_async().await?;
+        let mut gconf = self.read_register_async::<Gconf>().await?;
+        gconf.set_pdn_disable(true).set_mstep_reg_select(true);
+        self.write_register_async(&gconf).await?;
+        if self.ifcnt_async().await? == before {
+            return Err(Error::WriteNotAcknowledged(Address::Gconf as u8));
+        }
+        self.clear_gstat_async().await
+    }
+
+    /// Apply a conservative profile for quiet operation (async).
+    ///
+    /// See [`quiet_setup`](Self::quiet_setup).
+    pub async fn quiet_setup_async(&mut self, run_ma: u16, rsense: f32) -> Result<(), Error<E>> {
+        let (cs, vsense) =
+            calculate_current_settings(run_ma, rsense).ok_or(Error::InvalidArgument)?;
+
+        let mut gconf = self.read_register_async::<Gconf>().await?;
+        gconf
+            .set_pdn_disable(true)
+            .set_mstep_reg_select(true)
+            .set_en_spreadcycle(false);
+        self.write_register_async(&gconf).await?;
+
+        self.set_current_async(cs, cs / 2, 8).await?;
+        self.write_register_async(&Tpowerdown::new()).await?;
+
+        let mut pwmconf = self.read_register_async::<Pwmconf>().await?;
+        pwmconf
+            .set_pwm_ofs(36)
+            .set_pwm_grad(14)
+            .set_pwm_autoscale(true)
+            .set_pwm_autograd(true);
+        self.write_register_async(&pwmconf).await?;
+
+        let mut chopconf = self.read_register_async::<Chopconf>().await?;
+        chopconf
+            .set_toff(self.default_toff)
+            .set_hstrt(4)
+            .set_hend(1)
+            .set_tbl(2)
+            .set_vsense(vsense)
+            .set_microstep_resolution(MicrostepResolution::M16)
+            .set_intpol(true);
+        self.write_register_async(&chopconf).await
+    }
+
+    /// Set the motor currents (async).
+    pub async fn set_current_async(
+        &mut self,
+        run_current: u8,
+        hold_current: u8,
+        hold_delay: u8,
+    ) -> Result<(), Error<E>> {
+        let mut reg = IholdIrun::new();
+        reg.set_irun(run_current)
+            .set_ihold(hold_current)
+            .set_iholddelay(hold_delay);
+        self.write_register_async(&reg).await
+    }
+
+    /// Set motor currents from validated [`CurrentScale`] values (async).
+    pub async fn set_current_scaled_async(
+        &mut self,
+        run_current: CurrentScale,
+        hold_current: CurrentScale,
+        hold_delay: u8,
+    ) -> Result<(), Error<E>> {
+        self.set_current_async(run_current.value(), hold_current.value(), hold_delay)
+            .await
+    }
+
+    /// Set the motor currents with the hold current as a fraction of run current (async).
+    pub async fn set_current_with_hold_fraction_async(
+        &mut self,
+        run_current: u8,
+        hold_fraction: f32,
+        hold_delay: u8,
+    ) -> Result<(), Error<E>> {
+        let hold_current = hold_current_from_fraction(run_current, hold_fraction)?;
+        self.set_current_async(run_current, hold_current, hold_delay)
+            .await
+    }
+
+    /// Run the future returned by `f` with a temporarily boosted run
+    /// current (async).
+    ///
+    /// The future cannot borrow the driver, so it suits work done
+    /// elsewhere, such as generating STEP pulses or waiting for a move.
+    /// Otherwise as [`with_boosted_current`](Self::with_boosted_current).
+    pub async fn with_boosted_current_async<F>(
+        &mut self,
+        run_current: u8,
+        f: impl FnOnce() -> F,
+    ) -> Result<(), Error<E>>
+    where
+        F: core::future::Future<Output = Result<(), Error<E>>>,
+    {
+        let original = self
+            .last_written::<IholdIrun>()
+            .ok_or(Error::UnknownValue(Address::IholdIrun as u8))?;
+
+        let mut boosted = original;
+        boosted.set_irun(run_current.min(31));
+        self.write_register_async(&boosted).await?;
+
+        let result = f().await;
+        let restored = self.write_register_async(&original).await;
+        result.and(restored)
+    }
+
+    /// Set the microstep resolution (async).
+    pub async fn set_microsteps_async(
+        &mut self,
+        resolution: MicrostepResolution,
+    ) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register_async::<Chopconf>().await?;
+        chopconf.set_microstep_resolution(resolution);
+        self.write_register_async(&chopconf).await
+    }
+
+    /// Get the configured microstep resolution and whether interpolation is on (async).
+    pub async fn effective_microsteps_async(&mut self) -> Result<(u16, bool), Error<E>> {
+        let chopconf = self.read_register_async::<Chopconf>().await?;
+        Ok((
+            chopconf.microstep_resolution().microsteps(),
+            chopconf.intpol(),
+        ))
+    }
+
+    /// Set the microstep resolution and report the resulting position jump (async).
+    pub async fn set_microsteps_aligned_async(
+        &mut self,
+        resolution: MicrostepResolution,
+    ) -> Result<i16, Error<E>> {
+        let before = self.read_register_async::<Mscnt>().await?.count();
+        self.set_microsteps_async(resolution).await?;
+        let after = self.read_register_async::<Mscnt>().await?.count();
+        Ok(mscnt_shortest_delta(before, after))
+    }
+
+    /// Set velocity for internal motion controller (async).
+    pub async fn set_velocity_async(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        let mut reg = Vactual::new();
+        reg.set_velocity(velocity);
+        self.write_register_async(&reg).await
+    }
+
+    /// Calibrate the StallGuard threshold from free-running samples (async).
+    ///
+    /// See [`calibrate_stall_threshold`](Self::calibrate_stall_threshold).
+    pub async fn calibrate_stall_threshold_async(
+        &mut self,
+        velocity: i32,
+        samples: usize,
+    ) -> Result<u8, Error<E>> {
+        let mut buf = [0u16; MAX_STALL_SAMPLES];
+        if samples == 0 || samples > buf.len() {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_velocity_async(velocity).await?;
+        let mut sampled = Ok(());
+        for slot in buf[..samples].iter_mut() {
+            match self.read_register_async::<SgResult>().await {
+                Ok(reg) => *slot = reg.result(),
+                Err(e) => {
+                    sampled = Err(e);
+                    break;
+                }
+            }
+        }
+        let stopped = self.stop_async().await;
+        sampled?;
+        stopped?;
+        let threshold = recommend_stall_threshold(&buf[..samples]).unwrap_or(0);
+        let mut sgthrs = Sgthrs::new();
+        sgthrs.set_threshold(threshold);
+        self.write_register_async(&sgthrs).await?;
+        Ok(threshold)
+    }
+
+    /// Set VACTUAL and confirm that the motor started moving (async).
+    ///
+    /// `delay` returns a future awaited between TSTEP polls. Errors as for
+    /// [`start_motion`](Self::start_motion).
+    pub async fn start_motion_async<F>(
+        &mut self,
+        velocity: i32,
+        settle_polls: u32,
+        mut delay: impl FnMut() -> F,
+    ) -> Result<(), Error<E>>
+    where
+        F: core::future::Future<Output = ()>,
+    {
+        if settle_polls == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_velocity_async(velocity).await?;
+        if velocity == 0 {
+            return Ok(());
+        }
+        for poll in 0..settle_polls {
+            if poll > 0 {
+                delay().await;
+            }
+            if !self.read_register_async::<Tstep>().await?.is_standstill() {
+                return Ok(());
+            }
+        }
+        Err(Error::MotionFailedToStart)
+    }
+
+    /// Set the VACTUAL velocity in full steps/second (async).
+    pub async fn set_velocity_sps_async(&mut self, steps_per_sec: f32) -> Result<(), Error<E>> {
+        self.check_velocity(steps_per_sec)?;
+        let microsteps = self
+            .read_register_async::<Chopconf>()
+            .await?
+            .microstep_resolution()
+            .microsteps();
+        self.set_velocity_async(velocity_to_vactual(steps_per_sec, microsteps, self.fclk))
+            .await
+    }
+
+    /// Set the VACTUAL velocity in revolutions per minute (async).
+    ///
+    /// See [`set_velocity_rpm`](Self::set_velocity_rpm).
+    pub async fn set_velocity_rpm_async(
+        &mut self,
+        rpm: f32,
+        full_steps_per_rev: u16,
+    ) -> Result<(), Error<E>> {
+        if full_steps_per_rev == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_velocity_sps_async(rpm * full_steps_per_rev as f32 / 60.0)
+            .await
+    }
+
+    /// Measure the velocity in revolutions per minute from TSTEP (async).
+    pub async fn measured_rpm_async(&mut self, full_steps_per_rev: u16) -> Result<f32, Error<E>> {
+        if full_steps_per_rev == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        Ok(match self.velocity_state_async().await? {
+            VelocityState::Standstill => 0.0,
+            VelocityState::Moving { sps } => sps * 60.0 / full_steps_per_rev as f32,
+        })
+    }
+
+    /// Stop the motor (async).
+    pub async fn stop_async(&mut self) -> Result<(), Error<E>> {
+        self.set_velocity_async(0).await
+    }
+
+    /// Stop the motor and disable the driver without a jolt (async).
+    ///
+    /// `delay` returns a future awaited between DRV_STATUS polls. See
+    /// [`disable_gracefully`](Self::disable_gracefully).
+    pub async fn disable_gracefully_async<F>(
+        &mut self,
+        mut delay: impl FnMut() -> F,
+    ) -> Result<(), Error<E>>
+    where
+        F: core::future::Future<Output = ()>,
+    {
+        self.stop_async().await?;
+        let mut standstill = false;
+        for poll in 0..STANDSTILL_POLLS {
+            if poll > 0 {
+                delay().await;
+            }
+            if self.drv_status_async().await?.stst() {
+                standstill = true;
+                break;
+            }
+        }
+        if !standstill {
+            return Err(Error::StandstillTimeout);
+        }
+        if let Some(mut current) = self.last_written::<IholdIrun>() {
+            current.set_irun(current.ihold());
+            self.write_register_async(&current).await?;
+        }
+        self.set_enabled_async(false).await
+    }
+
+    // ========================================================================
+    // CoolStep and StallGuard methods (async)
+    // ========================================================================
+
+    /// Enable CoolStep adaptive current control (async).
+    pub async fn enable_coolstep_async(&mut self, semin: u8, semax: u8) -> Result<(), Error<E>> {
+        let mut coolconf = self.written_coolconf();
+        coolconf.set_semin(semin.min(15)).set_semax(semax.min(15));
+        self.write_register_async(&coolconf).await
+    }
+
+    /// Configure how fast CoolStep adapts the current (async).
+    ///
+    /// See [`configure_coolstep_dynamics`](Self::configure_coolstep_dynamics).
+    pub async fn configure_coolstep_dynamics_async(
+        &mut self,
+        seup: u8,
+        sedn: u8,
+        seimin: bool,
+    ) -> Result<(), Error<E>> {
+        let coolconf = self.coolstep_dynamics(seup, sedn, seimin)?;
+        self.write_register_async(&coolconf).await
+    }
+
+    /// Disable CoolStep (async).
+    pub async fn disable_coolstep_async(&mut self) -> Result<(), Error<E>> {
+        let coolconf = Coolconf::new();
+        self.write_register_async(&coolconf).await
+    }
+
+    /// Set the CoolStep velocity threshold (async).
+    pub async fn set_coolstep_threshold_async(&mut self, threshold: u32) -> Result<(), Error<E>> {
+        let mut tcoolthrs = Tcoolthrs::new();
+        tcoolthrs.set_threshold(threshold);
+        self.write_register_async(&tcoolthrs).await
+    }
+
+    /// Set the StealthChop velocity threshold (async).
+    pub async fn set_stealthchop_threshold_async(
+        &mut self,
+        threshold: u32,
+    ) -> Result<(), Error<E>> {
+        let mut tpwmthrs = Tpwmthrs::new();
+        tpwmthrs.set_threshold(threshold);
+        self.write_register_async(&tpwmthrs).await
+    }
+
+    /// Set TPWMTHRS and TCOOLTHRS from a velocity band (async).
+    ///
+    /// See [`configure_velocity_bands`](Self::configure_velocity_bands).
+    pub async fn configure_velocity_bands_async(
+        &mut self,
+        stealthchop_max_sps: f32,
+        stallguard_min_sps: f32,
+    ) -> Result<(), Error<E>> {
+        let microsteps = self
+            .read_register_async::<Chopconf>()
+            .await?
+            .microstep_resolution()
+            .microsteps();
+        let (tpwmthrs, tcoolthrs) = velocity_bands(
+            stealthchop_max_sps,
+            stallguard_min_sps,
+            microsteps,
+            self.fclk,
+        )?;
+        self.set_stealthchop_threshold_async(tpwmthrs).await?;
+        self.set_coolstep_threshold_async(tcoolthrs).await
+    }
+
+    // ========================================================================
+    // Sensorless homing methods (async)
+    // ========================================================================
+
+    /// Apply a complete sensorless homing configuration (async).
+    ///
+    /// See [`prepare_homing`](Self::prepare_homing).
+    pub async fn prepare_homing_async(&mut self, cfg: &HomingConfig) -> Result<(), Error<E>> {
+        cfg.validate()?;
+        self.set_current_async(cfg.run_current, cfg.hold_current, cfg.hold_delay)
+            .await?;
+        self.set_microsteps_async(cfg.microsteps).await?;
+
+        let mut gconf = self.read_register_async::<Gconf>().await?;
+        gconf.set_en_spreadcycle(false).set_diag0_stall(true);
+        self.write_register_async(&gconf).await?;
+
+        let mut sgthrs = Sgthrs::new();
+        sgthrs.set_threshold(cfg.stall_threshold);
+        self.write_register_async(&sgthrs).await?;
+        self.set_coolstep_threshold_async(cfg.tcoolthrs).await
+    }
+
+    /// Write a complete register configuration (async).
+    ///
+    /// Same order as [`apply_config`](Self::apply_config). `on_progress`
+    /// is called with each register's address after it was written.
+    pub async fn apply_config_async(
+        &mut self,
+        cfg: &Config,
+        mut on_progress: impl FnMut(Address),
+    ) -> Result<(), Error<E>> {
+        for (addr, value) in cfg.registers() {
+            self.write_raw_async(addr as u8, value).await?;
+            on_progress(addr);
+        }
+        Ok(())
+    }
+
+    /// Configure stall detection for sensorless homing (async).
+    pub async fn configure_stall_detection_async(&mut self, threshold: u8) -> Result<(), Error<E>> {
+        // Set StallGuard threshold
+        let mut sgthrs = Sgthrs::new();
+        sgthrs.set_threshold(threshold);
+        self.write_register_async(&sgthrs).await?;
+
+        // Enable DIAG output for stall indication
+        let mut gconf = self.read_register_async::<Gconf>().await?;
+        gconf.set_diag0_stall(true);
+        self.write_register_async(&gconf).await?;
+
+        Ok(())
+    }
+
+    /// Check if the motor is currently stalled (async).
+    pub async fn is_stalled_async(&mut self) -> Result<bool, Error<E>> {
+        let sg = self.read_register_async::<SgResult>().await?;
+        Ok(sg.result() == 0)
+    }
+
+    /// Get the current load indicator from StallGuard (async).
+    pub async fn load_indicator_async(&mut self) -> Result<u16, Error<E>> {
+        let sg = self.read_register_async::<SgResult>().await?;
+        Ok(sg.result())
+    }
+
+    /// Get how far SG_RESULT is above the stall trip point (async).
+    ///
+    /// See [`stall_margin`](Self::stall_margin).
+    pub async fn stall_margin_async(&mut self) -> Result<i16, Error<E>> {
+        let trip_point = self.written_stall_trip_point()?;
+        let sg = self.read_register_async::<SgResult>().await?;
+        Ok(sg.result() as i16 - trip_point)
+    }
+
+    /// Read the open load flags if the motor moves fast enough (async).
+    pub async fn open_load_valid_async(&mut self) -> Result<Option<(bool, bool)>, Error<E>> {
+        let tstep = self.read_register_async::<Tstep>().await?.tstep();
+        if tstep >= self.open_load_tstep {
+            return Ok(None);
+        }
+        let status = self.read_register_async::<DrvStatus>().await?;
+        Ok(Some((status.ola(), status.olb())))
+    }
+
+    // ========================================================================
+    // PWM and StealthChop configuration (async)
+    // ========================================================================
+
+    /// Set the StealthChop PWM regulation limits (async).
+    pub async fn set_pwm_regulation_async(
+        &mut self,
+        pwm_reg: u8,
+        pwm_lim: u8,
+    ) -> Result<(), Error<E>> {
+        let mut pwmconf = self.read_register_async::<Pwmconf>().await?;
+        pwmconf
+            .set_pwm_reg(pwm_reg.min(15))
+            .set_pwm_lim(pwm_lim.min(15));
+        self.write_register_async(&pwmconf).await
+    }
+
+    /// Freeze the learned StealthChop tuning into PWMCONF (async).
+    pub async fn freeze_stealthchop_tuning_async(&mut self) -> Result<(), Error<E>> {
+        let auto = self.read_register_async::<PwmAuto>().await?;
+        let mut pwmconf = self.read_register_async::<Pwmconf>().await?;
+        pwmconf
+            .set_pwm_ofs(auto.pwm_ofs_auto())
+            .set_pwm_grad(auto.pwm_grad_auto())
+            .set_pwm_autoscale(false)
+            .set_pwm_autograd(false);
+        self.write_register_async(&pwmconf).await
+    }
+
+    /// Set the hold current and standstill mode together (async).
+    ///
+    /// See [`set_standstill_behavior`](Self::set_standstill_behavior).
+    pub async fn set_standstill_behavior_async(
+        &mut self,
+        hold_current: u8,
+        mode: StandstillMode,
+    ) -> Result<(), Error<E>> {
+        let ihold_irun = self.standstill_current(hold_current, mode)?;
+        let mut pwmconf = self.read_register_async::<Pwmconf>().await?;
+        pwmconf.set_standstill_mode(mode);
+        self.write_register_async(&pwmconf).await?;
+        self.write_register_async(&ihold_irun).await
+    }
+
+    // ========================================================================
+    // Mode selection (async)
+    // ========================================================================
+
+    /// Enable StealthChop mode (async).
+    pub async fn enable_stealthchop_async(&mut self) -> Result<(), Error<E>> {
+        let mut gconf = self.read_register_async::<Gconf>().await?;
+        gconf.set_en_spreadcycle(false);
+        self.write_register_async(&gconf).await
+    }
+
+    /// Enable SpreadCycle mode (async).
+    pub async fn enable_spreadcycle_async(&mut self) -> Result<(), Error<E>> {
+        let mut gconf = self.read_register_async::<Gconf>().await?;
+        gconf.set_en_spreadcycle(true);
+        self.write_register_async(&gconf).await
+    }
+
+    /// Enable or disable the STEP input multistep filter (async).
+    pub async fn set_multistep_filter_async(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        let mut gconf = self.read_register_async::<Gconf>().await?;
+        gconf.set_multistep_filt(enabled);
+        self.write_register_async(&gconf).await
+    }
+
+    /// Check if the multistep filter is enabled (async).
+    pub async fn multistep_filter_async(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_register_async::<Gconf>().await?.multistep_filt())
+    }
+
+    /// Enable or disable the driver (async).
+    pub async fn set_enabled_async(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        let mut chopconf = self.read_register_async::<Chopconf>().await?;
+        if enabled {
+            if chopconf.toff() == 0 {
+                chopconf.set_toff(self.default_toff);
+            }
+        } else {
+            chopconf.set_toff(0);
+        }
+        self.write_register_async(&chopconf).await
+    }
+
+    /// Check that the driver stage is enabled by reading back CHOPCONF (async).
+    pub async fn confirm_enabled_async(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_register_async::<Chopconf>().await?.toff() != 0)
+    }
+
+    /// Check if motor is in standstill (async).
+    pub async fn is_standstill_async(&mut self) -> Result<bool, Error<E>> {
+        let status = self.drv_status_async().await?;
+        Ok(status.stst())
+    }
+
+    /// Check that the driver runs in the expected chopper mode (async).
+    ///
+    /// See [`verify_expected_mode`](Self::verify_expected_mode).
+    pub async fn verify_expected_mode_async(
+        &mut self,
+        expected: ChopperMode,
+    ) -> Result<(), Error<E>> {
+        let actual = self.drv_status_async().await?.chopper_mode();
+        check_chopper_mode(expected, actual)
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::datagram::{MASTER_ADDR, SYNC};
+    #[cfg(feature = "async")]
+    use crate::mock::block_on;
+    use crate::mock::MockUart;
+
+    #[test]
+    fn test_read_register_lenient() {
+        let mut uart = MockUart::new();
+        uart.reply_addr = Some(Address::Gconf as u8);
+        uart.regs[Address::Chopconf as usize] = 0x1000_0053;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(
+            driver.read_register::<Chopconf>(),
+            Err(Error::AddressMismatch {
+                expected: 0x6C,
+                actual: 0x00,
+            })
+        );
+
+        let chopconf = driver.read_register_lenient::<Chopconf>().unwrap();
+        assert_eq!(chopconf.raw(), 0x1000_0053);
+    }
+
+    #[test]
+    fn test_set_pwm_regulation() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Pwmconf as usize] = Pwmconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        driver.set_pwm_regulation(20, 3).unwrap();
+
+        let (addr, data) = driver.uart().writes()[0];
+        let pwmconf = Pwmconf::from_raw(data);
+        assert_eq!(addr, Address::Pwmconf as u8);
+        assert_eq!(pwmconf.pwm_reg(), 15);
+        assert_eq!(pwmconf.pwm_lim(), 3);
+        assert_eq!(pwmconf.pwm_ofs(), Pwmconf::new().pwm_ofs());
+        assert_eq!(pwmconf.pwm_freq(), Pwmconf::new().pwm_freq());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_set_pwm_regulation_async() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Pwmconf as usize] = Pwmconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        block_on(driver.set_pwm_regulation_async(4, 12)).unwrap();
+
+        let pwmconf = Pwmconf::from_raw(driver.uart().regs[Address::Pwmconf as usize]);
+        assert_eq!(pwmconf.pwm_reg(), 4);
+        assert_eq!(pwmconf.pwm_lim(), 12);
+    }
+
+    #[test]
+    fn test_set_microsteps_aligned() {
+        let mut uart = MockUart::new();
+        uart.script(Address::Mscnt as u8, 100);
+        uart.script(Address::Mscnt as u8, 96);
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let delta = driver
+            .set_microsteps_aligned(MicrostepResolution::M16)
+            .unwrap();
+        assert_eq!(delta, -4);
+
+        let chopconf = Chopconf::from_raw(driver.uart().regs[Address::Chopconf as usize]);
+        assert_eq!(chopconf.mres(), MicrostepResolution::M16.to_mres());
+    }
+
+    #[test]
+    fn test_effective_microsteps() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let mut chopconf = Chopconf::new();
+        chopconf
+            .set_microstep_resolution(MicrostepResolution::M16)
+            .set_intpol(true);
+        driver.write_register(&chopconf).unwrap();
+        assert_eq!(driver.effective_microsteps(), Ok((16, true)));
+
+        chopconf.set_intpol(false);
+        driver.write_register(&chopconf).unwrap();
+        assert_eq!(driver.effective_microsteps(), Ok((16, false)));
+    }
+
+    #[test]
+    fn test_init_uart_control() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gstat as usize] = 0x01; // reset flag
+        let mut driver = Tmc2209::new(uart, 0);
+
+        driver.init_uart_control().unwrap();
+        // Idempotent
+        driver.init_uart_control().unwrap();
+
+        let gconf = Gconf::from_raw(driver.uart().regs[Address::Gconf as usize]);
+        assert!(gconf.pdn_disable());
+        assert!(gconf.mstep_reg_select());
+        assert_eq!(driver.uart().regs[Address::Gstat as usize], 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_clear_gstat_async() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gstat as usize] = 0x05;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        block_on(driver.clear_gstat_async()).unwrap();
+        assert_eq!(driver.uart().writes(), &[(0x01, 0x07)]);
+        assert_eq!(driver.uart().regs[Address::Gstat as usize], 0);
+    }
+
+    #[test]
+    fn test_init_uart_control_not_acknowledged() {
+        let mut uart = MockUart::new();
+        // IFCNT stuck at the same value
+        uart.script(Address::Ifcnt as u8, 5);
+        uart.script(Address::Ifcnt as u8, 5);
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(
+            driver.init_uart_control(),
+            Err(Error::WriteNotAcknowledged(Address::Gconf as u8))
+        );
+    }
+
+    #[test]
+    fn test_velocity_state() {
+        let mut uart = MockUart::new();
+        let mut chopconf = Chopconf::new();
+        chopconf.set_microstep_resolution(MicrostepResolution::M16);
+        uart.regs[Address::Chopconf as usize] = chopconf.raw();
+        uart.script(Address::Tstep as u8, Tstep::MAX_VALUE);
+        // 12 MHz / (16 * 1000 steps/s)
+        uart.script(Address::Tstep as u8, 750);
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.velocity_state(), Ok(VelocityState::Standstill));
+        match driver.velocity_state().unwrap() {
+            VelocityState::Moving { sps } => assert!((sps - 1000.0).abs() < 0.01),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_health_check() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gstat as usize] = 0x01; // reset
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.health_check(), Ok(Health::NeedsReconfig));
+        assert_eq!(driver.uart().regs[Address::Gstat as usize], 0);
+        assert_eq!(driver.health_check(), Ok(Health::Ok));
+    }
+
+    #[test]
+    fn test_health_check_fault() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gstat as usize] = 0x03; // reset + drv_err
+        uart.regs[Address::DrvStatus as usize] = 1 << 2; // s2ga
+        let mut driver = Tmc2209::new(uart, 0);
+
+        match driver.health_check().unwrap() {
+            Health::Fault { report, reset } => {
+                assert!(report.drv_err);
+                assert!(report.short_to_ground_a);
+                assert!(!report.short_to_ground_b);
+                assert!(reset);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        // Only the reported reset flag is cleared; drv_err stays latched
+        assert_eq!(driver.uart().regs[Address::Gstat as usize], 0x02);
+        match driver.health_check().unwrap() {
+            Health::Fault { reset, .. } => assert!(!reset),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_health_check_fault_async() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gstat as usize] = 0x03; // reset + drv_err
+        let mut driver = Tmc2209::new(uart, 0);
+
+        match block_on(driver.health_check_async()).unwrap() {
+            Health::Fault { report, reset } => {
+                assert!(report.drv_err);
+                assert!(reset);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(driver.uart().regs[Address::Gstat as usize], 0x02);
+    }
+
+    #[test]
+    fn test_set_current_with_hold_fraction() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+
+        driver.set_current_with_hold_fraction(20, 0.5, 6).unwrap();
+        let reg = IholdIrun::from_raw(driver.uart().regs[Address::IholdIrun as usize]);
+        assert_eq!(reg.irun(), 20);
+        assert_eq!(reg.ihold(), 10);
+        assert_eq!(reg.iholddelay(), 6);
+
+        driver.set_current_with_hold_fraction(31, 1.0, 0).unwrap();
+        let reg = IholdIrun::from_raw(driver.uart().regs[Address::IholdIrun as usize]);
+        assert_eq!(reg.ihold(), 31);
+
+        driver.set_current_with_hold_fraction(31, 0.0, 0).unwrap();
+        let reg = IholdIrun::from_raw(driver.uart().regs[Address::IholdIrun as usize]);
+        assert_eq!(reg.ihold(), 0);
+    }
+
+    #[test]
+    fn test_set_current_with_hold_fraction_invalid() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+
+        for fraction in [-0.1, 1.01, f32::NAN] {
+            assert_eq!(
+                driver.set_current_with_hold_fraction(16, fraction, 0),
+                Err(Error::InvalidArgument)
+            );
+        }
+        assert!(driver.uart().writes().is_empty());
+    }
+
+    #[test]
+    fn test_cache_otp() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::OtpRead as usize] = 0x1234;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.cache_otp().unwrap().raw(), 0x1234);
+        assert_eq!(driver.uart().reads, 1);
+
+        let otp = driver.read_register::<OtpRead>().unwrap();
+        assert_eq!(otp.raw(), 0x1234);
+        assert_eq!(driver.uart().reads, 1);
+
+        driver.invalidate_cache();
+        driver.read_register::<OtpRead>().unwrap();
+        assert_eq!(driver.uart().reads, 2);
+    }
+
+    #[test]
+    fn test_set_multistep_filter() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x0C1;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.multistep_filter(), Ok(false));
+        driver.set_multistep_filter(true).unwrap();
+        assert_eq!(driver.multistep_filter(), Ok(true));
+        driver.set_multistep_filter(false).unwrap();
+        assert_eq!(driver.uart().writes(), &[(0x00, 0x1C1), (0x00, 0x0C1)]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_set_multistep_filter_async() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C1;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        block_on(driver.set_multistep_filter_async(false)).unwrap();
+        assert_eq!(block_on(driver.multistep_filter_async()), Ok(false));
+        assert_eq!(driver.uart().writes(), &[(0x00, 0x0C1)]);
+    }
+
+    #[test]
+    fn test_open_load_valid() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::DrvStatus as usize] = 1 << 6; // OLA
+        for tstep in [0xFFFFF, 2000, 500] {
+            uart.script(Address::Tstep as u8, tstep);
+        }
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_open_load_tstep_threshold(1000);
+
+        // Standstill and slow motion are not trusted
+        assert_eq!(driver.open_load_valid(), Ok(None));
+        assert_eq!(driver.open_load_valid(), Ok(None));
+        assert_eq!(driver.uart().reads, 2);
+
+        assert_eq!(driver.open_load_valid(), Ok(Some((true, false))));
+        assert_eq!(driver.uart().reads, 4);
+    }
+
+    #[test]
+    fn test_set_reply_delay() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_reply_delay(4).unwrap();
+        driver.set_reply_delay(20).unwrap();
+        driver.set_reply_delay_bit_times(24).unwrap();
+        assert_eq!(
+            driver.uart().writes(),
+            &[(0x03, 0x0400), (0x03, 0x0F00), (0x03, 0x0200)]
+        );
+    }
+
+    #[test]
+    fn test_coil_currents_ma() {
+        let mut uart = MockUart::new();
+        // CUR_A = 248, CUR_B = -124
+        uart.regs[Address::Mscuract as usize] = ((-124i16 as u32 & 0x1FF) << 16) | 248;
+        uart.regs[Address::DrvStatus as usize] = 31 << 16;
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.coil_currents_ma(0.11), Ok((2955, -1477)));
+
+        // VSENSE=1 and CS_ACTUAL=15
+        driver.uart_mut().regs[Address::DrvStatus as usize] = 15 << 16;
+        driver.uart_mut().regs[Address::Chopconf as usize] = Chopconf::DEFAULT | 1 << 17;
+        let (a, b) = driver.coil_currents_ma(0.11).unwrap();
+        assert_eq!(a, coil_current_ma(248, 15, 0.11, true));
+        assert_eq!(b, coil_current_ma(-124, 15, 0.11, true));
+        assert!(a < 1477);
+    }
+
+    #[test]
+    fn test_coolstep_headroom() {
+        let mut uart = MockUart::new();
+        for cs_actual in [12, 20, 20] {
+            uart.script(Address::DrvStatus as u8, cs_actual << 16);
+        }
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.coolstep_headroom(20), Ok(8));
+        assert_eq!(driver.coolstep_headroom(20), Ok(0));
+        assert_eq!(driver.coolstep_headroom(16), Ok(0));
+    }
+
+    #[test]
+    fn test_coolstep_headroom_shadowed() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::DrvStatus as usize] = 10 << 16;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(
+            driver.coolstep_headroom_shadowed(),
+            Err(Error::UnknownValue(Address::IholdIrun as u8))
+        );
+        driver.set_current(24, 8, 6).unwrap();
+        assert_eq!(driver.coolstep_headroom_shadowed(), Ok(14));
+    }
+
+    #[test]
+    fn test_quiet_setup() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x04; // en_spreadcycle
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        uart.regs[Address::Pwmconf as usize] = Pwmconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        driver.quiet_setup(800, 0.11).unwrap();
+
+        let addresses = driver.uart().writes().iter().map(|w| w.0);
+        assert!(addresses.eq([0x00, 0x10, 0x11, 0x70, 0x6C]));
+
+        let gconf = driver.read_register::<Gconf>().unwrap();
+        assert!(gconf.pdn_disable());
+        assert!(gconf.mstep_reg_select());
+        assert!(!gconf.en_spreadcycle());
+
+        let (cs, vsense) = calculate_current_settings(800, 0.11).unwrap();
+        let ihold_irun = driver.last_written::<IholdIrun>().unwrap();
+        assert_eq!(ihold_irun.irun(), cs);
+        assert_eq!(ihold_irun.ihold(), cs / 2);
+        assert!(driver.last_written::<Tpowerdown>().unwrap().value() >= 2);
+
+        let pwmconf = driver.read_register::<Pwmconf>().unwrap();
+        assert!(pwmconf.pwm_autoscale());
+        assert!(pwmconf.pwm_autograd());
+
+        let chopconf = driver.read_chopconf().unwrap();
+        assert_eq!(chopconf.toff, 3);
+        assert_eq!(chopconf.mres, MicrostepResolution::M16);
+        assert!(chopconf.intpol);
+        assert_eq!(chopconf.vsense, vsense);
+    }
+
+    #[test]
+    fn test_quiet_setup_current_too_high() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(driver.quiet_setup(5000, 0.11), Err(Error::InvalidArgument));
+        assert!(driver.uart().writes().is_empty());
+    }
+
+    #[test]
+    fn test_ifcnt_delta_since() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Ifcnt as usize] = 250;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let start = driver.ifcnt().unwrap();
+        assert_eq!(driver.ifcnt_delta_since(start), Ok(0));
+        for _ in 0..8 {
+            driver.set_velocity(0).unwrap();
+        }
+        assert_eq!(driver.uart().regs[Address::Ifcnt as usize], 2);
+        assert_eq!(driver.ifcnt_delta_since(start), Ok(8));
+        assert_eq!(driver.ifcnt_delta_since(255), Ok(3));
+    }
+
+    #[test]
+    fn test_stability_check() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let report = driver.stability_check().unwrap();
+        assert!(report.is_stable());
+        assert_eq!(report.unstable().count(), 0);
+        assert_eq!(driver.uart().reads, 12);
+    }
+
+    #[test]
+    fn test_stability_check_flags_register() {
+        let mut uart = MockUart::new();
+        uart.script(Address::Chopconf as u8, 0x1000_0053);
+        uart.script(Address::Chopconf as u8, 0x1000_0153);
+        // Dynamic registers are not compared
+        uart.script(Address::Tstep as u8, 100);
+        uart.script(Address::Tstep as u8, 200);
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let report = driver.stability_check().unwrap();
+        assert!(!report.is_stable());
+        assert!(report.is_unstable(Address::Chopconf));
+        assert!(!report.is_unstable(Address::Gconf));
+        assert!(!report.is_unstable(Address::Tstep));
+        assert!(report.unstable().eq([Address::Chopconf]));
+    }
+
+    #[test]
+    fn test_prepare_homing() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C4; // en_spreadcycle
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let cfg = HomingConfig {
+            stall_threshold: 80,
+            tcoolthrs: 400,
+            ..HomingConfig::new()
+        };
+        driver.prepare_homing(&cfg).unwrap();
+
+        let mut chopconf = Chopconf::new();
+        chopconf.set_microstep_resolution(MicrostepResolution::M16);
+        assert_eq!(
+            driver.uart().writes(),
+            &[
+                (0x10, 0x0004_0C06),
+                (0x6C, chopconf.raw()),
+                (0x00, 0x1C0),
+                (0x40, 80),
+                (0x14, 400),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prepare_homing_rejects_preconditions() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let invalid = [
+            HomingConfig {
+                tcoolthrs: 0,
+                ..HomingConfig::new()
+            },
+            HomingConfig {
+                tcoolthrs: 0x10_0000,
+                ..HomingConfig::new()
+            },
+            HomingConfig {
+                run_current: 32,
+                ..HomingConfig::new()
+            },
+        ];
+        for cfg in &invalid {
+            assert_eq!(driver.prepare_homing(cfg), Err(Error::InvalidArgument));
+        }
+        assert!(driver.uart().writes().is_empty());
+    }
+
+    #[test]
+    fn test_is_uart_motion_active() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert!(!driver.is_uart_motion_active());
+
+        driver.set_velocity(-500).unwrap();
+        assert!(driver.is_uart_motion_active());
+        driver.set_velocity(0).unwrap();
+        assert!(!driver.is_uart_motion_active());
+        driver.set_velocity(1200).unwrap();
+        assert!(driver.is_uart_motion_active());
+
+        driver.reset_protocol_state();
+        assert!(!driver.is_uart_motion_active());
+    }
+
+    #[test]
+    fn test_revision() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Ioin as usize] = 0x2100_0000;
+        let mut driver = Tmc2209::new(uart, 0);
+        assert_eq!(driver.revision(), Ok(Revision::V0x21));
+
+        driver.uart_mut().regs[Address::Ioin as usize] = 0x3000_0000;
+        assert_eq!(driver.revision(), Ok(Revision::Unknown(0x30)));
+    }
+
+    #[test]
+    fn test_ioin_cache() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_ioin_cache(2);
+
+        driver.ioin().unwrap();
+        driver.ioin().unwrap();
+        driver.ioin().unwrap();
+        assert_eq!(driver.uart().reads, 1);
+
+        // Cache expired
+        driver.ioin().unwrap();
+        assert_eq!(driver.uart().reads, 2);
+    }
+
+    #[test]
+    fn test_verify_echo() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+
+        // Ignored unless enabled
+        driver.uart_mut().corrupt_echo = Some(2);
+        driver.stop().unwrap();
+
+        driver.set_verify_echo(true);
+        driver.ifcnt().unwrap();
+        driver.stop().unwrap();
+
+        driver.uart_mut().corrupt_echo = Some(5);
+        assert_eq!(driver.stop(), Err(Error::EchoMismatch));
+
+        driver.reset_protocol_state();
+        driver.uart_mut().corrupt_echo = Some(1);
+        assert_eq!(driver.ifcnt(), Err(Error::EchoMismatch));
+        // The reply to the failed read was drained
+        assert_eq!(driver.uart().pending_rx(), 0);
+        driver.ifcnt().unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_verify_echo_async() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_verify_echo(true);
+
+        driver.uart_mut().corrupt_echo = Some(1);
+        assert_eq!(block_on(driver.ifcnt_async()), Err(Error::EchoMismatch));
+        assert_eq!(driver.uart().pending_rx(), 0);
+        block_on(driver.ifcnt_async()).unwrap();
+    }
+
+    #[test]
+    fn test_with_boosted_current() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(
+            driver.with_boosted_current(31, |_| Ok(())),
+            Err(Error::UnknownValue(Address::IholdIrun as u8))
+        );
+
+        driver.set_current(16, 8, 4).unwrap();
+        let original = driver.uart().regs[Address::IholdIrun as usize];
+
+        driver
+            .with_boosted_current(28, |d| {
+                let reg = IholdIrun::from_raw(d.uart().regs[Address::IholdIrun as usize]);
+                assert_eq!(reg.irun(), 28);
+                assert_eq!(reg.ihold(), 8);
+                d.set_velocity(1000)
+            })
+            .unwrap();
+        assert_eq!(driver.uart().regs[Address::IholdIrun as usize], original);
+    }
+
+    #[test]
+    fn test_with_boosted_current_restores_on_error() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_current(16, 8, 4).unwrap();
+        let original = driver.uart().regs[Address::IholdIrun as usize];
+
+        let result = driver.with_boosted_current(31, |_| Err(Error::NoResponse));
+        assert_eq!(result, Err(Error::NoResponse));
+        assert_eq!(driver.uart().regs[Address::IholdIrun as usize], original);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_with_boosted_current_async() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(
+            block_on(driver.with_boosted_current_async(31, || async { Ok(()) })),
+            Err(Error::UnknownValue(Address::IholdIrun as u8))
+        );
+
+        driver.set_current(16, 8, 4).unwrap();
+        block_on(driver.with_boosted_current_async(28, || async { Ok(()) })).unwrap();
+        let result =
+            block_on(driver.with_boosted_current_async(31, || async { Err(Error::NoResponse) }));
+        assert_eq!(result, Err(Error::NoResponse));
+        assert_eq!(
+            driver.uart().writes(),
+            &[
+                (0x10, 0x0004_1008),
+                (0x10, 0x0004_1C08),
+                (0x10, 0x0004_1008),
+                (0x10, 0x0004_1F08),
+                (0x10, 0x0004_1008),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_echo_disabled() {
+        let mut uart = MockUart::new();
+        uart.echo = false;
+        uart.regs[Address::Gconf as usize] = 0x40;
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_echo_enabled(false);
+
+        driver.stop().unwrap();
+        assert_eq!(driver.read_register::<Gconf>().unwrap().raw(), 0x40);
+        assert_eq!(driver.uart().pending_rx(), 0);
+    }
+
+    #[test]
+    fn test_echo_enabled_without_echo() {
+        let mut uart = MockUart::new();
+        uart.echo = false;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        // The response is consumed as if it were the echo
+        assert!(driver.read_register::<Gconf>().is_err());
+    }
+
+    #[test]
+    fn test_init_check_reset() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gstat as usize] = 0x03; // reset + drv_err
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.init_check_reset(), Ok(true));
+        assert_eq!(driver.uart().writes(), &[(0x01, 0x01)]);
+        assert_eq!(driver.uart().regs[Address::Gstat as usize], 0x02);
+
+        assert_eq!(driver.init_check_reset(), Ok(false));
+        assert_eq!(driver.uart().writes().len(), 1);
+    }
+
+    #[test]
+    fn test_protections_disabled() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = 0x1000_0053;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.protections_disabled(), Ok((false, false)));
+        driver.safe_protections().unwrap();
+        assert!(driver.uart().writes().is_empty());
+
+        for (raw, expected) in [
+            (0x5000_0053, (true, false)),
+            (0x9000_0053, (false, true)),
+            (0xD000_0053, (true, true)),
+        ] {
+            driver.uart_mut().regs[Address::Chopconf as usize] = raw;
+            assert_eq!(driver.protections_disabled(), Ok(expected));
+            driver.safe_protections().unwrap();
+            assert_eq!(driver.uart().writes().last(), Some(&(0x6C, 0x1000_0053)));
+            assert_eq!(driver.protections_disabled(), Ok((false, false)));
+        }
+    }
+
+    #[test]
+    fn test_configure_velocity_bands() {
+        use crate::util::DEFAULT_FCLK;
+
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = 0x0400_0000; // MRES = 16
+        let mut driver = Tmc2209::new(uart, 0);
+
+        driver.configure_velocity_bands(500.0, 100.0).unwrap();
+        let tpwmthrs = velocity_to_tpwmthrs(500.0, 16, DEFAULT_FCLK);
+        let tcoolthrs = velocity_to_tpwmthrs(100.0, 16, DEFAULT_FCLK);
+        assert!(tpwmthrs < tcoolthrs);
+        assert_eq!(
+            driver.uart().writes(),
+            &[(0x13, tpwmthrs), (0x14, tcoolthrs)]
+        );
+
+        // Inverted and empty bands are rejected without writing
+        assert_eq!(
+            driver.configure_velocity_bands(100.0, 500.0),
+            Err(Error::InvalidThresholdOrder)
+        );
+        assert_eq!(
+            driver.configure_velocity_bands(200.0, 200.0),
+            Err(Error::InvalidThresholdOrder)
+        );
+        assert_eq!(driver.uart().writes().len(), 2);
+    }
+
+    #[test]
+    fn test_freeze_stealthchop_tuning() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Pwmconf as usize] = Pwmconf::DEFAULT;
+        uart.regs[Address::PwmAuto as usize] = (42 << 16) | 87; // grad=42, ofs=87
+        let mut driver = Tmc2209::new(uart, 0);
+
+        driver.freeze_stealthchop_tuning().unwrap();
+        let pwmconf = Pwmconf::from_raw(driver.uart().regs[Address::Pwmconf as usize]);
+        assert_eq!(pwmconf.pwm_ofs(), 87);
+        assert_eq!(pwmconf.pwm_grad(), 42);
+        assert!(!pwmconf.pwm_autoscale());
+        assert!(!pwmconf.pwm_autograd());
+        // Other fields are preserved
+        let default = Pwmconf::new();
+        assert_eq!(pwmconf.pwm_freq(), default.pwm_freq());
+        assert_eq!(pwmconf.pwm_reg(), default.pwm_reg());
+        assert_eq!(pwmconf.pwm_lim(), default.pwm_lim());
+    }
+
+    #[test]
+    fn test_last_written() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(driver.last_written::<Vactual>(), None);
+
+        driver.set_velocity(-1200).unwrap();
+        driver.set_stall_threshold(42).unwrap();
+        driver.set_coolstep_threshold(500).unwrap();
+        driver.enable_coolstep(5, 2).unwrap();
+        driver.write_raw(Address::Tpwmthrs as u8, 300).unwrap();
+        driver.clear_gstat().unwrap();
+
+        assert_eq!(driver.last_written::<Vactual>().unwrap().velocity(), -1200);
+        assert_eq!(driver.last_written::<Sgthrs>().unwrap().threshold(), 42);
+        assert_eq!(driver.last_written::<Tcoolthrs>().unwrap().threshold(), 500);
+        let coolconf = driver.last_written::<Coolconf>().unwrap();
+        assert_eq!((coolconf.semin(), coolconf.semax()), (5, 2));
+        assert_eq!(driver.last_written::<Tpwmthrs>().unwrap().raw(), 300);
+        assert_eq!(driver.last_written::<Gstat>(), None);
+        assert_eq!(driver.last_written::<IholdIrun>(), None);
+
+        driver.set_velocity(0).unwrap();
+        assert_eq!(driver.last_written::<Vactual>().unwrap().velocity(), 0);
+    }
+
+    #[test]
+    fn test_set_current_scaled() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let run = CurrentScale::new(20).unwrap();
+        let hold = CurrentScale::new(8).unwrap();
+        driver.set_current_scaled(run, hold, 4).unwrap();
+
+        let reg = IholdIrun::from_raw(driver.uart().regs[Address::IholdIrun as usize]);
+        assert_eq!(reg.irun_scale(), run);
+        assert_eq!(reg.ihold_scale(), hold);
+        assert_eq!(reg.iholddelay(), 4);
+    }
+
+    #[test]
+    fn test_load_and_current() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::SgResult as usize] = 312;
+        uart.regs[Address::DrvStatus as usize] = 0x8014_0000; // stst, CS_ACTUAL=20
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(driver.load_and_current(), Ok((312, 20)));
+        assert_eq!(driver.uart().reads, 2);
+    }
+
+    #[test]
+    fn test_set_default_toff() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT & !0x0F;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        driver.set_default_toff(5).unwrap();
+        driver.set_enabled(true).unwrap();
+        let chopconf = Chopconf::from_raw(driver.uart().regs[Address::Chopconf as usize]);
+        assert_eq!(chopconf.toff(), 5);
+
+        // An enabled driver keeps its TOFF
+        driver.set_default_toff(8).unwrap();
+        driver.set_enabled(true).unwrap();
+        let chopconf = Chopconf::from_raw(driver.uart().regs[Address::Chopconf as usize]);
+        assert_eq!(chopconf.toff(), 5);
+
+        driver.set_enabled(false).unwrap();
+        driver.set_enabled(true).unwrap();
+        let chopconf = Chopconf::from_raw(driver.uart().regs[Address::Chopconf as usize]);
+        assert_eq!(chopconf.toff(), 8);
+    }
+
+    #[test]
+    fn test_set_default_toff_invalid() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(driver.set_default_toff(0).map_err(|e| e.value), Err(0));
+        assert_eq!(driver.set_default_toff(16).map_err(|e| e.value), Err(16));
+        assert_eq!(driver.default_toff, 3);
+    }
+
+    #[test]
+    fn test_inter_byte_delay() {
+        use core::cell::Cell;
+        let delays = Cell::new(0);
+
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_stall_threshold(10).unwrap();
+        assert_eq!(driver.uart().write_calls, 1);
+
+        let mut driver = driver.with_inter_byte_delay(|| delays.set(delays.get() + 1));
+        driver.set_stall_threshold(20).unwrap();
+        assert_eq!(driver.uart().write_calls, 1 + 8);
+        assert_eq!(delays.get(), 7);
+        assert_eq!(driver.ifcnt(), Ok(2));
+        assert_eq!(driver.uart().write_calls, 1 + 8 + 4);
+        assert_eq!(delays.get(), 7 + 3);
+        // Settings and shadowed values survive the conversion
+        assert_eq!(
+            driver.last_written::<Sgthrs>().map(|r| r.threshold()),
+            Some(20)
+        );
+
+        driver.clear_inter_byte_delay();
+        driver.set_stall_threshold(30).unwrap();
+        assert_eq!(driver.uart().write_calls, 1 + 8 + 4 + 1);
+        assert_eq!(delays.get(), 7 + 3);
+    }
+
+    #[test]
+    fn test_set_inter_byte_delay_fn() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_inter_byte_delay(|| {});
+        driver.set_stall_threshold(10).unwrap();
+        assert_eq!(driver.uart().write_calls, 8);
+    }
+
+    #[test]
+    fn test_calibrate_stall_threshold() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        for sg in [310, 290, 252, 301] {
+            driver.uart_mut().script(Address::SgResult as u8, sg);
+        }
+
+        assert_eq!(driver.calibrate_stall_threshold(800, 4), Ok(100));
+        assert_eq!(
+            driver.uart().writes(),
+            &[(0x22, 800), (0x22, 0), (0x40, 100)]
+        );
+        assert_eq!(driver.uart().reads, 4);
+
+        assert_eq!(
+            driver.calibrate_stall_threshold(800, 0),
+            Err(Error::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_start_motion() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let standstill = Tstep::MAX_VALUE;
+        driver.uart_mut().script(Address::Tstep as u8, standstill);
+        driver.uart_mut().script(Address::Tstep as u8, standstill);
+        driver.uart_mut().script(Address::Tstep as u8, 2000);
+
+        let mut delays = 0;
+        driver.start_motion(1000, 5, || delays += 1).unwrap();
+        assert_eq!(delays, 2);
+        assert_eq!(driver.uart().reads, 3);
+        assert_eq!(driver.uart().writes(), &[(0x22, 1000)]);
+    }
+
+    #[test]
+    fn test_start_motion_fails() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.uart_mut().regs[Address::Tstep as usize] = Tstep::MAX_VALUE;
+
+        let mut delays = 0;
+        assert_eq!(
+            driver.start_motion(1000, 3, || delays += 1),
+            Err(Error::MotionFailedToStart)
+        );
+        assert_eq!(delays, 2);
+        assert_eq!(driver.uart().reads, 3);
+
+        // Stopping does not poll
+        driver.start_motion(0, 3, || {}).unwrap();
+        assert_eq!(driver.uart().reads, 3);
+        assert_eq!(driver.uart().writes().len(), 2);
+
+        assert_eq!(
+            driver.start_motion(1000, 0, || {}),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(driver.uart().writes().len(), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_start_motion_async() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver
+            .uart_mut()
+            .script(Address::Tstep as u8, Tstep::MAX_VALUE);
+        driver.uart_mut().script(Address::Tstep as u8, 500);
+
+        block_on(driver.start_motion_async(-200, 4, || async {})).unwrap();
+        assert_eq!(driver.uart().reads, 2);
+
+        assert_eq!(
+            block_on(driver.start_motion_async(-200, 0, || async {})),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(driver.uart().writes().len(), 1);
+    }
+
+    #[test]
+    fn test_set_velocity_sps_limit() {
+        use crate::util::DEFAULT_FCLK;
+
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.uart_mut().regs[Address::Chopconf as usize] = 0x0400_0000; // MRES = 16
+        driver.set_max_velocity(100.0);
+
+        driver.set_velocity_sps(-100.0).unwrap();
+        let expected = velocity_to_vactual(-100.0, 16, DEFAULT_FCLK);
+        assert_eq!(
+            driver.uart().regs[Address::Vactual as usize] & 0xFF_FFFF,
+            expected as u32 & 0xFF_FFFF
+        );
+
+        let writes = driver.uart().writes().len();
+        assert_eq!(driver.set_velocity_sps(150.0), Err(Error::VelocityLimited));
+        assert_eq!(
+            driver.set_velocity_sps(f32::NAN),
+            Err(Error::VelocityLimited)
+        );
+        assert_eq!(driver.uart().writes().len(), writes);
+
+        driver.clear_max_velocity();
+        assert!(driver.set_velocity_sps(150.0).is_ok());
+    }
+
+    #[test]
+    fn test_set_crc_poly() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_crc_poly(0x31);
+
+        // The genuine chip rejects the request
+        assert_eq!(driver.ifcnt(), Err(Error::NoResponse));
+
+        let tx = driver.uart().tx();
+        assert_eq!(tx[3], crate::crc::compute_with(&tx[..3], 0x31));
+
+        driver.reset_protocol_state();
+        driver.set_crc_poly(crate::crc::POLY);
+        assert_eq!(driver.ifcnt(), Ok(0));
+    }
+
+    #[test]
+    fn test_bus_stats() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+
+        // Disabled by default
+        driver.ifcnt().unwrap();
+        assert_eq!(driver.stats(), BusStats::default());
+
+        driver.enable_stats();
+        driver.uart_mut().corrupt_crc = 1;
+        assert_eq!(driver.ifcnt(), Err(Error::CrcMismatch));
+        driver.ifcnt().unwrap();
+        driver.stop().unwrap();
+
+        // Nobody answers at slave address 1
+        driver.set_slave_addr(1);
+        assert_eq!(driver.ifcnt(), Err(Error::NoResponse));
+
+        let stats = driver.stats();
+        assert_eq!(stats.reads, 1);
+        assert_eq!(stats.writes, 1);
+        assert_eq!(stats.crc_errors, 1);
+        assert_eq!(stats.no_response, 1);
+        assert_eq!(stats.retries, 0);
+
+        // A CRC error absorbed by a retry
+        driver.set_slave_addr(0);
+        driver.set_read_retries(1);
+        driver.uart_mut().corrupt_crc = 1;
+        driver.ifcnt().unwrap();
+        let stats = driver.stats();
+        assert_eq!(stats.reads, 2);
+        // The failed attempt is still counted
+        assert_eq!(stats.crc_errors, 2);
+        assert_eq!(stats.retries, 1);
+
+        driver.reset_stats();
+        assert_eq!(driver.stats(), BusStats::default());
+    }
+
+    #[test]
+    fn test_reset_protocol_state() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.enable_stats();
+        driver.ifcnt().unwrap();
+
+        // Leave the reader in the middle of a frame
+        driver.reader.feed::<()>(&[SYNC, MASTER_ADDR, 0x02]);
+        assert_eq!(driver.reader.buffered(), 3);
+
+        driver.reset_protocol_state();
+        assert_eq!(driver.reader.buffered(), 0);
+        assert_eq!(driver.reader.pending_bytes(), 0);
+        assert_eq!(driver.stats(), BusStats::default());
+        driver.ifcnt().unwrap();
+    }
+
+    #[test]
+    fn test_write_raw_read_only() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+
+        assert_eq!(
+            driver.write_raw(Address::Ifcnt as u8, 0),
+            Err(Error::NotWritable(0x02))
+        );
+        assert_eq!(
+            driver.write_raw(Address::Tstep as u8, 0),
+            Err(Error::NotWritable(0x12))
+        );
+        assert_eq!(driver.uart().tx_len, 0);
+    }
+
+    #[test]
+    fn test_write_raw_unknown_address() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+
+        driver.write_raw(0x50, 0x1234).unwrap();
+        assert_eq!(driver.uart().writes(), &[(0x50, 0x1234)]);
+    }
+
+    #[test]
+    fn test_write_register_readback() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let mut chopconf = Chopconf::new();
+        chopconf.set_toff(4).set_tbl(2);
+        driver.write_register_readback(&chopconf).unwrap();
+        assert_eq!(driver.uart().reads, 1);
+
+        // Reserved bits reading back differently are not a mismatch
+        driver
+            .uart_mut()
+            .script(Address::Chopconf as u8, chopconf.raw() | 0x0000_1000);
+        driver.write_register_readback(&chopconf).unwrap();
+    }
+
+    #[test]
+    fn test_write_register_readback_mismatch() {
+        let mut uart = MockUart::new();
+        uart.script(Address::Gconf as u8, 0x0000_0001);
+        let mut driver = Tmc2209::new(uart, 0);
+        let mut gconf = Gconf::new();
+        gconf.set_pdn_disable(true);
+        assert_eq!(
+            driver.write_register_readback(&gconf),
+            Err(Error::VerifyMismatch {
+                expected: gconf.raw(),
+                actual: 0x0000_0001
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_config() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let mut cfg = Config::new();
+        cfg.ihold_irun.set_irun(20).set_ihold(10);
+        cfg.chopconf.set_toff(4);
+        driver.apply_config(&cfg).unwrap();
+
+        let writes = driver.uart().writes();
+        assert!(writes
+            .iter()
+            .map(|&(addr, _)| addr)
+            .eq([0x00, 0x10, 0x11, 0x13, 0x14, 0x40, 0x42, 0x6C, 0x70]));
+        assert_eq!(writes[1].1, cfg.ihold_irun.raw());
+        assert_eq!(writes[7].1, cfg.chopconf.raw());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_apply_config_async_progress() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let cfg = Config::new();
+        let mut seen = [0u8; 9];
+        let mut count = 0;
+        block_on(driver.apply_config_async(&cfg, |addr| {
+            seen[count] = addr as u8;
+            count += 1;
+        }))
+        .unwrap();
+
+        assert_eq!(count, 9);
+        assert!(driver
+            .uart()
+            .writes()
+            .iter()
+            .map(|&(addr, _)| addr)
+            .eq(seen));
+    }
+
+    #[test]
+    fn test_disable_gracefully() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        for status in [0, 0, 1 << 31] {
+            uart.script(Address::DrvStatus as u8, status);
+        }
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_current(20, 8, 4).unwrap();
+
+        let mut delays = 0;
+        driver.disable_gracefully(|| delays += 1).unwrap();
+        assert_eq!(delays, 2);
+
+        let writes = driver.uart().writes();
+        assert_eq!(&writes[1..3], &[(0x22, 0), (0x10, 0x0004_0808)]);
+        assert_eq!(writes[3].0, 0x6C);
+        assert_eq!(writes[3].1 & 0x0F, 0);
+    }
+
+    #[test]
+    fn test_disable_gracefully_timeout() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let mut delays = 0;
+        assert_eq!(
+            driver.disable_gracefully(|| delays += 1),
+            Err(Error::StandstillTimeout)
+        );
+        assert_eq!(delays, STANDSTILL_POLLS - 1);
+        // Still enabled, only VACTUAL was written
+        assert_eq!(driver.uart().writes(), &[(0x22, 0)]);
     }
 
-    /// Read the actual motor current scale being used.
-    ///
-    /// This reflects CoolStep adjustments if enabled.
-    /// Returns a value from 0-31.
-    pub fn actual_current_scale(&mut self) -> Result<u8, Error<E>> {
-        let status = self.drv_status()?;
-        Ok(status.cs_actual())
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_disable_gracefully_async() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        uart.script(Address::DrvStatus as u8, 0);
+        uart.script(Address::DrvStatus as u8, 1 << 31);
+        let mut driver = Tmc2209::new(uart, 0);
+
+        block_on(driver.disable_gracefully_async(|| async {})).unwrap();
+        // No IHOLD_IRUN shadow, so the current is left alone
+        let writes = driver.uart().writes();
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes[1].0, 0x6C);
     }
 
-    /// Check if the driver is currently in StealthChop mode.
-    pub fn is_stealthchop_active(&mut self) -> Result<bool, Error<E>> {
-        let status = self.drv_status()?;
-        Ok(status.stealth())
+    #[test]
+    fn test_export_config() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C0;
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_current(16, 8, 6).unwrap();
+        driver.enable_spreadcycle().unwrap();
+        driver.set_microsteps(MicrostepResolution::M8).unwrap();
+        driver.set_stall_threshold(40).unwrap();
+
+        let cfg = driver.export_config();
+        assert_eq!(cfg.ihold_irun.irun(), 16);
+        assert_eq!(cfg.ihold_irun.ihold(), 8);
+        assert!(cfg.gconf.en_spreadcycle());
+        assert_eq!(cfg.chopconf.microstep_resolution(), MicrostepResolution::M8);
+        assert_eq!(cfg.sgthrs.threshold(), 40);
+        // Never written, so at the reset value
+        assert_eq!(cfg.pwmconf, Pwmconf::new());
+
+        // Restoring on a fresh chip reproduces the written registers
+        let mut restored = Tmc2209::new(MockUart::new(), 0);
+        restored.apply_config(&cfg).unwrap();
+        assert_eq!(restored.export_config(), cfg);
     }
 
-    /// Get a comprehensive status summary.
-    ///
-    /// Returns a tuple of (errors_present, warnings_present, is_running).
-    pub fn status_summary(&mut self) -> Result<(bool, bool, bool), Error<E>> {
-        let status = self.drv_status()?;
+    #[test]
+    fn test_set_fclk() {
+        let mut uart = MockUart::new();
+        // MRES = 16
+        uart.regs[Address::Chopconf as usize] = 0x0400_0000;
+        // At fclk = 6 MHz: 6 MHz / (16 * 750) = 500 steps/s
+        uart.regs[Address::Tstep as usize] = 750;
+        let mut driver = Tmc2209::new(uart, 0);
+        assert_eq!(driver.fclk(), DEFAULT_FCLK);
 
-        let errors = status.has_error();
-        let warnings = status.otpw() || status.open_load_detected();
-        let running = !status.stst();
+        driver.set_fclk(DEFAULT_FCLK / 2);
+        driver.set_velocity_sps(100.0).unwrap();
+        assert_eq!(
+            driver.uart().regs[Address::Vactual as usize] as i32,
+            velocity_to_vactual(100.0, 16, DEFAULT_FCLK / 2)
+        );
+        match driver.velocity_state().unwrap() {
+            VelocityState::Moving { sps } => assert!((sps - 500.0).abs() < 0.01),
+            other => panic!("unexpected {:?}", other),
+        }
 
-        Ok((errors, warnings, running))
+        driver.configure_velocity_bands(500.0, 100.0).unwrap();
+        let writes = driver.uart().writes();
+        assert_eq!(
+            writes[writes.len() - 2..],
+            [
+                (0x13, velocity_to_tpwmthrs(500.0, 16, DEFAULT_FCLK / 2)),
+                (0x14, velocity_to_tpwmthrs(100.0, 16, DEFAULT_FCLK / 2)),
+            ]
+        );
     }
-}
 
-// ============================================================================
-// Async API
-// ============================================================================
+    #[test]
+    fn test_detect_slave_address() {
+        let mut uart = MockUart::new();
+        uart.slave = 1;
+        let mut driver = Tmc2209::new(uart, 0);
 
-#[cfg(feature = "async")]
-impl<U, E> Tmc2209<U>
-where
-    U: embedded_io_async::Read<Error = E> + embedded_io_async::Write<Error = E>,
-{
-    /// Read a register (async).
-    ///
-    /// Sends a read request and waits for the response.
-    pub async fn read_register_async<R: ReadableRegister>(&mut self) -> Result<R, Error<E>> {
-        let request = self.read_request::<R>();
+        assert_eq!(driver.detect_slave_address(), Ok(1));
+        assert_eq!(driver.slave_addr(), 1);
+        assert_eq!(driver.uart().reads, 1);
+        driver.stop().unwrap();
+        assert_eq!(driver.uart().writes(), &[(0x22, 0)]);
+    }
 
-        // Send the read request
-        self.uart
-            .write_all(request.as_bytes())
-            .await
-            .map_err(Error::Uart)?;
-        self.uart.flush().await.map_err(Error::Uart)?;
+    #[test]
+    fn test_detect_slave_address_none() {
+        let mut uart = MockUart::new();
+        uart.slave = 7; // Never matches a valid address
+        let mut driver = Tmc2209::new(uart, 2);
 
-        // Skip the echo (4 bytes)
-        let mut echo_buf = [0u8; 4];
-        self.read_exact_async(&mut echo_buf).await?;
+        assert_eq!(driver.detect_slave_address(), Err(Error::NoResponse));
+        assert_eq!(driver.slave_addr(), 2);
+    }
 
-        // Read the response
-        let response = self.read_response_async().await?;
+    #[test]
+    fn test_detect_slave_address_keeps_shadow() {
+        let mut uart = MockUart::new();
+        uart.slave = 2;
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_current(20, 10, 6).unwrap();
 
-        // Verify the register address matches
-        let expected_addr = R::ADDRESS as u8;
-        if response.reg_addr() != expected_addr {
-            return Err(Error::AddressMismatch {
-                expected: expected_addr,
-                actual: response.reg_addr(),
-            });
-        }
+        assert_eq!(driver.detect_slave_address(), Ok(2));
+        assert_eq!(driver.written_irun::<()>(), Ok(20));
+    }
 
-        Ok(R::from(response.data()))
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_detect_slave_address_async() {
+        let mut uart = MockUart::new();
+        uart.slave = 3;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        assert_eq!(block_on(driver.detect_slave_address_async()), Ok(3));
+        assert_eq!(driver.slave_addr(), 3);
     }
 
-    /// Write a register (async).
-    ///
-    /// Sends a write request to update a register value.
-    pub async fn write_register_async<R: WritableRegister>(
-        &mut self,
-        reg: &R,
-    ) -> Result<(), Error<E>> {
-        let request = self.write_request(reg);
+    #[test]
+    fn test_read_retries() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C0;
+        uart.corrupt_crc = 2;
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.enable_stats();
 
-        // Send the write request
-        self.uart
-            .write_all(request.as_bytes())
-            .await
-            .map_err(Error::Uart)?;
-        self.uart.flush().await.map_err(Error::Uart)?;
+        // Not retried by default
+        assert_eq!(driver.ifcnt(), Err(Error::CrcMismatch));
 
-        // Read back the echo (8 bytes)
-        let mut echo_buf = [0u8; 8];
-        self.read_exact_async(&mut echo_buf).await?;
+        driver.set_read_retries(2);
+        assert_eq!(driver.read_register::<Gconf>().unwrap().raw(), 0x1C0);
+        assert_eq!(driver.uart().reads, 3);
+        assert_eq!(driver.stats().retries, 1);
+    }
 
-        Ok(())
+    #[test]
+    fn test_retry_address_mismatch() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C0;
+        uart.misaddressed = 1;
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_read_retries(1);
+
+        // Fatal unless enabled
+        assert_eq!(
+            driver.read_register::<Gconf>(),
+            Err(Error::AddressMismatch {
+                expected: 0x00,
+                actual: 0x01
+            })
+        );
+        assert_eq!(driver.uart().reads, 1);
+
+        driver.uart_mut().misaddressed = 1;
+        driver.set_retry_address_mismatch(true);
+        assert_eq!(driver.read_register::<Gconf>().unwrap().raw(), 0x1C0);
+        assert_eq!(driver.uart().reads, 3);
     }
 
-    /// Read a register by raw address (async).
-    pub async fn read_raw_async(&mut self, reg_addr: u8) -> Result<u32, Error<E>> {
-        let request = ReadRequest::from_raw_addr(self.slave_addr, reg_addr);
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_retry_address_mismatch_async() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C0;
+        uart.misaddressed = 1;
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_read_retries(1);
+        driver.set_retry_address_mismatch(true);
 
-        self.uart
-            .write_all(request.as_bytes())
-            .await
-            .map_err(Error::Uart)?;
-        self.uart.flush().await.map_err(Error::Uart)?;
+        let gconf = block_on(driver.read_register_async::<Gconf>()).unwrap();
+        assert_eq!(gconf.raw(), 0x1C0);
+        assert_eq!(driver.uart().reads, 2);
+    }
 
-        // Skip echo
-        let mut echo_buf = [0u8; 4];
-        self.read_exact_async(&mut echo_buf).await?;
+    #[test]
+    fn test_read_gconf_decoded() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C0;
+        let mut driver = Tmc2209::new(uart, 0);
 
-        let response = self.read_response_async().await?;
-        Ok(response.data())
+        let gconf = driver.read_gconf_decoded().unwrap();
+        assert!(gconf.pdn_disable);
+        assert!(gconf.mstep_reg_select);
+        assert!(gconf.multistep_filt);
+        assert!(!gconf.en_spreadcycle);
+        assert_eq!(gconf.index, crate::registers::IndexOutput::FirstMicrostep);
     }
 
-    /// Write a register by raw address (async).
-    pub async fn write_raw_async(&mut self, reg_addr: u8, data: u32) -> Result<(), Error<E>> {
-        let request = WriteRequest::from_raw(self.slave_addr, reg_addr, data);
+    #[test]
+    fn test_velocity_rpm() {
+        let mut uart = MockUart::new();
+        // MRES = 16
+        uart.regs[Address::Chopconf as usize] = 0x0400_0000;
+        // 60 RPM at 200 steps/rev is 200 steps/s: 12 MHz / (16 * 200)
+        uart.regs[Address::Tstep as usize] = 3750;
+        let mut driver = Tmc2209::new(uart, 0);
 
-        self.uart
-            .write_all(request.as_bytes())
-            .await
-            .map_err(Error::Uart)?;
-        self.uart.flush().await.map_err(Error::Uart)?;
+        driver.set_velocity_rpm(60.0, 200).unwrap();
+        assert_eq!(
+            driver.uart().regs[Address::Vactual as usize] as i32,
+            velocity_to_vactual(200.0, 16, DEFAULT_FCLK)
+        );
+        let rpm = driver.measured_rpm(200).unwrap();
+        assert!((rpm - 60.0).abs() < 0.01);
+
+        driver.uart_mut().regs[Address::Tstep as usize] = Tstep::MAX_VALUE;
+        assert_eq!(driver.measured_rpm(200), Ok(0.0));
+        assert_eq!(
+            driver.set_velocity_rpm(60.0, 0),
+            Err(Error::InvalidArgument)
+        );
+    }
 
-        // Read back echo
-        let mut echo_buf = [0u8; 8];
-        self.read_exact_async(&mut echo_buf).await?;
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_velocity_rpm_async() {
+        let mut uart = MockUart::new();
+        // MRES = 16
+        uart.regs[Address::Chopconf as usize] = 0x0400_0000;
+        // 150 RPM at 400 steps/rev is 1000 steps/s: 12 MHz / (16 * 1000)
+        uart.regs[Address::Tstep as usize] = 750;
+        let mut driver = Tmc2209::new(uart, 0);
 
-        Ok(())
+        block_on(driver.set_velocity_rpm_async(-150.0, 400)).unwrap();
+        let vactual = driver.uart().regs[Address::Vactual as usize];
+        assert_eq!(
+            vactual & 0xFF_FFFF,
+            velocity_to_vactual(-1000.0, 16, DEFAULT_FCLK) as u32 & 0xFF_FFFF
+        );
+        let rpm = block_on(driver.measured_rpm_async(400)).unwrap();
+        assert!((rpm - 150.0).abs() < 0.01);
     }
 
-    /// Helper to read exact number of bytes (async).
-    async fn read_exact_async(&mut self, buf: &mut [u8]) -> Result<(), Error<E>> {
-        let mut total_read = 0;
-        while total_read < buf.len() {
-            let n = self
-                .uart
-                .read(&mut buf[total_read..])
-                .await
-                .map_err(Error::Uart)?;
-            if n == 0 {
-                return Err(Error::NoResponse);
-            }
-            total_read += n;
-        }
-        Ok(())
+    #[test]
+    fn test_microstep_phase() {
+        let mut uart = MockUart::new();
+        uart.script(Address::Mscnt as u8, 768);
+        let mut driver = Tmc2209::new(uart, 0);
+        assert_eq!(driver.microstep_phase(), Ok(0.75));
     }
 
-    /// Helper to read a complete response (async).
-    async fn read_response_async(&mut self) -> Result<ReadResponse, Error<E>> {
-        self.reader.reset();
-        let mut buf = [0u8; 8];
-        self.read_exact_async(&mut buf).await?;
+    #[test]
+    fn test_chopper_warnings() {
+        let mut uart = MockUart::new();
+        // Reset value: TOFF=3, HSTRT=5, HEND=0 (-3), TBL=0
+        uart.regs[Address::Chopconf as usize] = Chopconf::DEFAULT;
+        let mut driver = Tmc2209::new(uart, 0);
 
-        let (_, result) = self.reader.feed(&buf);
-        result.ok_or(Error::NoResponse)?
+        let warnings = driver.chopper_warnings().unwrap();
+        assert!(warnings
+            .iter()
+            .eq([crate::registers::ChopperWarning::BlankTimeTooLow]));
     }
 
-    // ========================================================================
-    // Convenience methods (async)
-    // ========================================================================
+    #[test]
+    fn test_transact_raw() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x1C0;
+        let mut driver = Tmc2209::new(uart, 0);
 
-    /// Check if the driver is communicating properly (async).
-    pub async fn is_connected_async(&mut self) -> bool {
-        self.read_register_async::<Ifcnt>().await.is_ok()
+        // Hand-built read request for GCONF
+        let mut request = [SYNC, 0x00, 0x00, 0x00];
+        request[3] = crate::crc::compute(&request[..3]);
+        let mut response = [0u8; 8];
+        driver.transact_raw(&request, &mut response).unwrap();
+        assert_eq!(&response[..7], &[SYNC, MASTER_ADDR, 0x00, 0, 0, 0x01, 0xC0]);
+        assert_eq!(
+            ReadResponse::from_bytes::<()>(response).unwrap().data(),
+            0x1C0
+        );
+
+        // Hand-built write request for SGTHRS has no reply
+        let mut request = [SYNC, 0x00, 0x40 | 0x80, 0, 0, 0, 42, 0];
+        request[7] = crate::crc::compute(&request[..7]);
+        let mut response = [0xAA; 8];
+        driver.transact_raw(&request, &mut response).unwrap();
+        assert_eq!(response, [0xAA; 8]);
+        assert_eq!(driver.uart().writes(), &[(0x40, 42)]);
+        assert_eq!(driver.uart().pending_rx(), 0);
+
+        assert_eq!(
+            driver.transact_raw(&request[..5], &mut response),
+            Err(Error::InvalidArgument)
+        );
     }
 
-    /// Get the interface transmission counter (async).
-    pub async fn ifcnt_async(&mut self) -> Result<u8, Error<E>> {
-        let reg = self.read_register_async::<Ifcnt>().await?;
-        Ok(reg.count())
+    #[test]
+    fn test_configure_coolstep_dynamics() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.enable_coolstep(5, 2).unwrap();
+        driver.configure_coolstep_dynamics(3, 1, true).unwrap();
+
+        let coolconf = driver.last_written::<Coolconf>().unwrap();
+        assert_eq!((coolconf.semin(), coolconf.semax()), (5, 2));
+        assert_eq!((coolconf.seup(), coolconf.sedn()), (3, 1));
+        assert!(coolconf.seimin());
+
+        // Changing the thresholds keeps the dynamics
+        driver.enable_coolstep(8, 4).unwrap();
+        let coolconf = driver.last_written::<Coolconf>().unwrap();
+        assert_eq!((coolconf.semin(), coolconf.semax()), (8, 4));
+        assert_eq!((coolconf.seup(), coolconf.sedn()), (3, 1));
+        assert!(coolconf.seimin());
+
+        // Out of range values are rejected without writing
+        let writes = driver.uart().writes().len();
+        assert_eq!(
+            driver.configure_coolstep_dynamics(4, 0, false),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(
+            driver.configure_coolstep_dynamics(0, 4, false),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(driver.uart().writes().len(), writes);
     }
 
-    /// Get the driver status (async).
-    pub async fn drv_status_async(&mut self) -> Result<DrvStatus, Error<E>> {
-        self.read_register_async().await
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_configure_coolstep_dynamics_async() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        block_on(driver.enable_coolstep_async(6, 3)).unwrap();
+        block_on(driver.configure_coolstep_dynamics_async(2, 2, false)).unwrap();
+
+        let coolconf = driver.last_written::<Coolconf>().unwrap();
+        assert_eq!((coolconf.semin(), coolconf.semax()), (6, 3));
+        assert_eq!((coolconf.seup(), coolconf.sedn()), (2, 2));
     }
 
-    /// Set the motor currents (async).
-    pub async fn set_current_async(
-        &mut self,
-        run_current: u8,
-        hold_current: u8,
-        hold_delay: u8,
-    ) -> Result<(), Error<E>> {
-        let mut reg = IholdIrun::new();
-        reg.set_irun(run_current)
-            .set_ihold(hold_current)
-            .set_iholddelay(hold_delay);
-        self.write_register_async(&reg).await
+    #[test]
+    fn test_motor_state() {
+        let mut uart = MockUart::new();
+        // CUR_A = -20 (9-bit two's complement), CUR_B = 245
+        uart.script(Address::Mscuract as u8, (245 << 16) | (0x200 - 20));
+        uart.script(Address::Mscnt as u8, 384);
+        uart.script(Address::Tstep as u8, 1000);
+        uart.script(Address::SgResult as u8, 210);
+        // CS_ACTUAL = 17, stealth and OTPW set
+        uart.script(Address::DrvStatus as u8, (1 << 30) | (17 << 16) | 1);
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let state = driver.motor_state(12_000_000).unwrap();
+        assert_eq!(state.cur_a, -20);
+        assert_eq!(state.cur_b, 245);
+        assert_eq!(state.mscnt, 384);
+        assert_eq!(state.tstep, 1000);
+        assert_eq!(state.usteps_per_sec, Some(12_000.0));
+        assert_eq!(state.sg_result, 210);
+        assert_eq!(state.cs_actual, 17);
+        assert!(state.drv_status().stealth());
+        assert!(state.drv_status().otpw());
+        assert_eq!(driver.uart().reads, 5);
+
+        driver.uart_mut().script(Address::Tstep as u8, 0xFFFFF);
+        let state = driver.motor_state(12_000_000).unwrap();
+        assert_eq!(state.tstep, 0xFFFFF);
+        assert_eq!(state.usteps_per_sec, None);
     }
 
-    /// Set the microstep resolution (async).
-    pub async fn set_microsteps_async(
-        &mut self,
-        resolution: MicrostepResolution,
-    ) -> Result<(), Error<E>> {
-        let mut chopconf = self.read_register_async::<Chopconf>().await?;
-        chopconf.set_microstep_resolution(resolution);
-        self.write_register_async(&chopconf).await
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_motor_state_async() {
+        let mut uart = MockUart::new();
+        uart.script(Address::Mscnt as u8, 768);
+        uart.script(Address::Tstep as u8, 500);
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let state = block_on(driver.motor_state_async(12_000_000)).unwrap();
+        assert_eq!(state.mscnt, 768);
+        assert_eq!(state.usteps_per_sec, Some(24_000.0));
+        assert_eq!(driver.uart().reads, 5);
     }
 
-    /// Set velocity for internal motion controller (async).
-    pub async fn set_velocity_async(&mut self, velocity: i32) -> Result<(), Error<E>> {
-        let mut reg = Vactual::new();
-        reg.set_velocity(velocity);
-        self.write_register_async(&reg).await
+    #[test]
+    fn test_set_standstill_behavior() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(
+            driver.set_standstill_behavior(8, StandstillMode::Normal),
+            Err(Error::UnknownValue(Address::IholdIrun as u8))
+        );
+        assert!(driver.uart().writes().is_empty());
+
+        driver.set_current(20, 10, 6).unwrap();
+        for mode in [
+            StandstillMode::Freewheeling,
+            StandstillMode::StrongBraking,
+            StandstillMode::Braking,
+            StandstillMode::Normal,
+        ] {
+            driver.set_standstill_behavior(8, mode).unwrap();
+            let pwmconf = Pwmconf::from_raw(driver.uart().regs[Address::Pwmconf as usize]);
+            assert_eq!(pwmconf.standstill_mode(), mode);
+            let reg = IholdIrun::from_raw(driver.uart().regs[Address::IholdIrun as usize]);
+            let expected = if mode == StandstillMode::Normal { 8 } else { 0 };
+            assert_eq!(reg.ihold(), expected);
+            assert_eq!(reg.irun(), 20);
+            assert_eq!(reg.iholddelay(), 6);
+        }
     }
 
-    /// Stop the motor (async).
-    pub async fn stop_async(&mut self) -> Result<(), Error<E>> {
-        self.set_velocity_async(0).await
+    #[test]
+    fn test_set_standstill_behavior_hold_current_coordination() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_current(20, 10, 6).unwrap();
+
+        // Out-of-range hold current is only rejected when it is used
+        driver
+            .set_standstill_behavior(40, StandstillMode::Freewheeling)
+            .unwrap();
+        let reg = IholdIrun::from_raw(driver.uart().regs[Address::IholdIrun as usize]);
+        assert_eq!(reg.ihold(), 0);
+
+        let writes = driver.uart().writes_len;
+        assert_eq!(
+            driver.set_standstill_behavior(40, StandstillMode::Normal),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(driver.uart().writes_len, writes);
     }
 
-    // ========================================================================
-    // CoolStep and StallGuard methods (async)
-    // ========================================================================
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_set_standstill_behavior_async() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_current(20, 10, 6).unwrap();
+        block_on(driver.set_standstill_behavior_async(8, StandstillMode::Braking)).unwrap();
+        let pwmconf = Pwmconf::from_raw(driver.uart().regs[Address::Pwmconf as usize]);
+        assert_eq!(pwmconf.standstill_mode(), StandstillMode::Braking);
+        let reg = IholdIrun::from_raw(driver.uart().regs[Address::IholdIrun as usize]);
+        assert_eq!(reg.ihold(), 0);
+    }
 
-    /// Enable CoolStep adaptive current control (async).
-    pub async fn enable_coolstep_async(&mut self, semin: u8, semax: u8) -> Result<(), Error<E>> {
-        let mut coolconf = Coolconf::new();
-        coolconf
-            .set_semin(semin.min(15))
-            .set_semax(semax.min(15))
-            .set_seup(0)
-            .set_sedn(0);
-        self.write_register_async(&coolconf).await
+    #[test]
+    fn test_confirm_enabled() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = 0x1000_0050;
+        let mut driver = Tmc2209::new(uart, 0);
+        assert!(!driver.confirm_enabled().unwrap());
+
+        driver.uart_mut().regs[Address::Chopconf as usize] = 0x1000_0053;
+        assert!(driver.confirm_enabled().unwrap());
+
+        driver.set_enabled(false).unwrap();
+        assert!(!driver.confirm_enabled().unwrap());
     }
 
-    /// Disable CoolStep (async).
-    pub async fn disable_coolstep_async(&mut self) -> Result<(), Error<E>> {
-        let coolconf = Coolconf::new();
-        self.write_register_async(&coolconf).await
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_confirm_enabled_async() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Chopconf as usize] = 0x1000_0053;
+        let mut driver = Tmc2209::new(uart, 0);
+        assert!(block_on(driver.confirm_enabled_async()).unwrap());
     }
 
-    /// Set the CoolStep velocity threshold (async).
-    pub async fn set_coolstep_threshold_async(&mut self, threshold: u32) -> Result<(), Error<E>> {
-        let mut tcoolthrs = Tcoolthrs::new();
-        tcoolthrs.set_threshold(threshold);
-        self.write_register_async(&tcoolthrs).await
+    #[test]
+    fn test_coolstep_min_current_ma() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let full = cs_to_current(31, 0.11, false);
+
+        // SEIMIN=0 (reset value): half of IRUN
+        assert_eq!(driver.coolstep_min_current_ma(31, 0.11), Ok(full / 2));
+
+        driver.configure_coolstep_dynamics(0, 0, true).unwrap();
+        assert_eq!(driver.coolstep_min_current_ma(31, 0.11), Ok(full / 4));
+
+        driver.configure_coolstep_dynamics(0, 0, false).unwrap();
+        assert_eq!(driver.coolstep_min_current_ma(31, 0.11), Ok(full / 2));
+
+        assert_eq!(
+            driver.coolstep_min_current_ma(32, 0.11),
+            Err(Error::InvalidArgument)
+        );
     }
 
-    /// Set the StealthChop velocity threshold (async).
-    pub async fn set_stealthchop_threshold_async(
-        &mut self,
-        threshold: u32,
-    ) -> Result<(), Error<E>> {
-        let mut tpwmthrs = Tpwmthrs::new();
-        tpwmthrs.set_threshold(threshold);
-        self.write_register_async(&tpwmthrs).await
+    #[test]
+    fn test_coolstep_min_current_ma_vsense() {
+        let mut uart = MockUart::new();
+        let mut chopconf = Chopconf::new();
+        chopconf.set_vsense(true);
+        uart.regs[Address::Chopconf as usize] = chopconf.raw();
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.configure_coolstep_dynamics(0, 0, true).unwrap();
+
+        let full = cs_to_current(16, 0.11, true);
+        assert_eq!(driver.coolstep_min_current_ma(16, 0.11), Ok(full / 4));
     }
 
-    // ========================================================================
-    // Sensorless homing methods (async)
-    // ========================================================================
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_coolstep_min_current_ma_async() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.configure_coolstep_dynamics(0, 0, true).unwrap();
+        let full = cs_to_current(20, 0.11, false);
+        assert_eq!(
+            block_on(driver.coolstep_min_current_ma_async(20, 0.11)),
+            Ok(full / 4)
+        );
+    }
 
-    /// Configure stall detection for sensorless homing (async).
-    pub async fn configure_stall_detection_async(&mut self, threshold: u8) -> Result<(), Error<E>> {
-        // Set StallGuard threshold
-        let mut sgthrs = Sgthrs::new();
-        sgthrs.set_threshold(threshold);
-        self.write_register_async(&sgthrs).await?;
+    #[test]
+    fn test_program_otp_field() {
+        let mut uart = MockUart::new();
+        let mask = OtpField::EnSpreadcycle.otp_read_mask();
+        uart.script(Address::OtpRead as u8, 0x0000_000F);
+        uart.script(Address::OtpRead as u8, 0x0000_000F | mask);
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.cache_otp().unwrap();
 
-        // Enable DIAG output for stall indication
-        let mut gconf = self.read_register_async::<Gconf>().await?;
-        gconf.set_diag0_stall(true);
-        self.write_register_async(&gconf).await?;
+        let mut waited = false;
+        driver
+            .program_otp_field(OtpField::EnSpreadcycle, OtpConfirm::irreversible(), || {
+                waited = true
+            })
+            .unwrap();
+        assert!(waited);
+        assert_eq!(driver.uart().writes(), &[(Address::OtpProg as u8, 0xBD27)]);
 
-        Ok(())
+        // The cache holds the programmed value
+        let reads = driver.uart().reads;
+        let otp = driver.read_register::<OtpRead>().unwrap();
+        assert!(otp.otp_en_spreadcycle());
+        assert_eq!(driver.uart().reads, reads);
+
+        // Already programmed: nothing is written
+        driver
+            .program_otp_field(OtpField::EnSpreadcycle, OtpConfirm::irreversible(), || {})
+            .unwrap();
+        assert_eq!(driver.uart().writes().len(), 1);
     }
 
-    /// Check if the motor is currently stalled (async).
-    pub async fn is_stalled_async(&mut self) -> Result<bool, Error<E>> {
-        let sg = self.read_register_async::<SgResult>().await?;
-        Ok(sg.result() == 0)
+    #[test]
+    fn test_program_otp_field_not_set() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(
+            driver.program_otp_field(OtpField::Ottrim, OtpConfirm::irreversible(), || {}),
+            Err(Error::VerifyMismatch {
+                expected: 1 << 5,
+                actual: 0,
+            })
+        );
+        assert_eq!(driver.uart().writes(), &[(Address::OtpProg as u8, 0xBD05)]);
     }
 
-    /// Get the current load indicator from StallGuard (async).
-    pub async fn load_indicator_async(&mut self) -> Result<u16, Error<E>> {
-        let sg = self.read_register_async::<SgResult>().await?;
-        Ok(sg.result())
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_program_otp_field_async() {
+        let mut uart = MockUart::new();
+        uart.script(Address::OtpRead as u8, 0);
+        uart.script(Address::OtpRead as u8, OtpField::PwmOfs.otp_read_mask());
+        let mut driver = Tmc2209::new(uart, 0);
+        block_on(driver.program_otp_field_async(
+            OtpField::PwmOfs,
+            OtpConfirm::irreversible(),
+            || async {},
+        ))
+        .unwrap();
+        assert_eq!(driver.uart().writes(), &[(Address::OtpProg as u8, 0xBD20)]);
     }
 
-    // ========================================================================
-    // Mode selection (async)
-    // ========================================================================
+    #[test]
+    fn test_read_register_counted() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x40;
+        let mut driver = Tmc2209::new(uart, 0);
 
-    /// Enable StealthChop mode (async).
-    pub async fn enable_stealthchop_async(&mut self) -> Result<(), Error<E>> {
-        let mut gconf = self.read_register_async::<Gconf>().await?;
-        gconf.set_en_spreadcycle(false);
-        self.write_register_async(&gconf).await
+        // Whole echo and response in one read each
+        let (gconf, reads) = driver.read_register_counted::<Gconf>().unwrap();
+        assert_eq!(gconf.raw(), 0x40);
+        assert_eq!(reads, 2);
+
+        // 4-byte echo + 8-byte response in chunks of 3
+        driver.uart_mut().chunk = 3;
+        assert_eq!(driver.read_register_counted::<Gconf>().unwrap().1, 2 + 3);
+
+        // One byte per read
+        driver.uart_mut().chunk = 1;
+        assert_eq!(driver.read_register_counted::<Gconf>().unwrap().1, 12);
+
+        // Retries are included
+        driver.uart_mut().chunk = usize::MAX;
+        driver.uart_mut().corrupt_crc = 1;
+        driver.set_read_retries(1);
+        assert_eq!(driver.read_register_counted::<Gconf>().unwrap().1, 4);
     }
 
-    /// Enable SpreadCycle mode (async).
-    pub async fn enable_spreadcycle_async(&mut self) -> Result<(), Error<E>> {
-        let mut gconf = self.read_register_async::<Gconf>().await?;
-        gconf.set_en_spreadcycle(true);
-        self.write_register_async(&gconf).await
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_read_register_counted_async() {
+        let mut uart = MockUart::new();
+        uart.chunk = 4;
+        let mut driver = Tmc2209::new(uart, 0);
+        let (_, reads) = block_on(driver.read_register_counted_async::<Gconf>()).unwrap();
+        assert_eq!(reads, 3);
     }
 
-    /// Enable or disable the driver (async).
-    pub async fn set_enabled_async(&mut self, enabled: bool) -> Result<(), Error<E>> {
-        let mut chopconf = self.read_register_async::<Chopconf>().await?;
-        if enabled {
-            if chopconf.toff() == 0 {
-                chopconf.set_toff(3);
-            }
-        } else {
-            chopconf.set_toff(0);
+    #[test]
+    fn test_stall_margin() {
+        let mut uart = MockUart::new();
+        for sg in [250, 200, 120] {
+            uart.script(Address::SgResult as u8, sg);
         }
-        self.write_register_async(&chopconf).await
+        let mut driver = Tmc2209::new(uart, 0);
+        assert_eq!(
+            driver.stall_margin(),
+            Err(Error::UnknownValue(Address::Sgthrs as u8))
+        );
+        assert_eq!(driver.uart().reads, 0);
+
+        // Trip point 200
+        driver.set_stall_threshold(100).unwrap();
+        assert_eq!(driver.stall_margin(), Ok(50));
+        assert_eq!(driver.stall_margin(), Ok(0));
+        assert_eq!(driver.stall_margin(), Ok(-80));
+
+        // Largest trip point with the largest SG_RESULT
+        driver.set_stall_threshold(255).unwrap();
+        driver.uart_mut().script(Address::SgResult as u8, 510);
+        assert_eq!(driver.stall_margin(), Ok(0));
     }
 
-    /// Check if motor is in standstill (async).
-    pub async fn is_standstill_async(&mut self) -> Result<bool, Error<E>> {
-        let status = self.drv_status_async().await?;
-        Ok(status.stst())
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_stall_margin_async() {
+        let mut uart = MockUart::new();
+        uart.script(Address::SgResult as u8, 30);
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_stall_threshold(40).unwrap();
+        assert_eq!(block_on(driver.stall_margin_async()), Ok(-50));
+    }
+
+    #[test]
+    fn test_verify_expected_mode() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::DrvStatus as usize] = 1 << 30;
+        let mut driver = Tmc2209::new(uart, 0);
+        assert_eq!(
+            driver.verify_expected_mode(ChopperMode::StealthChop),
+            Ok(())
+        );
+        assert_eq!(
+            driver.verify_expected_mode(ChopperMode::SpreadCycle),
+            Err(Error::UnexpectedChopperMode {
+                expected: ChopperMode::SpreadCycle,
+                actual: ChopperMode::StealthChop,
+            })
+        );
+
+        // Above TPWMTHRS the chip has switched to SpreadCycle
+        driver.uart_mut().regs[Address::DrvStatus as usize] = 0;
+        assert_eq!(
+            driver.verify_expected_mode(ChopperMode::SpreadCycle),
+            Ok(())
+        );
+        assert_eq!(
+            driver.verify_expected_mode(ChopperMode::StealthChop),
+            Err(Error::UnexpectedChopperMode {
+                expected: ChopperMode::StealthChop,
+                actual: ChopperMode::SpreadCycle,
+            })
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_verify_expected_mode_async() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        assert_eq!(
+            block_on(driver.verify_expected_mode_async(ChopperMode::StealthChop)),
+            Err(Error::UnexpectedChopperMode {
+                expected: ChopperMode::StealthChop,
+                actual: ChopperMode::SpreadCycle,
+            })
+        );
     }
 }
@@ -2,6 +2,8 @@
 
 use core::fmt;
 
+use crate::registers::ChopperMode;
+
 /// Errors that can occur during TMC2209 communication.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -29,6 +31,42 @@ pub enum Error<E> {
     BufferTooSmall,
     /// No response received (timeout or no data).
     NoResponse,
+    /// Register at this address is read-only.
+    NotWritable(u8),
+    /// A write to this register address did not increment IFCNT.
+    WriteNotAcknowledged(u8),
+    /// A method argument is outside its valid range.
+    InvalidArgument,
+    /// The echoed request differs from the transmitted one.
+    EchoMismatch,
+    /// The value of this write-only register is unknown because it has not
+    /// been written through the driver.
+    UnknownValue(u8),
+    /// The requested velocity exceeds the configured maximum.
+    VelocityLimited,
+    /// Unexpected bytes followed a response, suggesting that more than
+    /// one chip answered.
+    BusContention,
+    /// TSTEP stayed at standstill after a non-zero VACTUAL was written.
+    MotionFailedToStart,
+    /// TPWMTHRS would not be below TCOOLTHRS.
+    InvalidThresholdOrder,
+    /// A register read back after a write differs in its documented fields.
+    VerifyMismatch {
+        /// The value written.
+        expected: u32,
+        /// The value read back.
+        actual: u32,
+    },
+    /// The motor did not reach standstill in time.
+    StandstillTimeout,
+    /// DRV_STATUS reports a different chopper mode than expected.
+    UnexpectedChopperMode {
+        /// The mode the driver was expected to be in.
+        expected: ChopperMode,
+        /// The mode reported by the driver.
+        actual: ChopperMode,
+    },
 }
 
 impl<E> Error<E> {
@@ -49,6 +87,77 @@ impl<E> Error<E> {
             Error::InvalidSlaveAddress(addr) => Error::InvalidSlaveAddress(addr),
             Error::BufferTooSmall => Error::BufferTooSmall,
             Error::NoResponse => Error::NoResponse,
+            Error::NotWritable(addr) => Error::NotWritable(addr),
+            Error::WriteNotAcknowledged(addr) => Error::WriteNotAcknowledged(addr),
+            Error::InvalidArgument => Error::InvalidArgument,
+            Error::EchoMismatch => Error::EchoMismatch,
+            Error::UnknownValue(addr) => Error::UnknownValue(addr),
+            Error::VelocityLimited => Error::VelocityLimited,
+            Error::BusContention => Error::BusContention,
+            Error::MotionFailedToStart => Error::MotionFailedToStart,
+            Error::InvalidThresholdOrder => Error::InvalidThresholdOrder,
+            Error::VerifyMismatch { expected, actual } => {
+                Error::VerifyMismatch { expected, actual }
+            }
+            Error::StandstillTimeout => Error::StandstillTimeout,
+            Error::UnexpectedChopperMode { expected, actual } => {
+                Error::UnexpectedChopperMode { expected, actual }
+            }
+        }
+    }
+
+    /// Get a compact numeric code for the error kind.
+    ///
+    /// Variant payloads (including the UART error) are not encoded. The
+    /// codes are stable: existing variants keep their code and new
+    /// variants get the next free one. 0 is never used.
+    ///
+    /// | Code | Variant |
+    /// |------|---------|
+    /// | 1 | `Uart` |
+    /// | 2 | `CrcMismatch` |
+    /// | 3 | `InvalidSync` |
+    /// | 4 | `InvalidMasterAddress` |
+    /// | 5 | `AddressMismatch` |
+    /// | 6 | `UnknownAddress` |
+    /// | 7 | `InvalidSlaveAddress` |
+    /// | 8 | `BufferTooSmall` |
+    /// | 9 | `NoResponse` |
+    /// | 10 | `NotWritable` |
+    /// | 11 | `WriteNotAcknowledged` |
+    /// | 12 | `InvalidArgument` |
+    /// | 13 | `EchoMismatch` |
+    /// | 14 | `UnknownValue` |
+    /// | 15 | `VelocityLimited` |
+    /// | 16 | `BusContention` |
+    /// | 17 | `MotionFailedToStart` |
+    /// | 18 | `InvalidThresholdOrder` |
+    /// | 19 | `VerifyMismatch` |
+    /// | 20 | `StandstillTimeout` |
+    /// | 21 | `UnexpectedChopperMode` |
+    pub fn code(&self) -> u8 {
+        match self {
+            Error::Uart(_) => 1,
+            Error::CrcMismatch => 2,
+            Error::InvalidSync => 3,
+            Error::InvalidMasterAddress => 4,
+            Error::AddressMismatch { .. } => 5,
+            Error::UnknownAddress(_) => 6,
+            Error::InvalidSlaveAddress(_) => 7,
+            Error::BufferTooSmall => 8,
+            Error::NoResponse => 9,
+            Error::NotWritable(_) => 10,
+            Error::WriteNotAcknowledged(_) => 11,
+            Error::InvalidArgument => 12,
+            Error::EchoMismatch => 13,
+            Error::UnknownValue(_) => 14,
+            Error::VelocityLimited => 15,
+            Error::BusContention => 16,
+            Error::MotionFailedToStart => 17,
+            Error::InvalidThresholdOrder => 18,
+            Error::VerifyMismatch { .. } => 19,
+            Error::StandstillTimeout => 20,
+            Error::UnexpectedChopperMode { .. } => 21,
         }
     }
 }
@@ -73,6 +182,78 @@ impl<E: fmt::Debug> fmt::Display for Error<E> {
             }
             Error::BufferTooSmall => write!(f, "Response buffer too small"),
             Error::NoResponse => write!(f, "No response received"),
+            Error::NotWritable(addr) => write!(f, "Register 0x{:02X} is read-only", addr),
+            Error::WriteNotAcknowledged(addr) => {
+                write!(f, "Write to register 0x{:02X} not acknowledged", addr)
+            }
+            Error::InvalidArgument => write!(f, "Argument out of range"),
+            Error::EchoMismatch => write!(f, "Echo does not match the transmitted request"),
+            Error::UnknownValue(addr) => {
+                write!(f, "Register 0x{:02X} has not been written yet", addr)
+            }
+            Error::VelocityLimited => write!(f, "Velocity exceeds the configured maximum"),
+            Error::BusContention => write!(f, "Unexpected bytes after response (bus contention)"),
+            Error::MotionFailedToStart => write!(f, "Motor did not start moving"),
+            Error::InvalidThresholdOrder => {
+                write!(f, "TPWMTHRS must be below TCOOLTHRS")
+            }
+            Error::VerifyMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Read-back mismatch: wrote 0x{:08X}, read 0x{:08X}",
+                    expected, actual
+                )
+            }
+            Error::StandstillTimeout => write!(f, "Motor did not reach standstill"),
+            Error::UnexpectedChopperMode { expected, actual } => {
+                write!(f, "Chopper mode is {:?}, expected {:?}", actual, expected)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_distinct() {
+        let errors: [Error<()>; 21] = [
+            Error::Uart(()),
+            Error::CrcMismatch,
+            Error::InvalidSync,
+            Error::InvalidMasterAddress,
+            Error::AddressMismatch {
+                expected: 0,
+                actual: 1,
+            },
+            Error::UnknownAddress(0),
+            Error::InvalidSlaveAddress(4),
+            Error::BufferTooSmall,
+            Error::NoResponse,
+            Error::NotWritable(0),
+            Error::WriteNotAcknowledged(0),
+            Error::InvalidArgument,
+            Error::EchoMismatch,
+            Error::UnknownValue(0),
+            Error::VelocityLimited,
+            Error::BusContention,
+            Error::MotionFailedToStart,
+            Error::InvalidThresholdOrder,
+            Error::VerifyMismatch {
+                expected: 0,
+                actual: 1,
+            },
+            Error::StandstillTimeout,
+            Error::UnexpectedChopperMode {
+                expected: ChopperMode::StealthChop,
+                actual: ChopperMode::SpreadCycle,
+            },
+        ];
+        for (i, error) in errors.iter().enumerate() {
+            // Codes follow declaration order starting at 1
+            assert_eq!(error.code() as usize, i + 1);
         }
+        assert_eq!(Error::Uart(42u8).code(), Error::<()>::Uart(()).code());
     }
 }
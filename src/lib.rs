@@ -6,6 +6,10 @@
 //! - Full register definitions for all 24 TMC2209 registers
 //! - Type-safe register access with bitfield getters/setters
 //! - High-level `Tmc2209` driver struct for UART communication
+//! - `Tmc2209Bus` for several chips sharing one UART
+//! - `MotionController` state machine for homing and VACTUAL motion
+//! - `Tmc2209Transport` and `Tmc2209TransportAsync` for packet-oriented links such
+//!   as USB bridges
 //! - Blocking API (feature `blocking`)
 //! - Async API (feature `async`)
 //! - Utility functions for current/velocity calculations
@@ -15,6 +19,9 @@
 //! - `blocking` (default): Enable blocking UART API using `embedded_io` traits
 //! - `async`: Enable async UART API using `embedded_io_async` traits
 //! - `defmt`: Enable `defmt::Format` derives for debugging
+//! - `embedded-hal`: Enable [`Tmc2209WithDiag`] for reading the DIAG pin and
+//!   [`StepDir`] for STEP/DIR motion
+//! - `serde`: Enable `serde` derives for [`MotorState`]
 //!
 //! ## Example
 //!
@@ -54,30 +61,64 @@
 #![no_std]
 #![warn(missing_docs)]
 
+pub mod bus;
 pub mod crc;
 pub mod datagram;
+#[cfg(feature = "embedded-hal")]
+pub mod diag;
 pub mod driver;
 pub mod error;
+pub mod motion;
 pub mod registers;
+#[cfg(feature = "embedded-hal")]
+pub mod step_dir;
+pub mod telemetry;
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub mod transport;
 pub mod util;
 
+#[cfg(all(test, feature = "blocking"))]
+mod mock;
+
 // Re-export main types at crate root
-pub use driver::Tmc2209;
+pub use bus::Tmc2209Bus;
+#[cfg(feature = "embedded-hal")]
+pub use diag::Tmc2209WithDiag;
+pub use driver::{BusStats, Config, Health, HomingConfig, StabilityReport, Tmc2209};
 pub use error::Error;
+pub use motion::{MotionController, MotionEvent, MotionState};
+#[cfg(feature = "embedded-hal")]
+pub use step_dir::StepDir;
+pub use telemetry::{MotorState, RegisterPoller, SgLogger};
+#[cfg(feature = "blocking")]
+pub use transport::Tmc2209Transport;
+#[cfg(feature = "async")]
+pub use transport::Tmc2209TransportAsync;
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub use transport::TransportUart;
 
 // Re-export commonly used register types
 pub use registers::{
-    Address, Chopconf, Coolconf, DrvStatus, FactoryConf, Gconf, Gstat, Ifcnt, IholdIrun, Ioin,
-    MicrostepResolution, Mscnt, Mscuract, OtpProg, OtpRead, Pwmconf, PwmAuto, PwmScale,
-    ReadableRegister, Register, SgResult, Sgthrs, Slaveconf, StandstillMode, Tcoolthrs, Tpowerdown,
+    Address, Chopconf, ChopconfDecoded, ChopperMode, ChopperWarning, ChopperWarnings, Coolconf,
+    CurrentScale, DrvStatus, FactoryConf, FaultReport, FieldError, Gconf, GconfDecoded, Gstat,
+    Ifcnt, IholdIrun, IndexOutput, Ioin, IoinState, MicrostepResolution, Mscnt, Mscuract,
+    OtpConfirm, OtpField, OtpProg, OtpRead, Pwmconf, PwmAuto, PwmScale, ReadableRegister, Register,
+    Revision, SgResult, Sgthrs, Slaveconf, StandstillMode, Tcoolthrs, ThermalStatus, Tpowerdown,
     Tpwmthrs, Tstep, Vactual, WritableRegister,
 };
 
 // Re-export utility functions
 pub use util::{
-    calculate_current_settings, cs_to_current, current_to_cs, optimal_vsense, tstep_to_velocity,
-    velocity_to_tpwmthrs, velocity_to_vactual, DEFAULT_FCLK, DEFAULT_RSENSE,
+    calculate_current_settings, calculate_current_settings_with, coil_current_ma, cs_to_current,
+    current_headroom, current_to_cs, estimated_power_dissipation_mw, microstep_frequency_hz,
+    mscnt_delta, mscnt_shortest_delta, optimal_vsense, recommend_stall_threshold,
+    sg_to_load_percent, tstep_to_velocity, vactual_scale, vactual_to_usteps_per_sec,
+    velocity_to_tpwmthrs, velocity_to_vactual, velocity_to_vactual_checked, CurrentAdvice,
+    CurrentSenseParams, VelocityState, DEFAULT_FCLK, DEFAULT_RSENSE, MULTISTEP_FILT_THRESHOLD_HZ,
+    STALL_THRESHOLD_MARGIN_PERCENT,
 };
 
 // Re-export datagram types for advanced usage
-pub use datagram::{ReadRequest, ReadResponse, ResponseReader, WriteRequest, MASTER_ADDR, SYNC};
+pub use datagram::{
+    response_crc_ok, ReadRequest, ReadResponse, ResponseReader, WriteRequest, MASTER_ADDR, SYNC,
+};
@@ -0,0 +1,297 @@
+//! Scripted UART used by the driver tests.
+//!
+//! `MockUart` emulates a single-wire TMC2209 bus: every transmitted byte is
+//! echoed back, read requests are answered from an internal register file,
+//! and write requests update that register file.
+
+#![allow(dead_code)]
+
+use crate::crc;
+use crate::datagram::{ADDRESS_MASK, MASTER_ADDR, SYNC, WRITE_BIT};
+
+/// Capacity of the byte buffers.
+const BUF_LEN: usize = 1024;
+/// Capacity of the write log and read script.
+const LOG_LEN: usize = 64;
+
+/// Emulated TMC2209 behind a single-wire UART.
+pub(crate) struct MockUart {
+    /// Register file indexed by address.
+    pub regs: [u32; 128],
+    /// Slave address the emulated chip answers to.
+    pub slave: u8,
+    /// Echo transmitted bytes back (single-wire topology).
+    pub echo: bool,
+    /// Maximum number of bytes returned by a single `read` call.
+    pub chunk: usize,
+    /// Number of `read` calls made by the driver.
+    pub read_calls: usize,
+    /// Number of `write` calls made by the driver.
+    pub write_calls: usize,
+    /// All bytes transmitted by the driver.
+    pub tx: [u8; BUF_LEN],
+    /// Number of valid bytes in `tx`.
+    pub tx_len: usize,
+    /// Register writes received, as `(address, data)`.
+    pub writes: [(u8, u32); LOG_LEN],
+    /// Number of valid entries in `writes`.
+    pub writes_len: usize,
+    /// Number of read requests answered.
+    pub reads: usize,
+    /// Register address to put in replies instead of the requested one.
+    pub reply_addr: Option<u8>,
+    /// Number of upcoming replies sent with a corrupted CRC.
+    pub corrupt_crc: usize,
+    /// Number of upcoming replies sent with the wrong register address.
+    pub misaddressed: usize,
+    /// Corrupt the echo of the byte this many bytes from now.
+    pub corrupt_echo: Option<usize>,
+    /// Answer read requests twice, as two chips sharing an address would.
+    pub duplicate_reply: bool,
+    rx: [u8; BUF_LEN],
+    rx_head: usize,
+    rx_tail: usize,
+    frame: [u8; 8],
+    frame_len: usize,
+    script: [(u8, u32); LOG_LEN],
+    script_len: usize,
+}
+
+impl MockUart {
+    /// Create an emulated chip at slave address 0 with all registers zeroed.
+    pub fn new() -> Self {
+        Self {
+            regs: [0; 128],
+            slave: 0,
+            echo: true,
+            chunk: usize::MAX,
+            read_calls: 0,
+            write_calls: 0,
+            tx: [0; BUF_LEN],
+            tx_len: 0,
+            writes: [(0, 0); LOG_LEN],
+            writes_len: 0,
+            reads: 0,
+            reply_addr: None,
+            corrupt_crc: 0,
+            misaddressed: 0,
+            corrupt_echo: None,
+            duplicate_reply: false,
+            rx: [0; BUF_LEN],
+            rx_head: 0,
+            rx_tail: 0,
+            frame: [0; 8],
+            frame_len: 0,
+            script: [(0, 0); LOG_LEN],
+            script_len: 0,
+        }
+    }
+
+    /// Queue a value returned by the next read of `addr`.
+    ///
+    /// Scripted values are consumed in order and also stored in the
+    /// register file, so later reads keep returning the last value.
+    pub fn script(&mut self, addr: u8, value: u32) {
+        self.script[self.script_len] = (addr, value);
+        self.script_len += 1;
+    }
+
+    /// Queue raw bytes for the driver to read.
+    pub fn push_rx(&mut self, bytes: &[u8]) {
+        if self.rx_head == self.rx_tail {
+            // Reuse the buffer once drained so long polling loops fit
+            self.rx_head = 0;
+            self.rx_tail = 0;
+        }
+        for &b in bytes {
+            self.rx[self.rx_tail] = b;
+            self.rx_tail += 1;
+        }
+    }
+
+    /// Register writes received so far.
+    pub fn writes(&self) -> &[(u8, u32)] {
+        &self.writes[..self.writes_len]
+    }
+
+    /// Bytes transmitted so far.
+    pub fn tx(&self) -> &[u8] {
+        &self.tx[..self.tx_len]
+    }
+
+    /// Number of bytes still waiting to be read.
+    pub fn pending_rx(&self) -> usize {
+        self.rx_tail - self.rx_head
+    }
+
+    fn next_value(&mut self, addr: u8) -> u32 {
+        if let Some(pos) = self.script[..self.script_len]
+            .iter()
+            .position(|&(a, _)| a == addr)
+        {
+            let (_, value) = self.script[pos];
+            self.script.copy_within(pos + 1..self.script_len, pos);
+            self.script_len -= 1;
+            self.regs[addr as usize] = value;
+        }
+        self.regs[addr as usize]
+    }
+
+    fn handle_byte(&mut self, byte: u8) {
+        if self.echo {
+            let echo = match self.corrupt_echo {
+                Some(0) => {
+                    self.corrupt_echo = None;
+                    byte ^ 0x01
+                }
+                Some(n) => {
+                    self.corrupt_echo = Some(n - 1);
+                    byte
+                }
+                None => byte,
+            };
+            self.push_rx(&[echo]);
+        }
+        if self.frame_len == 0 && byte != SYNC {
+            return;
+        }
+        self.frame[self.frame_len] = byte;
+        self.frame_len += 1;
+
+        let is_write = self.frame_len > 2 && self.frame[2] & WRITE_BIT != 0;
+        let complete = match self.frame_len {
+            4 => !is_write,
+            8 => true,
+            _ => false,
+        };
+        if !complete {
+            return;
+        }
+        let len = self.frame_len;
+        self.frame_len = 0;
+
+        if !crc::verify(&self.frame[..len]) || self.frame[1] != self.slave {
+            return;
+        }
+        let addr = self.frame[2] & ADDRESS_MASK;
+        if is_write {
+            let data =
+                u32::from_be_bytes([self.frame[3], self.frame[4], self.frame[5], self.frame[6]]);
+            if self.writes_len < LOG_LEN {
+                self.writes[self.writes_len] = (addr, data);
+                self.writes_len += 1;
+            }
+            if addr == 0x01 {
+                // GSTAT is write-1-to-clear
+                self.regs[1] &= !data;
+            } else {
+                self.regs[addr as usize] = data;
+            }
+            self.regs[0x02] = (self.regs[0x02] + 1) & 0xFF;
+        } else {
+            self.reads += 1;
+            let data = self.next_value(addr).to_be_bytes();
+            let mut reply = [
+                SYNC,
+                MASTER_ADDR,
+                self.reply_addr.unwrap_or(addr),
+                data[0],
+                data[1],
+                data[2],
+                data[3],
+                0,
+            ];
+            if self.misaddressed > 0 {
+                self.misaddressed -= 1;
+                reply[2] ^= 0x01;
+            }
+            reply[7] = crc::compute(&reply[..7]);
+            if self.corrupt_crc > 0 {
+                self.corrupt_crc -= 1;
+                reply[7] ^= 0xFF;
+            }
+            self.push_rx(&reply);
+            if self.duplicate_reply {
+                self.push_rx(&reply);
+            }
+        }
+    }
+}
+
+impl embedded_io::ErrorType for MockUart {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_io::Read for MockUart {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.read_calls += 1;
+        let n = buf.len().min(self.pending_rx()).min(self.chunk);
+        buf[..n].copy_from_slice(&self.rx[self.rx_head..self.rx_head + n]);
+        self.rx_head += n;
+        Ok(n)
+    }
+}
+
+impl embedded_io::ReadReady for MockUart {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.pending_rx() > 0)
+    }
+}
+
+impl embedded_io::Write for MockUart {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.write_calls += 1;
+        for &b in buf {
+            self.tx[self.tx_len] = b;
+            self.tx_len += 1;
+            self.handle_byte(b);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_io_async::Read for MockUart {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        embedded_io::Read::read(self, buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_io_async::Write for MockUart {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        embedded_io::Write::write(self, buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Poll a future to completion with a no-op waker.
+///
+/// The mock never returns `Pending`, so a busy loop is sufficient.
+#[cfg(feature = "async")]
+pub(crate) fn block_on<F: core::future::Future>(fut: F) -> F::Output {
+    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    fn noop_raw_waker() -> RawWaker {
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+    static VTABLE: RawWakerVTable =
+        RawWakerVTable::new(|_| noop_raw_waker(), |_| {}, |_| {}, |_| {});
+
+    // SAFETY: the vtable functions ignore the data pointer and do nothing.
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = core::pin::pin!(fut);
+    loop {
+        if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Supervisory state machine for VACTUAL motion.
+//!
+//! [`MotionController`] wraps a driver and tracks whether the motor is
+//! idle, homing against a stall, running, or stopped by a fault. Calling
+//! `poll` periodically reads the status registers, advances the state and
+//! reports what happened.
+
+use crate::driver::Tmc2209;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::error::Error;
+use crate::registers::FaultReport;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::registers::{DrvStatus, Gstat, SgResult, Vactual};
+
+/// State of a [`MotionController`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MotionState {
+    /// Motor not commanded to move.
+    Idle,
+    /// Moving until StallGuard reports a stall.
+    Homing,
+    /// Moving at a commanded velocity, or stopping after `stop`.
+    Running,
+    /// A driver fault stopped the motor; see `clear_fault`.
+    Fault,
+}
+
+/// Event reported by [`MotionController::poll`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MotionEvent {
+    /// Nothing changed.
+    NoEvent,
+    /// The homing move stalled; the motor was stopped.
+    Stalled,
+    /// A driver fault was detected; the motor was stopped.
+    FaultDetected(FaultReport),
+    /// The motor came to rest after `stop`.
+    ReachedStandstill,
+}
+
+/// TMC2209 driver with a motion state machine.
+///
+/// Motion is commanded through VACTUAL. Stalls are detected by polling
+/// SG_RESULT, so StallGuard must be configured (SGTHRS, TCOOLTHRS) before
+/// homing. SGTHRS must be written through the wrapped driver, since the
+/// stall trip point is taken from its last written value.
+pub struct MotionController<U, D = fn()> {
+    driver: Tmc2209<U, D>,
+    state: MotionState,
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    velocity: i32,
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    trip_point: i16,
+}
+
+impl<U, D> MotionController<U, D> {
+    /// Wrap a driver. The controller starts idle.
+    pub fn new(driver: Tmc2209<U, D>) -> Self {
+        Self {
+            driver,
+            state: MotionState::Idle,
+            #[cfg(any(feature = "blocking", feature = "async"))]
+            velocity: 0,
+            #[cfg(any(feature = "blocking", feature = "async"))]
+            trip_point: 0,
+        }
+    }
+
+    /// Get the current state.
+    pub fn state(&self) -> MotionState {
+        self.state
+    }
+
+    /// Get a reference to the UART driver.
+    pub fn driver(&self) -> &Tmc2209<U, D> {
+        &self.driver
+    }
+
+    /// Get a mutable reference to the UART driver.
+    pub fn driver_mut(&mut self) -> &mut Tmc2209<U, D> {
+        &mut self.driver
+    }
+
+    /// Release the driver.
+    pub fn release(self) -> Tmc2209<U, D> {
+        self.driver
+    }
+
+    /// Decide the next state from freshly read status registers.
+    ///
+    /// Returns the event and whether the motor must be stopped.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn advance(&mut self, report: FaultReport, status: DrvStatusView) -> (MotionEvent, bool) {
+        if report.has_fault() {
+            self.state = MotionState::Fault;
+            return (MotionEvent::FaultDetected(report), true);
+        }
+        match self.state {
+            MotionState::Homing if status.stalled => {
+                self.state = MotionState::Idle;
+                (MotionEvent::Stalled, true)
+            }
+            MotionState::Running if self.velocity == 0 && status.standstill => {
+                self.state = MotionState::Idle;
+                (MotionEvent::ReachedStandstill, false)
+            }
+            _ => (MotionEvent::NoEvent, false),
+        }
+    }
+}
+
+/// The status bits `advance` needs.
+#[cfg(any(feature = "blocking", feature = "async"))]
+#[derive(Clone, Copy)]
+struct DrvStatusView {
+    standstill: bool,
+    stalled: bool,
+}
+
+// ============================================================================
+// Blocking implementation
+// ============================================================================
+
+#[cfg(feature = "blocking")]
+impl<U, E, D> MotionController<U, D>
+where
+    U: embedded_io::Read<Error = E> + embedded_io::Write<Error = E>,
+    D: FnMut(),
+{
+    /// Start a homing move at `velocity` (VACTUAL).
+    ///
+    /// The motor stops when `poll` sees a stall.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownValue`] without moving if SGTHRS has not
+    /// been written through the driver yet.
+    pub fn home(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        self.trip_point = self.driver.written_stall_trip_point()?;
+        self.command(velocity)?;
+        self.state = MotionState::Homing;
+        Ok(())
+    }
+
+    /// Move at `velocity` (VACTUAL) until `stop` is called.
+    pub fn run(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        self.command(velocity)?;
+        self.state = MotionState::Running;
+        Ok(())
+    }
+
+    /// Stop the motor.
+    ///
+    /// The controller stays `Running` until `poll` reports
+    /// [`MotionEvent::ReachedStandstill`].
+    pub fn stop(&mut self) -> Result<(), Error<E>> {
+        self.command(0)?;
+        if self.state == MotionState::Homing {
+            self.state = MotionState::Running;
+        }
+        Ok(())
+    }
+
+    /// Clear the latched GSTAT flags and return to `Idle` after a fault.
+    pub fn clear_fault(&mut self) -> Result<(), Error<E>> {
+        self.driver.clear_gstat()?;
+        self.state = MotionState::Idle;
+        Ok(())
+    }
+
+    /// Read the status registers and advance the state machine.
+    ///
+    /// Faults are checked in every state except `Fault`. While homing,
+    /// SG_RESULT <= 2 * SGTHRS counts as a stall, the same condition that
+    /// asserts the DIAG stall output.
+    pub fn poll(&mut self) -> Result<MotionEvent, Error<E>> {
+        if self.state == MotionState::Fault {
+            return Ok(MotionEvent::NoEvent);
+        }
+        let gstat = self.driver.read_register::<Gstat>()?;
+        let drv_status = self.driver.read_register::<DrvStatus>()?;
+        let stalled = if self.state == MotionState::Homing {
+            self.driver.read_register::<SgResult>()?.result() as i16 <= self.trip_point
+        } else {
+            false
+        };
+        let view = DrvStatusView {
+            standstill: drv_status.stst(),
+            stalled,
+        };
+        let (event, stop) = self.advance(FaultReport::new(gstat, drv_status), view);
+        if stop {
+            self.command(0)?;
+        }
+        Ok(event)
+    }
+
+    /// Write VACTUAL and remember the commanded velocity.
+    fn command(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        let mut vactual = Vactual::new();
+        vactual.set_velocity(velocity);
+        self.driver.write_register(&vactual)?;
+        self.velocity = velocity;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Async implementation
+// ============================================================================
+
+#[cfg(feature = "async")]
+impl<U, E, D> MotionController<U, D>
+where
+    U: embedded_io_async::Read<Error = E> + embedded_io_async::Write<Error = E>,
+    D: FnMut(),
+{
+    /// Start a homing move at `velocity` (async).
+    pub async fn home_async(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        self.trip_point = self.driver.written_stall_trip_point()?;
+        self.command_async(velocity).await?;
+        self.state = MotionState::Homing;
+        Ok(())
+    }
+
+    /// Move at `velocity` until `stop_async` is called (async).
+    pub async fn run_async(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        self.command_async(velocity).await?;
+        self.state = MotionState::Running;
+        Ok(())
+    }
+
+    /// Stop the motor (async).
+    pub async fn stop_async(&mut self) -> Result<(), Error<E>> {
+        self.command_async(0).await?;
+        if self.state == MotionState::Homing {
+            self.state = MotionState::Running;
+        }
+        Ok(())
+    }
+
+    /// Clear the latched GSTAT flags and return to `Idle` (async).
+    pub async fn clear_fault_async(&mut self) -> Result<(), Error<E>> {
+        self.driver.clear_gstat_async().await?;
+        self.state = MotionState::Idle;
+        Ok(())
+    }
+
+    /// Read the status registers and advance the state machine (async).
+    pub async fn poll_async(&mut self) -> Result<MotionEvent, Error<E>> {
+        if self.state == MotionState::Fault {
+            return Ok(MotionEvent::NoEvent);
+        }
+        let gstat = self.driver.read_register_async::<Gstat>().await?;
+        let drv_status = self.driver.read_register_async::<DrvStatus>().await?;
+        let stalled = if self.state == MotionState::Homing {
+            self.driver
+                .read_register_async::<SgResult>()
+                .await?
+                .result() as i16
+                <= self.trip_point
+        } else {
+            false
+        };
+        let view = DrvStatusView {
+            standstill: drv_status.stst(),
+            stalled,
+        };
+        let (event, stop) = self.advance(FaultReport::new(gstat, drv_status), view);
+        if stop {
+            self.command_async(0).await?;
+        }
+        Ok(event)
+    }
+
+    /// Write VACTUAL and remember the commanded velocity (async).
+    async fn command_async(&mut self, velocity: i32) -> Result<(), Error<E>> {
+        let mut vactual = Vactual::new();
+        vactual.set_velocity(velocity);
+        self.driver.write_register_async(&vactual).await?;
+        self.velocity = velocity;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::mock::MockUart;
+    use crate::registers::Address;
+
+    #[test]
+    fn test_homing_sequence() {
+        let mut uart = MockUart::new();
+        for sg in [250, 101, 100] {
+            uart.script(Address::SgResult as u8, sg);
+        }
+        let mut driver = Tmc2209::new(uart, 0);
+        driver.set_stall_threshold(50).unwrap();
+        let mut motion = MotionController::new(driver);
+
+        motion.home(-2000).unwrap();
+        assert_eq!(motion.state(), MotionState::Homing);
+        assert_eq!(motion.poll(), Ok(MotionEvent::NoEvent));
+        assert_eq!(motion.poll(), Ok(MotionEvent::NoEvent));
+        assert_eq!(motion.poll(), Ok(MotionEvent::Stalled));
+        assert_eq!(motion.state(), MotionState::Idle);
+
+        let vactual = |v: i32| (0x22, v as u32 & 0xFF_FFFF);
+        assert_eq!(
+            motion.driver().uart().writes(),
+            &[(0x40, 50), vactual(-2000), vactual(0)]
+        );
+
+        // Idle polls do not read SG_RESULT
+        let reads = motion.driver().uart().reads;
+        assert_eq!(motion.poll(), Ok(MotionEvent::NoEvent));
+        assert_eq!(motion.driver().uart().reads, reads + 2);
+    }
+
+    #[test]
+    fn test_home_requires_stall_threshold() {
+        let mut motion = MotionController::new(Tmc2209::new(MockUart::new(), 0));
+        assert_eq!(
+            motion.home(-2000),
+            Err(Error::UnknownValue(Address::Sgthrs as u8))
+        );
+        assert_eq!(motion.state(), MotionState::Idle);
+        assert!(motion.driver().uart().writes().is_empty());
+    }
+
+    #[test]
+    fn test_run_and_stop() {
+        let mut motion = MotionController::new(Tmc2209::new(MockUart::new(), 0));
+        motion.run(1500).unwrap();
+        assert_eq!(motion.poll(), Ok(MotionEvent::NoEvent));
+
+        // Standstill while still commanded to move is not reported
+        motion.driver_mut().uart_mut().regs[Address::DrvStatus as usize] = 1 << 31;
+        assert_eq!(motion.poll(), Ok(MotionEvent::NoEvent));
+
+        motion.stop().unwrap();
+        assert_eq!(motion.state(), MotionState::Running);
+        assert_eq!(motion.poll(), Ok(MotionEvent::ReachedStandstill));
+        assert_eq!(motion.state(), MotionState::Idle);
+    }
+
+    #[test]
+    fn test_fault_stops_motor() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        driver.set_stall_threshold(50).unwrap();
+        let mut motion = MotionController::new(driver);
+        motion.home(800).unwrap();
+
+        // Short to ground on phase A, reported with GSTAT.drv_err
+        motion.driver_mut().uart_mut().regs[Address::Gstat as usize] = 0x02;
+        motion.driver_mut().uart_mut().regs[Address::DrvStatus as usize] = 1 << 2;
+        let event = motion.poll().unwrap();
+        match event {
+            MotionEvent::FaultDetected(report) => {
+                assert!(report.drv_err);
+                assert!(report.short_to_ground_a);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(motion.state(), MotionState::Fault);
+        assert_eq!(motion.driver().uart().writes().last(), Some(&(0x22, 0)));
+
+        // Faults are latched until cleared
+        assert_eq!(motion.poll(), Ok(MotionEvent::NoEvent));
+        motion.driver_mut().uart_mut().regs[Address::DrvStatus as usize] = 0;
+        motion.clear_fault().unwrap();
+        assert_eq!(motion.state(), MotionState::Idle);
+        assert_eq!(motion.poll(), Ok(MotionEvent::NoEvent));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_clear_fault_async() {
+        use crate::mock::block_on;
+
+        let mut motion = MotionController::new(Tmc2209::new(MockUart::new(), 0));
+        motion.driver_mut().uart_mut().regs[Address::Gstat as usize] = 0x02;
+        assert!(matches!(
+            block_on(motion.poll_async()),
+            Ok(MotionEvent::FaultDetected(_))
+        ));
+
+        block_on(motion.clear_fault_async()).unwrap();
+        assert_eq!(motion.state(), MotionState::Idle);
+        assert_eq!(motion.driver().uart().regs[Address::Gstat as usize], 0);
+    }
+}
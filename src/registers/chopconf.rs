@@ -1,6 +1,178 @@
 //! CHOPCONF - Chopper configuration register (0x6C)
 
-use super::{Address, MicrostepResolution, ReadableRegister, Register, WritableRegister};
+use super::{
+    register_diff, Address, FieldError, MicrostepResolution, ReadableRegister, Register,
+    WritableRegister,
+};
+
+/// Set of CHOPCONF fields, as returned by [`Chopconf::changed_fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChopconfFields(u16);
+
+impl ChopconfFields {
+    /// TOFF field.
+    pub const TOFF: Self = Self(1 << 0);
+    /// HSTRT field.
+    pub const HSTRT: Self = Self(1 << 1);
+    /// HEND field.
+    pub const HEND: Self = Self(1 << 2);
+    /// TBL field.
+    pub const TBL: Self = Self(1 << 3);
+    /// VSENSE field.
+    pub const VSENSE: Self = Self(1 << 4);
+    /// MRES field.
+    pub const MRES: Self = Self(1 << 5);
+    /// INTPOL field.
+    pub const INTPOL: Self = Self(1 << 6);
+    /// DEDGE field.
+    pub const DEDGE: Self = Self(1 << 7);
+    /// DISS2G field.
+    pub const DISS2G: Self = Self(1 << 8);
+    /// DISS2VS field.
+    pub const DISS2VS: Self = Self(1 << 9);
+
+    /// Register bit mask of each field.
+    const MASKS: [(Self, u32); 10] = [
+        (Self::TOFF, 0x0F),
+        (Self::HSTRT, 0x07 << 4),
+        (Self::HEND, 0x0F << 7),
+        (Self::TBL, 0x03 << 15),
+        (Self::VSENSE, 1 << 17),
+        (Self::MRES, 0x0F << 24),
+        (Self::INTPOL, 1 << 28),
+        (Self::DEDGE, 1 << 29),
+        (Self::DISS2G, 1 << 30),
+        (Self::DISS2VS, 1 << 31),
+    ];
+
+    /// Empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Get the raw flag bits.
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Check if no fields are set.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Check if all fields in `other` are set.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Build the set of fields touched by a raw bit difference.
+    fn from_diff(diff: u32) -> Self {
+        let mut fields = Self::empty();
+        for (field, mask) in Self::MASKS {
+            if diff & mask != 0 {
+                fields.0 |= field.0;
+            }
+        }
+        fields
+    }
+}
+
+impl core::ops::BitOr for ChopconfFields {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A questionable CHOPCONF setting, as reported by [`Chopconf::warnings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChopperWarning {
+    /// TOFF is 0, so the driver is disabled.
+    ToffDisabled,
+    /// Effective HSTRT + HEND exceeds the allowed maximum of 16.
+    HysteresisSumExceeds16,
+    /// TBL is 0 (16 clocks); 1 or 2 suits most motors.
+    BlankTimeTooLow,
+}
+
+impl ChopperWarning {
+    /// All warnings, in reporting order.
+    pub const ALL: [Self; 3] = [
+        Self::ToffDisabled,
+        Self::HysteresisSumExceeds16,
+        Self::BlankTimeTooLow,
+    ];
+
+    /// Get the flag bit of this warning.
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Set of [`ChopperWarning`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChopperWarnings(u8);
+
+impl ChopperWarnings {
+    /// Check if there are no warnings.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Get the number of warnings.
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Check if a warning is present.
+    pub const fn contains(&self, warning: ChopperWarning) -> bool {
+        self.0 & warning.bit() != 0
+    }
+
+    /// Iterate over the warnings present.
+    pub fn iter(&self) -> impl Iterator<Item = ChopperWarning> + '_ {
+        ChopperWarning::ALL
+            .into_iter()
+            .filter(move |&warning| self.contains(warning))
+    }
+
+    /// Add a warning.
+    fn insert(&mut self, warning: ChopperWarning) {
+        self.0 |= warning.bit();
+    }
+}
+
+/// Decoded snapshot of all CHOPCONF fields.
+///
+/// Returned by [`Chopconf::decode`], for logging and tuning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChopconfDecoded {
+    /// Off-time (0 = driver disabled).
+    pub toff: u8,
+    /// Hysteresis start register value (adds 1-8 to HEND).
+    pub hstrt: u8,
+    /// Effective hysteresis end, -3 to 12 (register value - 3).
+    pub hend: i8,
+    /// Comparator blank time select.
+    pub tbl: u8,
+    /// High sensitivity, low sense resistor voltage.
+    pub vsense: bool,
+    /// Microstep resolution.
+    pub mres: MicrostepResolution,
+    /// Interpolation to 256 microsteps.
+    pub intpol: bool,
+    /// Step on both edges.
+    pub dedge: bool,
+    /// Short to GND protection disabled.
+    pub diss2g: bool,
+    /// Low side short protection disabled.
+    pub diss2vs: bool,
+}
 
 /// Chopper configuration register.
 ///
@@ -11,6 +183,9 @@ use super::{Address, MicrostepResolution, ReadableRegister, Register, WritableRe
 pub struct Chopconf(u32);
 
 impl Chopconf {
+    /// Bits holding documented fields; the rest are reserved.
+    pub const FIELD_MASK: u32 = 0xFF03_87FF;
+
     /// Default value recommended by Trinamic.
     /// TOFF=3, HSTRT=4, HEND=1, TBL=2, MRES=0 (256 microsteps)
     pub const DEFAULT: u32 = 0x10000053;
@@ -25,7 +200,7 @@ impl Chopconf {
     /// Off-time setting controls chopper frequency.
     /// - 0: Driver disabled (all bridges off)
     /// - 1: Driver enabled with minimum off-time
-    /// - 2-15: Off-time = 12 + 32*TOFF clocks
+    /// - 2-15: Off-time = 24 + 32*TOFF clocks
     ///
     /// Recommended: 3-5 for most applications.
     pub fn toff(&self) -> u8 {
@@ -38,6 +213,12 @@ impl Chopconf {
         self
     }
 
+    /// Set TOFF, rejecting values above 15.
+    pub fn try_set_toff(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("toff", value as u32, 15)?;
+        Ok(self.set_toff(value))
+    }
+
     /// Get HSTRT (0-7).
     ///
     /// Hysteresis start value (adds 1-8 to HEND).
@@ -52,6 +233,12 @@ impl Chopconf {
         self
     }
 
+    /// Set HSTRT, rejecting values above 7.
+    pub fn try_set_hstrt(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("hstrt", value as u32, 7)?;
+        Ok(self.set_hstrt(value))
+    }
+
     /// Get HEND (0-15).
     ///
     /// Hysteresis end (low) value.
@@ -67,13 +254,19 @@ impl Chopconf {
         self
     }
 
+    /// Set HEND, rejecting values above 15.
+    pub fn try_set_hend(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("hend", value as u32, 15)?;
+        Ok(self.set_hend(value))
+    }
+
     /// Get TBL (0-3).
     ///
     /// Comparator blank time select:
     /// - 0: 16 clocks
     /// - 1: 24 clocks
-    /// - 2: 36 clocks (recommended)
-    /// - 3: 54 clocks
+    /// - 2: 32 clocks (recommended)
+    /// - 3: 40 clocks
     pub fn tbl(&self) -> u8 {
         ((self.0 >> 15) & 0x03) as u8
     }
@@ -84,6 +277,12 @@ impl Chopconf {
         self
     }
 
+    /// Set TBL, rejecting values above 3.
+    pub fn try_set_tbl(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("tbl", value as u32, 3)?;
+        Ok(self.set_tbl(value))
+    }
+
     /// Get VSENSE.
     ///
     /// Sense resistor voltage-based current scaling:
@@ -210,6 +409,91 @@ impl Chopconf {
         self
     }
 
+    /// Get HEND as its effective signed value (-3 to 12).
+    pub fn hend_effective(&self) -> i8 {
+        self.hend() as i8 - 3
+    }
+
+    /// Set HEND from its effective signed value, clamped to -3 to 12.
+    pub fn set_hend_effective(&mut self, value: i8) -> &mut Self {
+        self.set_hend((value.clamp(-3, 12) + 3) as u8)
+    }
+
+    /// Estimate the SpreadCycle chopper frequency in Hz.
+    ///
+    /// A chopper cycle has two slow decay phases of 24 + 32*TOFF clocks
+    /// each, and each of the on and fast decay phases lasts at least the
+    /// blank time set by TBL. Only these fixed parts are counted: the on
+    /// and fast decay phases also depend on the motor and current, so the
+    /// real frequency is somewhat lower. Returns `None` when TOFF=0
+    /// (driver disabled).
+    ///
+    /// # Arguments
+    ///
+    /// * `fclk` - Internal clock frequency in Hz (typically 12 MHz)
+    pub fn chopper_frequency_hz(&self, fclk: u32) -> Option<f32> {
+        if self.toff() == 0 {
+            return None;
+        }
+        let off_clocks = 24 + 32 * self.toff() as u32;
+        let blank_clocks = match self.tbl() {
+            0 => 16,
+            1 => 24,
+            2 => 32,
+            _ => 40,
+        };
+        Some(fclk as f32 / (2 * (off_clocks + blank_clocks)) as f32)
+    }
+
+    /// Check the chopper settings for common mistakes.
+    ///
+    /// This is a lint for copied or hand-written configurations; an empty
+    /// result does not mean the settings suit a particular motor.
+    pub fn warnings(&self) -> ChopperWarnings {
+        let mut warnings = ChopperWarnings::default();
+        if self.toff() == 0 {
+            warnings.insert(ChopperWarning::ToffDisabled);
+        }
+        // HSTRT adds 1-8 to the effective HEND
+        if self.hstrt() as i8 + 1 + self.hend_effective() > 16 {
+            warnings.insert(ChopperWarning::HysteresisSumExceeds16);
+        }
+        if self.tbl() == 0 {
+            warnings.insert(ChopperWarning::BlankTimeTooLow);
+        }
+        warnings
+    }
+
+    /// Decode all fields into a [`ChopconfDecoded`] snapshot.
+    pub fn decode(&self) -> ChopconfDecoded {
+        ChopconfDecoded {
+            toff: self.toff(),
+            hstrt: self.hstrt(),
+            hend: self.hend_effective(),
+            tbl: self.tbl(),
+            vsense: self.vsense(),
+            mres: self.microstep_resolution(),
+            intpol: self.intpol(),
+            dedge: self.dedge(),
+            diss2g: self.diss2g(),
+            diss2vs: self.diss2vs(),
+        }
+    }
+
+    /// Get the fields that differ between this value and `other`.
+    ///
+    /// Reserved bits are ignored.
+    pub fn changed_fields(&self, other: &Chopconf) -> ChopconfFields {
+        ChopconfFields::from_diff(register_diff(self.0, other.0))
+    }
+
+    /// Compare only the documented fields, ignoring reserved bits.
+    ///
+    /// Use this to verify a write by reading the register back.
+    pub fn eq_fields(&self, other: &Self) -> bool {
+        (self.0 ^ other.0) & Self::FIELD_MASK == 0
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -229,6 +513,7 @@ impl Default for Chopconf {
 
 impl Register for Chopconf {
     const ADDRESS: Address = Address::Chopconf;
+    const FIELD_MASK: u32 = Chopconf::FIELD_MASK;
 }
 
 impl ReadableRegister for Chopconf {}
@@ -245,3 +530,176 @@ impl From<Chopconf> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chopper_frequency_hz() {
+        let mut reg = Chopconf::from_raw(0);
+        assert_eq!(reg.chopper_frequency_hz(12_000_000), None);
+
+        // 2 * (120 + 32) = 304 clocks
+        reg.set_toff(3).set_tbl(2);
+        let f = reg.chopper_frequency_hz(12_000_000).unwrap();
+        assert!((f - 39_473.68).abs() < 0.1);
+
+        // 2 * (184 + 24) = 416 clocks
+        reg.set_toff(5).set_tbl(1);
+        let f = reg.chopper_frequency_hz(12_000_000).unwrap();
+        assert!((f - 28_846.15).abs() < 0.1);
+
+        // Longest off time and blank time: 2 * (504 + 40) = 1088 clocks
+        reg.set_toff(15).set_tbl(3);
+        let f = reg.chopper_frequency_hz(12_000_000).unwrap();
+        assert!((f - 11_029.41).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_eq_fields() {
+        let a = Chopconf::new();
+        // Bits 11-14 and 18-23 are reserved
+        let b = Chopconf::from_raw(a.raw() | (1 << 12) | (1 << 20));
+        assert_ne!(a, b);
+        assert!(a.eq_fields(&b));
+
+        let mut c = a;
+        c.set_toff(5);
+        assert!(!a.eq_fields(&c));
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let old = Chopconf::new();
+        let mut new = old;
+        new.set_toff(5)
+            .set_microstep_resolution(MicrostepResolution::M16);
+
+        let changed = old.changed_fields(&new);
+        assert_eq!(changed, ChopconfFields::TOFF | ChopconfFields::MRES);
+        assert!(!changed.contains(ChopconfFields::HSTRT));
+    }
+
+    #[test]
+    fn test_changed_fields_ignores_reserved() {
+        let old = Chopconf::new();
+        // Bits 18-23 are reserved
+        let new = Chopconf::from_raw(old.raw() | 0x0010_0000);
+
+        assert_eq!(register_diff(old.raw(), new.raw()), 0x0010_0000);
+        assert!(old.changed_fields(&new).is_empty());
+    }
+
+    #[test]
+    fn test_decode() {
+        let decoded = Chopconf::new().decode();
+        assert_eq!(
+            decoded,
+            ChopconfDecoded {
+                toff: 3,
+                hstrt: 5,
+                hend: -3,
+                tbl: 0,
+                vsense: false,
+                mres: MicrostepResolution::M256,
+                intpol: true,
+                dedge: false,
+                diss2g: false,
+                diss2vs: false,
+            }
+        );
+
+        let mut chopconf = Chopconf::new();
+        chopconf
+            .set_hend(15)
+            .set_vsense(true)
+            .set_microstep_resolution(MicrostepResolution::M16)
+            .set_diss2vs(true);
+        let decoded = chopconf.decode();
+        assert_eq!(decoded.hend, 12);
+        assert!(decoded.vsense);
+        assert_eq!(decoded.mres, MicrostepResolution::M16);
+        assert!(!decoded.diss2g);
+        assert!(decoded.diss2vs);
+
+        chopconf.set_hend(3);
+        assert_eq!(chopconf.decode().hend, 0);
+    }
+
+    #[test]
+    fn test_hend_effective() {
+        let mut chopconf = Chopconf::new();
+        for (raw, effective) in [(0, -3), (2, -1), (3, 0), (5, 2), (15, 12)] {
+            chopconf.set_hend(raw);
+            assert_eq!(chopconf.hend_effective(), effective);
+            chopconf.set_hend(0).set_hend_effective(effective);
+            assert_eq!(chopconf.hend(), raw);
+        }
+
+        chopconf.set_hend_effective(-10);
+        assert_eq!(chopconf.hend(), 0);
+        chopconf.set_hend_effective(20);
+        assert_eq!(chopconf.hend(), 15);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut chopconf = Chopconf::new();
+        chopconf.set_toff(3).set_hstrt(4).set_hend(1).set_tbl(2);
+        assert!(chopconf.warnings().is_empty());
+
+        chopconf.set_toff(0);
+        assert!(chopconf
+            .warnings()
+            .iter()
+            .eq([ChopperWarning::ToffDisabled]));
+
+        // HSTRT=7 (8) + HEND=12 (9) is 17
+        chopconf.set_toff(3).set_hstrt(7).set_hend(12);
+        assert!(chopconf
+            .warnings()
+            .iter()
+            .eq([ChopperWarning::HysteresisSumExceeds16]));
+        chopconf.set_hend(11);
+        assert!(chopconf.warnings().is_empty());
+
+        chopconf.set_tbl(0).set_toff(0).set_hend(15);
+        let warnings = chopconf.warnings();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().eq(ChopperWarning::ALL));
+    }
+
+    #[test]
+    fn test_try_set_fields() {
+        let mut chopconf = Chopconf::new();
+        chopconf
+            .try_set_toff(5)
+            .unwrap()
+            .try_set_hstrt(7)
+            .unwrap()
+            .try_set_tbl(3)
+            .unwrap();
+        assert_eq!(
+            (chopconf.toff(), chopconf.hstrt(), chopconf.tbl()),
+            (5, 7, 3)
+        );
+
+        let before = chopconf;
+        assert_eq!(
+            chopconf.try_set_toff(20).err(),
+            Some(FieldError {
+                field: "toff",
+                value: 20,
+                max: 15
+            })
+        );
+        assert_eq!(
+            chopconf.try_set_hstrt(8).err().map(|e| e.field),
+            Some("hstrt")
+        );
+        assert_eq!(chopconf.try_set_hend(16).err().map(|e| e.max), Some(15));
+        assert!(chopconf.try_set_tbl(4).is_err());
+        assert_eq!(chopconf, before);
+    }
+}
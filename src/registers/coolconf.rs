@@ -1,6 +1,6 @@
 //! COOLCONF - CoolStep and StallGuard2 configuration register (0x42)
 
-use super::{Address, Register, WritableRegister};
+use super::{Address, FieldError, Register, WritableRegister};
 
 /// CoolStep and StallGuard2 configuration register.
 ///
@@ -31,6 +31,12 @@ impl Coolconf {
         self
     }
 
+    /// Set SEMIN, rejecting values above 15.
+    pub fn try_set_semin(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("semin", value as u32, 15)?;
+        Ok(self.set_semin(value))
+    }
+
     /// Get SEUP (0-3).
     ///
     /// Current increment step width:
@@ -48,6 +54,12 @@ impl Coolconf {
         self
     }
 
+    /// Set SEUP, rejecting values above 3.
+    pub fn try_set_seup(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("seup", value as u32, 3)?;
+        Ok(self.set_seup(value))
+    }
+
     /// Get SEMAX (0-15).
     ///
     /// StallGuard hysteresis value for CoolStep current decrease.
@@ -62,6 +74,12 @@ impl Coolconf {
         self
     }
 
+    /// Set SEMAX, rejecting values above 15.
+    pub fn try_set_semax(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("semax", value as u32, 15)?;
+        Ok(self.set_semax(value))
+    }
+
     /// Get SEDN (0-3).
     ///
     /// Current decrement step width:
@@ -79,6 +97,12 @@ impl Coolconf {
         self
     }
 
+    /// Set SEDN, rejecting values above 3.
+    pub fn try_set_sedn(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("sedn", value as u32, 3)?;
+        Ok(self.set_sedn(value))
+    }
+
     /// Get SEIMIN.
     ///
     /// Minimum current for CoolStep:
@@ -132,3 +156,36 @@ impl From<Coolconf> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_set_fields() {
+        let mut coolconf = Coolconf::new();
+        coolconf
+            .try_set_semin(15)
+            .unwrap()
+            .try_set_semax(2)
+            .unwrap()
+            .try_set_seup(3)
+            .unwrap()
+            .try_set_sedn(1)
+            .unwrap();
+        assert_eq!(coolconf.semin(), 15);
+        assert_eq!(coolconf.semax(), 2);
+        assert_eq!(coolconf.seup(), 3);
+        assert_eq!(coolconf.sedn(), 1);
+
+        let before = coolconf;
+        assert!(coolconf.try_set_semin(16).is_err());
+        assert!(coolconf.try_set_semax(16).is_err());
+        assert_eq!(
+            coolconf.try_set_seup(4).err().map(|e| e.field),
+            Some("seup")
+        );
+        assert_eq!(coolconf.try_set_sedn(4).err().map(|e| e.max), Some(3));
+        assert_eq!(coolconf, before);
+    }
+}
@@ -1,6 +1,6 @@
 //! DRV_STATUS - Driver status register (0x6F)
 
-use super::{Address, ReadableRegister, Register};
+use super::{Address, ChopperMode, Gstat, ReadableRegister, Register};
 
 /// Driver status register.
 ///
@@ -11,6 +11,9 @@ use super::{Address, ReadableRegister, Register};
 pub struct DrvStatus(u32);
 
 impl DrvStatus {
+    /// Bits not assigned to a documented field (12-15 and 21-29).
+    pub const RESERVED_MASK: u32 = 0x3FE0_F000;
+
     /// Create with default value (0).
     pub fn new() -> Self {
         Self(0)
@@ -109,6 +112,15 @@ impl DrvStatus {
         (self.0 >> 30) & 1 != 0
     }
 
+    /// Get the active chopper mode from the stealth flag.
+    pub fn chopper_mode(&self) -> ChopperMode {
+        if self.stealth() {
+            ChopperMode::StealthChop
+        } else {
+            ChopperMode::SpreadCycle
+        }
+    }
+
     /// Get STST - standstill indicator.
     ///
     /// True when motor is in standstill (no step pulses for >2^20 clocks).
@@ -136,6 +148,46 @@ impl DrvStatus {
         self.short_detected() || self.ot()
     }
 
+    /// Get all temperature flags.
+    pub fn thermal(&self) -> ThermalStatus {
+        ThermalStatus {
+            otpw: self.otpw(),
+            ot: self.ot(),
+            t120: self.t120(),
+            t143: self.t143(),
+            t150: self.t150(),
+            t157: self.t157(),
+        }
+    }
+
+    /// Estimate the die temperature range in degrees Celsius.
+    ///
+    /// Returns the `(low, high)` bounds implied by the highest threshold
+    /// flag set, e.g. `(143, 150)` when t143 is set but t150 is not. This
+    /// is a range, not a measurement. Below 120C the result is `(0, 120)`;
+    /// above 157C the upper bound is `u8::MAX` (unbounded).
+    pub fn estimated_temperature_range(&self) -> (u8, u8) {
+        if self.t157() {
+            (157, u8::MAX)
+        } else if self.t150() {
+            (150, 157)
+        } else if self.t143() {
+            (143, 150)
+        } else if self.t120() {
+            (120, 143)
+        } else {
+            (0, 120)
+        }
+    }
+
+    /// Get the bits at undocumented positions.
+    ///
+    /// These read as zero on genuine silicon. A non-zero value points to
+    /// a silicon revision or clone using bits the crate does not model.
+    pub fn reserved_bits(&self) -> u32 {
+        self.0 & Self::RESERVED_MASK
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -147,6 +199,101 @@ impl DrvStatus {
     }
 }
 
+/// Temperature flags from DRV_STATUS.
+///
+/// The comparator flags t120..t157 are set while the die is above the
+/// respective temperature; otpw and ot are the pre-warning and shutdown
+/// flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ThermalStatus {
+    /// Overtemperature pre-warning.
+    pub otpw: bool,
+    /// Overtemperature shutdown.
+    pub ot: bool,
+    /// Temperature above 120C.
+    pub t120: bool,
+    /// Temperature above 143C.
+    pub t143: bool,
+    /// Temperature above 150C.
+    pub t150: bool,
+    /// Temperature above 157C.
+    pub t157: bool,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ThermalStatus {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "otpw={=bool} ot={=bool} t120={=bool} t143={=bool} t150={=bool} t157={=bool}",
+            self.otpw,
+            self.ot,
+            self.t120,
+            self.t143,
+            self.t150,
+            self.t157
+        )
+    }
+}
+
+/// Driver fault flags gathered from GSTAT and DRV_STATUS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FaultReport {
+    /// GSTAT.drv_err: driver shut down (overtemperature or short).
+    pub drv_err: bool,
+    /// GSTAT.uv_cp: charge pump undervoltage.
+    pub uv_cp: bool,
+    /// Overtemperature shutdown.
+    pub overtemperature: bool,
+    /// Overtemperature pre-warning.
+    pub overtemperature_warning: bool,
+    /// Short to ground on phase A.
+    pub short_to_ground_a: bool,
+    /// Short to ground on phase B.
+    pub short_to_ground_b: bool,
+    /// Short to supply (low-side) on phase A.
+    pub short_to_supply_a: bool,
+    /// Short to supply (low-side) on phase B.
+    pub short_to_supply_b: bool,
+    /// Open load on phase A.
+    pub open_load_a: bool,
+    /// Open load on phase B.
+    pub open_load_b: bool,
+}
+
+impl FaultReport {
+    /// Gather the fault flags from GSTAT and DRV_STATUS.
+    pub fn new(gstat: Gstat, drv_status: DrvStatus) -> Self {
+        Self {
+            drv_err: gstat.drv_err(),
+            uv_cp: gstat.uv_cp(),
+            overtemperature: drv_status.ot(),
+            overtemperature_warning: drv_status.otpw(),
+            short_to_ground_a: drv_status.s2ga(),
+            short_to_ground_b: drv_status.s2gb(),
+            short_to_supply_a: drv_status.s2vsa(),
+            short_to_supply_b: drv_status.s2vsb(),
+            open_load_a: drv_status.ola(),
+            open_load_b: drv_status.olb(),
+        }
+    }
+
+    /// Check if a fault that disables the driver is present.
+    ///
+    /// Open load and the overtemperature pre-warning are informational
+    /// and do not count.
+    pub fn has_fault(&self) -> bool {
+        self.drv_err
+            || self.uv_cp
+            || self.overtemperature
+            || self.short_to_ground_a
+            || self.short_to_ground_b
+            || self.short_to_supply_a
+            || self.short_to_supply_b
+    }
+}
+
 impl Default for DrvStatus {
     fn default() -> Self {
         Self::new()
@@ -170,3 +317,66 @@ impl From<DrvStatus> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chopper_mode() {
+        assert_eq!(
+            DrvStatus::from_raw(1 << 30).chopper_mode(),
+            ChopperMode::StealthChop
+        );
+        assert_eq!(
+            DrvStatus::from_raw(0).chopper_mode(),
+            ChopperMode::SpreadCycle
+        );
+    }
+
+    #[test]
+    fn test_thermal() {
+        // otpw, t120, t143 set; ot, t150, t157 clear; plus unrelated bits
+        let status = DrvStatus::from_raw(0x8000_0301 | (1 << 2));
+        assert_eq!(
+            status.thermal(),
+            ThermalStatus {
+                otpw: true,
+                ot: false,
+                t120: true,
+                t143: true,
+                t150: false,
+                t157: false,
+            }
+        );
+        assert_eq!(DrvStatus::new().thermal(), ThermalStatus::default());
+    }
+
+    #[test]
+    fn test_estimated_temperature_range() {
+        let range = |bits: u32| DrvStatus::from_raw(bits << 8).estimated_temperature_range();
+        assert_eq!(range(0b0000), (0, 120));
+        assert_eq!(range(0b0001), (120, 143));
+        assert_eq!(range(0b0011), (143, 150));
+        assert_eq!(range(0b0111), (150, 157));
+        assert_eq!(range(0b1111), (157, u8::MAX));
+        // The highest set flag wins even if lower ones are clear
+        assert_eq!(range(0b0100), (150, 157));
+    }
+
+    #[test]
+    fn test_reserved_bits() {
+        // stst, stealth, CS_ACTUAL=31 and all flags: no reserved bits
+        let status = DrvStatus::from_raw(0xC01F_0FFF);
+        assert_eq!(status.reserved_bits(), 0);
+
+        let status = DrvStatus::from_raw(0xC01F_0FFF | (1 << 12) | (1 << 25));
+        assert_eq!(status.reserved_bits(), (1 << 12) | (1 << 25));
+        assert_eq!(status.cs_actual(), 31);
+
+        assert_eq!(
+            DrvStatus::from_raw(u32::MAX).reserved_bits(),
+            DrvStatus::RESERVED_MASK
+        );
+    }
+}
@@ -54,6 +54,7 @@ impl FactoryConf {
 
 impl Register for FactoryConf {
     const ADDRESS: Address = Address::FactoryConf;
+    const FIELD_MASK: u32 = 0x0000_031F;
 }
 
 impl ReadableRegister for FactoryConf {}
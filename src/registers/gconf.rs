@@ -1,6 +1,123 @@
 //! GCONF - Global configuration register (0x00)
 
-use super::{Address, ReadableRegister, Register, WritableRegister};
+use super::{register_diff, Address, ReadableRegister, Register, WritableRegister};
+
+/// Set of GCONF fields, as returned by [`Gconf::changed_fields`].
+///
+/// Each GCONF field is a single bit, so the flag bits match the register layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GconfFields(u16);
+
+impl GconfFields {
+    /// I_scale_analog field.
+    pub const I_SCALE_ANALOG: Self = Self(1 << 0);
+    /// internal_Rsense field.
+    pub const INTERNAL_RSENSE: Self = Self(1 << 1);
+    /// en_SpreadCycle field.
+    pub const EN_SPREADCYCLE: Self = Self(1 << 2);
+    /// shaft field.
+    pub const SHAFT: Self = Self(1 << 3);
+    /// index_otpw field.
+    pub const INDEX_OTPW: Self = Self(1 << 4);
+    /// index_step field.
+    pub const INDEX_STEP: Self = Self(1 << 5);
+    /// pdn_disable field.
+    pub const PDN_DISABLE: Self = Self(1 << 6);
+    /// mstep_reg_select field.
+    pub const MSTEP_REG_SELECT: Self = Self(1 << 7);
+    /// multistep_filt field.
+    pub const MULTISTEP_FILT: Self = Self(1 << 8);
+    /// test_mode field.
+    pub const TEST_MODE: Self = Self(1 << 9);
+
+    /// Mask of all documented GCONF bits.
+    const ALL: u32 = 0x3FF;
+
+    /// Empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Get the raw flag bits.
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Check if no fields are set.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Check if all fields in `other` are set.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for GconfFields {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Signal on the INDEX pin, selected by index_otpw and index_step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IndexOutput {
+    /// First microstep position of the sequencer.
+    FirstMicrostep,
+    /// Overtemperature prewarning flag.
+    Otpw,
+    /// Step pulses from the internal pulse generator (toggles each step).
+    StepPulses,
+}
+
+/// Decoded snapshot of all GCONF fields.
+///
+/// Returned by [`Gconf::decode`], for logging and debugging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GconfDecoded {
+    /// Use VREF as current reference.
+    pub i_scale_analog: bool,
+    /// Use the internal sense resistors.
+    pub internal_rsense: bool,
+    /// SpreadCycle instead of StealthChop.
+    pub en_spreadcycle: bool,
+    /// Motor direction inverted.
+    pub shaft: bool,
+    /// INDEX pin signal.
+    pub index: IndexOutput,
+    /// PDN_UART input disabled (required for UART control).
+    pub pdn_disable: bool,
+    /// Microstep resolution set by CHOPCONF.MRES.
+    pub mstep_reg_select: bool,
+    /// STEP input filter enabled.
+    pub multistep_filt: bool,
+    /// Test mode (must be off).
+    pub test_mode: bool,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for GconfDecoded {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "analog={=bool} int_rsense={=bool} spread={=bool} shaft={=bool} index={} pdn_dis={=bool} mstep_reg={=bool} filt={=bool} test={=bool}",
+            self.i_scale_analog,
+            self.internal_rsense,
+            self.en_spreadcycle,
+            self.shaft,
+            self.index,
+            self.pdn_disable,
+            self.mstep_reg_select,
+            self.multistep_filt,
+            self.test_mode
+        )
+    }
+}
 
 /// Global configuration register.
 ///
@@ -11,6 +128,9 @@ use super::{Address, ReadableRegister, Register, WritableRegister};
 pub struct Gconf(u32);
 
 impl Gconf {
+    /// Bits holding documented fields; the rest are reserved.
+    pub const FIELD_MASK: u32 = 0x0000_03FF;
+
     /// Create a new GCONF with default value.
     ///
     /// Default: 0x00000040 (pdn_disable=1 for UART operation)
@@ -126,6 +246,23 @@ impl Gconf {
         self
     }
 
+    /// Get the INDEX pin signal.
+    pub fn index_output(&self) -> IndexOutput {
+        if self.index_step() {
+            IndexOutput::StepPulses
+        } else if self.index_otpw() {
+            IndexOutput::Otpw
+        } else {
+            IndexOutput::FirstMicrostep
+        }
+    }
+
+    /// Select the INDEX pin signal.
+    pub fn set_index_output(&mut self, output: IndexOutput) -> &mut Self {
+        self.set_index_otpw(output == IndexOutput::Otpw)
+            .set_index_step(output == IndexOutput::StepPulses)
+    }
+
     /// PDN_UART disable.
     ///
     /// **Must be set to `true` when using UART interface.**
@@ -225,6 +362,35 @@ impl Gconf {
         self
     }
 
+    /// Decode all fields into a [`GconfDecoded`] snapshot.
+    pub fn decode(&self) -> GconfDecoded {
+        GconfDecoded {
+            i_scale_analog: self.i_scale_analog(),
+            internal_rsense: self.internal_rsense(),
+            en_spreadcycle: self.en_spreadcycle(),
+            shaft: self.shaft(),
+            index: self.index_output(),
+            pdn_disable: self.pdn_disable(),
+            mstep_reg_select: self.mstep_reg_select(),
+            multistep_filt: self.multistep_filt(),
+            test_mode: self.test_mode(),
+        }
+    }
+
+    /// Get the fields that differ between this value and `other`.
+    ///
+    /// Reserved bits are ignored.
+    pub fn changed_fields(&self, other: &Gconf) -> GconfFields {
+        GconfFields((register_diff(self.0, other.0) & GconfFields::ALL) as u16)
+    }
+
+    /// Compare only the documented fields, ignoring reserved bits.
+    ///
+    /// Use this to verify a write by reading the register back.
+    pub fn eq_fields(&self, other: &Self) -> bool {
+        (self.0 ^ other.0) & Self::FIELD_MASK == 0
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -238,6 +404,7 @@ impl Gconf {
 
 impl Register for Gconf {
     const ADDRESS: Address = Address::Gconf;
+    const FIELD_MASK: u32 = Gconf::FIELD_MASK;
 }
 
 impl ReadableRegister for Gconf {}
@@ -254,3 +421,74 @@ impl From<Gconf> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eq_fields() {
+        let a = Gconf::new();
+        let b = Gconf::from_raw(a.raw() | 0xFFFF_FC00);
+        assert_ne!(a, b);
+        assert!(a.eq_fields(&b));
+
+        let mut c = a;
+        c.set_shaft(true);
+        assert!(!a.eq_fields(&c));
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let old = Gconf::new();
+        let mut new = old;
+        new.set_en_spreadcycle(true).set_pdn_disable(false);
+
+        assert_eq!(
+            old.changed_fields(&new),
+            GconfFields::EN_SPREADCYCLE | GconfFields::PDN_DISABLE
+        );
+        assert!(old.changed_fields(&old).is_empty());
+    }
+
+    #[test]
+    fn test_decode() {
+        // pdn_disable, mstep_reg_select, multistep_filt, en_spreadcycle, index_otpw
+        let decoded = Gconf::from_raw(0x1D4).decode();
+        assert_eq!(
+            decoded,
+            GconfDecoded {
+                i_scale_analog: false,
+                internal_rsense: false,
+                en_spreadcycle: true,
+                shaft: false,
+                index: IndexOutput::Otpw,
+                pdn_disable: true,
+                mstep_reg_select: true,
+                multistep_filt: true,
+                test_mode: false,
+            }
+        );
+
+        // index_step takes precedence over index_otpw
+        assert_eq!(
+            Gconf::from_raw(0x30).index_output(),
+            IndexOutput::StepPulses
+        );
+        assert_eq!(Gconf::new().index_output(), IndexOutput::FirstMicrostep);
+    }
+
+    #[test]
+    fn test_set_index_output() {
+        let mut gconf = Gconf::new();
+        for output in [
+            IndexOutput::Otpw,
+            IndexOutput::StepPulses,
+            IndexOutput::FirstMicrostep,
+        ] {
+            gconf.set_index_output(output);
+            assert_eq!(gconf.index_output(), output);
+        }
+        assert_eq!(gconf, Gconf::new());
+    }
+}
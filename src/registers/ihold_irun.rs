@@ -1,6 +1,6 @@
 //! IHOLD_IRUN - Driver current control register (0x10)
 
-use super::{Address, Register, WritableRegister};
+use super::{Address, CurrentScale, FieldError, Register, WritableRegister};
 
 /// Driver current control register.
 ///
@@ -32,6 +32,12 @@ impl IholdIrun {
         self
     }
 
+    /// Set standstill current, rejecting values above 31.
+    pub fn try_set_ihold(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("ihold", value as u32, 31)?;
+        Ok(self.set_ihold(value))
+    }
+
     /// Motor run current (0-31).
     ///
     /// 0 = 1/32 of max current, 31 = 32/32 of max current.
@@ -46,6 +52,32 @@ impl IholdIrun {
         self
     }
 
+    /// Set motor run current, rejecting values above 31.
+    pub fn try_set_irun(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("irun", value as u32, 31)?;
+        Ok(self.set_irun(value))
+    }
+
+    /// Standstill current as a [`CurrentScale`].
+    pub fn ihold_scale(&self) -> CurrentScale {
+        CurrentScale(self.ihold())
+    }
+
+    /// Set standstill current from a validated [`CurrentScale`].
+    pub fn set_ihold_scale(&mut self, value: CurrentScale) -> &mut Self {
+        self.set_ihold(value.value())
+    }
+
+    /// Motor run current as a [`CurrentScale`].
+    pub fn irun_scale(&self) -> CurrentScale {
+        CurrentScale(self.irun())
+    }
+
+    /// Set motor run current from a validated [`CurrentScale`].
+    pub fn set_irun_scale(&mut self, value: CurrentScale) -> &mut Self {
+        self.set_irun(value.value())
+    }
+
     /// IHOLDDELAY (0-15).
     ///
     /// Delay per current reduction step after standstill detection.
@@ -61,6 +93,12 @@ impl IholdIrun {
         self
     }
 
+    /// Set IHOLDDELAY, rejecting values above 15.
+    pub fn try_set_iholddelay(&mut self, value: u8) -> Result<&mut Self, FieldError> {
+        FieldError::check("iholddelay", value as u32, 15)?;
+        Ok(self.set_iholddelay(value))
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -95,3 +133,34 @@ impl From<IholdIrun> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_set_fields() {
+        let mut reg = IholdIrun::new();
+        reg.try_set_irun(31)
+            .unwrap()
+            .try_set_ihold(0)
+            .unwrap()
+            .try_set_iholddelay(15)
+            .unwrap();
+        assert_eq!((reg.irun(), reg.ihold(), reg.iholddelay()), (31, 0, 15));
+
+        // The masking setter would have stored 32 & 0x1F = 0
+        let before = reg;
+        assert_eq!(
+            reg.try_set_irun(32).err(),
+            Some(FieldError {
+                field: "irun",
+                value: 32,
+                max: 31
+            })
+        );
+        assert!(reg.try_set_ihold(40).is_err());
+        assert!(reg.try_set_iholddelay(16).is_err());
+        assert_eq!(reg, before);
+    }
+}
@@ -2,6 +2,78 @@
 
 use super::{Address, ReadableRegister, Register};
 
+/// Decoded snapshot of all IOIN pin states.
+///
+/// Useful for checking step/dir and address strap wiring in one read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IoinState {
+    /// ENN pin state.
+    pub enn: bool,
+    /// MS1 pin state.
+    pub ms1: bool,
+    /// MS2 pin state.
+    pub ms2: bool,
+    /// DIAG pin state.
+    pub diag: bool,
+    /// PDN_UART pin state.
+    pub pdn_uart: bool,
+    /// STEP pin state.
+    pub step: bool,
+    /// SPREAD (SEL_A) pin state.
+    pub spread_en: bool,
+    /// DIR pin state.
+    pub dir: bool,
+    /// IC version number.
+    pub version: u8,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for IoinState {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ENN={=bool} MS1={=bool} MS2={=bool} DIAG={=bool} PDN={=bool} STEP={=bool} SPREAD={=bool} DIR={=bool} v=0x{=u8:02X}",
+            self.enn,
+            self.ms1,
+            self.ms2,
+            self.diag,
+            self.pdn_uart,
+            self.step,
+            self.spread_en,
+            self.dir,
+            self.version
+        )
+    }
+}
+
+/// TMC2209 silicon revision, from IOIN.version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Revision {
+    /// Version 0x21, the first TMC2209 revision.
+    V0x21,
+    /// A version this crate does not know about.
+    Unknown(u8),
+}
+
+impl Revision {
+    /// Decode an IOIN.version value.
+    pub fn from_version(version: u8) -> Self {
+        match version {
+            0x21 => Self::V0x21,
+            other => Self::Unknown(other),
+        }
+    }
+
+    /// Get the IOIN.version value.
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::V0x21 => 0x21,
+            Self::Unknown(version) => *version,
+        }
+    }
+}
+
 /// Input pin states register.
 ///
 /// Reads the state of all input pins and the IC version.
@@ -58,6 +130,26 @@ impl Ioin {
         ((self.0 >> 24) & 0xFF) as u8
     }
 
+    /// Get the silicon revision.
+    pub fn revision(&self) -> Revision {
+        Revision::from_version(self.version())
+    }
+
+    /// Get a snapshot of all pin states and the IC version.
+    pub fn state(&self) -> IoinState {
+        IoinState {
+            enn: self.enn(),
+            ms1: self.ms1(),
+            ms2: self.ms2(),
+            diag: self.diag(),
+            pdn_uart: self.pdn_uart(),
+            step: self.step(),
+            spread_en: self.spread_en(),
+            dir: self.dir(),
+            version: self.version(),
+        }
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -86,3 +178,36 @@ impl From<Ioin> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ioin_state() {
+        // ENN, MS2, STEP, DIR set; version 0x21
+        let ioin = Ioin::from_raw(0x2100_0289);
+        let state = ioin.state();
+
+        assert!(state.enn);
+        assert!(!state.ms1);
+        assert!(state.ms2);
+        assert!(!state.diag);
+        assert!(!state.pdn_uart);
+        assert!(state.step);
+        assert!(!state.spread_en);
+        assert!(state.dir);
+        assert_eq!(state.version, 0x21);
+    }
+
+    #[test]
+    fn test_ioin_revision() {
+        let ioin = Ioin::from_raw(0x2100_0289);
+        assert_eq!(ioin.revision(), Revision::V0x21);
+        assert_eq!(ioin.revision().version(), 0x21);
+
+        let ioin = Ioin::from_raw(0x2200_0000);
+        assert_eq!(ioin.revision(), Revision::Unknown(0x22));
+        assert_eq!(ioin.revision().version(), 0x22);
+    }
+}
@@ -30,13 +30,13 @@ mod pwmconf;
 mod pwm_scale;
 mod pwm_auto;
 
-pub use gconf::Gconf;
+pub use gconf::{Gconf, GconfDecoded, GconfFields, IndexOutput};
 pub use gstat::Gstat;
 pub use ifcnt::Ifcnt;
 pub use slaveconf::Slaveconf;
-pub use otp_prog::OtpProg;
+pub use otp_prog::{OtpConfirm, OtpField, OtpProg};
 pub use otp_read::OtpRead;
-pub use ioin::Ioin;
+pub use ioin::{Ioin, IoinState, Revision};
 pub use factory_conf::FactoryConf;
 pub use ihold_irun::IholdIrun;
 pub use tpowerdown::Tpowerdown;
@@ -49,9 +49,9 @@ pub use sg_result::SgResult;
 pub use coolconf::Coolconf;
 pub use mscnt::Mscnt;
 pub use mscuract::Mscuract;
-pub use chopconf::Chopconf;
-pub use drv_status::DrvStatus;
-pub use pwmconf::Pwmconf;
+pub use chopconf::{Chopconf, ChopconfDecoded, ChopconfFields, ChopperWarning, ChopperWarnings};
+pub use drv_status::{DrvStatus, FaultReport, ThermalStatus};
+pub use pwmconf::{Pwmconf, PwmconfBuilder};
 pub use pwm_scale::PwmScale;
 pub use pwm_auto::PwmAuto;
 
@@ -60,12 +60,25 @@ pub trait Register: Sized + Copy + Clone + Default + Into<u32> + From<u32> {
     /// The register address.
     const ADDRESS: Address;
 
+    /// Bits holding documented fields.
+    ///
+    /// Reserved bits outside this mask may read back differently from
+    /// what was written.
+    const FIELD_MASK: u32 = u32::MAX;
+
     /// Get the register address.
     fn address() -> Address {
         Self::ADDRESS
     }
 }
 
+/// Get the bits that differ between two raw register values.
+///
+/// Returns `old ^ new`; a set bit marks a changed bit position.
+pub fn register_diff(old: u32, new: u32) -> u32 {
+    old ^ new
+}
+
 /// Trait for registers that can be read.
 pub trait ReadableRegister: Register {}
 
@@ -130,6 +143,74 @@ pub enum Address {
 }
 
 impl Address {
+    /// All register addresses, in ascending order.
+    pub const ALL: [Address; 24] = [
+        Address::Gconf,
+        Address::Gstat,
+        Address::Ifcnt,
+        Address::Slaveconf,
+        Address::OtpProg,
+        Address::OtpRead,
+        Address::Ioin,
+        Address::FactoryConf,
+        Address::IholdIrun,
+        Address::Tpowerdown,
+        Address::Tstep,
+        Address::Tpwmthrs,
+        Address::Tcoolthrs,
+        Address::Vactual,
+        Address::Sgthrs,
+        Address::SgResult,
+        Address::Coolconf,
+        Address::Mscnt,
+        Address::Mscuract,
+        Address::Chopconf,
+        Address::DrvStatus,
+        Address::Pwmconf,
+        Address::PwmScale,
+        Address::PwmAuto,
+    ];
+
+    /// Get the register name as written in the datasheet, e.g. `"IHOLD_IRUN"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Address::Gconf => "GCONF",
+            Address::Gstat => "GSTAT",
+            Address::Ifcnt => "IFCNT",
+            Address::Slaveconf => "SLAVECONF",
+            Address::OtpProg => "OTP_PROG",
+            Address::OtpRead => "OTP_READ",
+            Address::Ioin => "IOIN",
+            Address::FactoryConf => "FACTORY_CONF",
+            Address::IholdIrun => "IHOLD_IRUN",
+            Address::Tpowerdown => "TPOWERDOWN",
+            Address::Tstep => "TSTEP",
+            Address::Tpwmthrs => "TPWMTHRS",
+            Address::Tcoolthrs => "TCOOLTHRS",
+            Address::Vactual => "VACTUAL",
+            Address::Sgthrs => "SGTHRS",
+            Address::SgResult => "SG_RESULT",
+            Address::Coolconf => "COOLCONF",
+            Address::Mscnt => "MSCNT",
+            Address::Mscuract => "MSCURACT",
+            Address::Chopconf => "CHOPCONF",
+            Address::DrvStatus => "DRV_STATUS",
+            Address::Pwmconf => "PWMCONF",
+            Address::PwmScale => "PWM_SCALE",
+            Address::PwmAuto => "PWM_AUTO",
+        }
+    }
+
+    /// Look up a register by its datasheet name, ignoring case.
+    ///
+    /// The inverse of [`name`](Self::name): `"chopconf"` and `"CHOPCONF"`
+    /// both give [`Address::Chopconf`].
+    pub fn parse_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|addr| addr.name().eq_ignore_ascii_case(name))
+    }
+
     /// Convert a u8 to an Address if it's a known register.
     pub fn from_u8(value: u8) -> Option<Self> {
         match value {
@@ -282,6 +363,39 @@ impl MicrostepResolution {
     }
 }
 
+/// Current scale (CS) value, 0-31.
+///
+/// The motor current is (CS + 1) / 32 of the full-scale current. Used for
+/// IRUN and IHOLD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CurrentScale(u8);
+
+impl CurrentScale {
+    /// Full-scale current (31).
+    pub const MAX: Self = Self(31);
+
+    /// Create a current scale, or `None` if `value` exceeds 31.
+    pub const fn new(value: u8) -> Option<Self> {
+        if value <= 31 {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Get the CS value (0-31).
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<CurrentScale> for u8 {
+    fn from(cs: CurrentScale) -> u8 {
+        cs.0
+    }
+}
+
 /// Standstill mode when motor current is zero (IHOLD=0).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -315,3 +429,77 @@ impl StandstillMode {
         self as u8
     }
 }
+
+/// Chopper mode the driver operates in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChopperMode {
+    /// StealthChop voltage PWM mode (quiet).
+    StealthChop,
+    /// SpreadCycle cycle-by-cycle current control.
+    SpreadCycle,
+}
+
+/// A register field value outside its valid range.
+///
+/// Returned by the register builders when `build()` finds a field that
+/// would not fit into its bitfield.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FieldError {
+    /// Name of the offending field.
+    pub field: &'static str,
+    /// The rejected value.
+    pub value: u32,
+    /// The largest accepted value.
+    pub max: u32,
+}
+
+impl FieldError {
+    /// Check that `value` does not exceed `max`.
+    pub(crate) fn check(field: &'static str, value: u32, max: u32) -> Result<(), Self> {
+        if value > max {
+            Err(Self { field, value, max })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl core::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} out of range: {} (max {})",
+            self.field, self.value, self.max
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_scale() {
+        assert_eq!(CurrentScale::new(0).map(CurrentScale::value), Some(0));
+        assert_eq!(CurrentScale::new(31), Some(CurrentScale::MAX));
+        assert_eq!(CurrentScale::new(32), None);
+        assert_eq!(CurrentScale::new(255), None);
+        assert_eq!(u8::from(CurrentScale::new(17).unwrap()), 17);
+    }
+
+    #[test]
+    fn test_address_parse_name() {
+        assert_eq!(Address::parse_name("CHOPCONF"), Some(Address::Chopconf));
+        assert_eq!(Address::parse_name("chopconf"), Some(Address::Chopconf));
+        assert_eq!(Address::parse_name("Ihold_Irun"), Some(Address::IholdIrun));
+        assert_eq!(Address::parse_name("CHOPCONFX"), None);
+        assert_eq!(Address::parse_name(""), None);
+
+        for addr in Address::ALL {
+            assert_eq!(Address::parse_name(addr.name()), Some(addr));
+            assert_eq!(Address::from_u8(addr as u8), Some(addr));
+        }
+    }
+}
@@ -24,6 +24,11 @@ impl Mscnt {
         (self.0 & 0x3FF) as u16
     }
 
+    /// Get the position as a fraction of the electrical period (0.0 to <1.0).
+    pub fn phase(&self) -> f32 {
+        self.count() as f32 / 1024.0
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -58,3 +63,17 @@ impl From<Mscnt> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase() {
+        assert_eq!(Mscnt::from_raw(0).phase(), 0.0);
+        assert_eq!(Mscnt::from_raw(512).phase(), 0.5);
+        assert_eq!(Mscnt::from_raw(1023).phase(), 1023.0 / 1024.0);
+        // Bits above the 10-bit counter are ignored
+        assert_eq!(Mscnt::from_raw(0x400 | 256).phase(), 0.25);
+    }
+}
@@ -44,6 +44,15 @@ impl Mscuract {
         }
     }
 
+    /// Get the electrical angle implied by the coil currents, in degrees.
+    ///
+    /// Computes `atan2(cur_b, cur_a)` in the range -180..=180 (accurate
+    /// to about 0.001 degrees). Returns 0 when both currents are zero.
+    /// Useful to cross-check the microstep table against MSCNT.
+    pub fn electrical_angle_deg(&self) -> f32 {
+        atan2(self.cur_b() as f32, self.cur_a() as f32).to_degrees()
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -55,6 +64,39 @@ impl Mscuract {
     }
 }
 
+/// Four-quadrant arctangent in radians, without `std`.
+fn atan2(y: f32, x: f32) -> f32 {
+    use core::f32::consts::{FRAC_PI_2, PI};
+
+    if x == 0.0 && y == 0.0 {
+        return 0.0;
+    }
+    // Reduce to |z| <= 1 so the polynomial stays accurate
+    if x.abs() >= y.abs() {
+        let a = atan_unit(y / x);
+        if x >= 0.0 {
+            a
+        } else if y >= 0.0 {
+            a + PI
+        } else {
+            a - PI
+        }
+    } else {
+        let a = atan_unit(x / y);
+        if y > 0.0 {
+            FRAC_PI_2 - a
+        } else {
+            -FRAC_PI_2 - a
+        }
+    }
+}
+
+/// Arctangent for |z| <= 1 (minimax polynomial, error below 1e-5 rad).
+fn atan_unit(z: f32) -> f32 {
+    let z2 = z * z;
+    z * (0.999_866 + z2 * (-0.330_299_5 + z2 * (0.180_141 + z2 * (-0.085_133 + z2 * 0.020_835_1))))
+}
+
 impl Default for Mscuract {
     fn default() -> Self {
         Self::new()
@@ -78,3 +120,33 @@ impl From<Mscuract> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn angle(cur_a: i16, cur_b: i16) -> f32 {
+        let raw = ((cur_b as u32 & 0x1FF) << 16) | (cur_a as u32 & 0x1FF);
+        Mscuract::from_raw(raw).electrical_angle_deg()
+    }
+
+    #[test]
+    fn test_electrical_angle_cardinal() {
+        assert!((angle(248, 0) - 0.0).abs() < 0.01);
+        assert!((angle(0, 248) - 90.0).abs() < 0.01);
+        assert!((angle(-248, 0) - 180.0).abs() < 0.01);
+        assert!((angle(0, -248) + 90.0).abs() < 0.01);
+        assert_eq!(angle(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_electrical_angle_diagonal() {
+        assert!((angle(100, 100) - 45.0).abs() < 0.01);
+        assert!((angle(-100, 100) - 135.0).abs() < 0.01);
+        assert!((angle(-100, -100) + 135.0).abs() < 0.01);
+        assert!((angle(100, -100) + 45.0).abs() < 0.01);
+        // 30 degrees: tan = 1/sqrt(3)
+        assert!((angle(173, 100) - 30.03).abs() < 0.01);
+        assert!((angle(100, 173) - 59.97).abs() < 0.01);
+    }
+}
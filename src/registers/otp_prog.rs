@@ -10,6 +10,19 @@ use super::{Address, Register, WritableRegister};
 pub struct OtpProg(u32);
 
 impl OtpProg {
+    /// Value of OTPMAGIC that enables programming.
+    pub const MAGIC: u8 = 0xBD;
+
+    /// Create a programming request for one OTP field, with OTPMAGIC set.
+    pub fn for_field(field: OtpField) -> Self {
+        let (byte, bit) = field.location();
+        let mut reg = Self::default();
+        reg.set_otpbyte(byte)
+            .set_otpbit(bit)
+            .set_otpmagic(Self::MAGIC);
+        reg
+    }
+
     /// OTP bit selection (0-7).
     ///
     /// Selects which bit to program in the selected byte location.
@@ -61,6 +74,104 @@ impl OtpProg {
     }
 }
 
+/// A programmable OTP bit and the power-up default it controls.
+///
+/// Each variant maps to one `(byte, bit)` location of the OTP memory, as
+/// read back in OTP_READ bit `byte * 8 + bit`. Programming a bit sets it
+/// to 1 permanently. The factory-trimmed FCLKTRIM bits (byte 0, bits 0-4)
+/// are deliberately not listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OtpField {
+    /// OTTRIM: overtemperature threshold 150°C instead of 143°C (0.5).
+    Ottrim,
+    /// Use internal sense resistors by default (0.6).
+    InternalRsense,
+    /// TBL default 0b01 instead of 0b10 (0.7).
+    Tbl,
+    /// PWM_GRAD default, bit 0 (1.0).
+    PwmGrad0,
+    /// PWM_GRAD default, bit 1 (1.1).
+    PwmGrad1,
+    /// PWM_GRAD default, bit 2 (1.2).
+    PwmGrad2,
+    /// PWM_GRAD default, bit 3 (1.3).
+    PwmGrad3,
+    /// PWM_AUTOGRAD enabled by default (1.4).
+    PwmAutograd,
+    /// TPWMTHRS default, bit 0 (1.5).
+    Tpwmthrs0,
+    /// TPWMTHRS default, bit 1 (1.6).
+    Tpwmthrs1,
+    /// TPWMTHRS default, bit 2 (1.7).
+    Tpwmthrs2,
+    /// PWM_OFS default 0 instead of 36 (2.0).
+    PwmOfs,
+    /// PWM_REG default 0b0010 instead of 0b1000 (2.1).
+    PwmReg,
+    /// PWM_FREQ default 0b10 instead of 0b01 (2.2).
+    PwmFreq,
+    /// IHOLDDELAY default, bit 0 (2.3).
+    Iholddelay0,
+    /// IHOLDDELAY default, bit 1 (2.4).
+    Iholddelay1,
+    /// IHOLD default, bit 0 (2.5).
+    Ihold0,
+    /// IHOLD default, bit 1 (2.6).
+    Ihold1,
+    /// SpreadCycle instead of StealthChop by default (2.7).
+    EnSpreadcycle,
+}
+
+impl OtpField {
+    /// Get the `(byte, bit)` location of the field in OTP memory.
+    pub fn location(self) -> (u8, u8) {
+        match self {
+            Self::Ottrim => (0, 5),
+            Self::InternalRsense => (0, 6),
+            Self::Tbl => (0, 7),
+            Self::PwmGrad0 => (1, 0),
+            Self::PwmGrad1 => (1, 1),
+            Self::PwmGrad2 => (1, 2),
+            Self::PwmGrad3 => (1, 3),
+            Self::PwmAutograd => (1, 4),
+            Self::Tpwmthrs0 => (1, 5),
+            Self::Tpwmthrs1 => (1, 6),
+            Self::Tpwmthrs2 => (1, 7),
+            Self::PwmOfs => (2, 0),
+            Self::PwmReg => (2, 1),
+            Self::PwmFreq => (2, 2),
+            Self::Iholddelay0 => (2, 3),
+            Self::Iholddelay1 => (2, 4),
+            Self::Ihold0 => (2, 5),
+            Self::Ihold1 => (2, 6),
+            Self::EnSpreadcycle => (2, 7),
+        }
+    }
+
+    /// Get the mask of the field's bit in OTP_READ.
+    pub fn otp_read_mask(self) -> u32 {
+        let (byte, bit) = self.location();
+        1 << (byte * 8 + bit)
+    }
+}
+
+/// Acknowledgement that OTP programming cannot be undone.
+///
+/// Required by [`Tmc2209::program_otp_field`](crate::Tmc2209::program_otp_field)
+/// so that permanently programming the chip is always spelled out at the
+/// call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OtpConfirm(());
+
+impl OtpConfirm {
+    /// Confirm that the programmed bit stays set for the life of the chip.
+    pub fn irreversible() -> Self {
+        Self(())
+    }
+}
+
 impl Register for OtpProg {
     const ADDRESS: Address = Address::OtpProg;
 }
@@ -78,3 +189,45 @@ impl From<OtpProg> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registers::OtpRead;
+
+    #[test]
+    fn test_otp_field_location() {
+        assert_eq!(OtpField::Ottrim.location(), (0, 5));
+        assert_eq!(OtpField::InternalRsense.location(), (0, 6));
+        assert_eq!(OtpField::PwmAutograd.location(), (1, 4));
+        assert_eq!(OtpField::Tpwmthrs2.location(), (1, 7));
+        assert_eq!(OtpField::PwmOfs.location(), (2, 0));
+        assert_eq!(OtpField::EnSpreadcycle.location(), (2, 7));
+    }
+
+    #[test]
+    fn test_otp_field_matches_otp_read() {
+        let read = |field: OtpField| OtpRead::from_raw(field.otp_read_mask());
+        assert!(read(OtpField::Ottrim).otp_ottrim());
+        assert!(read(OtpField::InternalRsense).otp_internal_rsense());
+        assert!(read(OtpField::Tbl).otp_tbl());
+        assert_eq!(read(OtpField::PwmGrad3).otp_pwm_grad(), 8);
+        assert!(read(OtpField::PwmAutograd).otp_pwm_autograd());
+        assert_eq!(read(OtpField::Tpwmthrs1).otp_tpwmthrs(), 2);
+        assert!(read(OtpField::PwmOfs).otp_pwm_ofs());
+        assert!(read(OtpField::PwmReg).otp_pwm_reg());
+        assert!(read(OtpField::PwmFreq).otp_pwm_freq());
+        assert_eq!(read(OtpField::Iholddelay1).otp_iholddelay(), 2);
+        assert_eq!(read(OtpField::Ihold0).otp_ihold(), 1);
+        assert!(read(OtpField::EnSpreadcycle).otp_en_spreadcycle());
+    }
+
+    #[test]
+    fn test_otp_prog_for_field() {
+        let reg = OtpProg::for_field(OtpField::EnSpreadcycle);
+        assert_eq!(reg.otpbyte(), 2);
+        assert_eq!(reg.otpbit(), 7);
+        assert_eq!(reg.otpmagic(), OtpProg::MAGIC);
+        assert_eq!(reg.raw(), 0xBD27);
+    }
+}
@@ -1,6 +1,6 @@
 //! PWMCONF - StealthChop PWM configuration register (0x70)
 
-use super::{Address, ReadableRegister, Register, StandstillMode, WritableRegister};
+use super::{Address, FieldError, ReadableRegister, Register, StandstillMode, WritableRegister};
 
 /// StealthChop PWM configuration register.
 ///
@@ -11,6 +11,9 @@ use super::{Address, ReadableRegister, Register, StandstillMode, WritableRegiste
 pub struct Pwmconf(u32);
 
 impl Pwmconf {
+    /// Bits holding documented fields; the rest are reserved.
+    pub const FIELD_MASK: u32 = 0xFF3F_FFFF;
+
     /// Default value with StealthChop enabled and auto-tuning.
     /// PWM_OFS=36, PWM_GRAD=14, pwm_freq=1, pwm_autoscale=1, pwm_autograd=1
     pub const DEFAULT: u32 = 0xC10D0024;
@@ -20,6 +23,14 @@ impl Pwmconf {
         Self(Self::DEFAULT)
     }
 
+    /// Start building a PWMCONF value from the defaults.
+    ///
+    /// Unlike the setters, the builder rejects out-of-range values
+    /// instead of masking them.
+    pub fn builder() -> PwmconfBuilder {
+        PwmconfBuilder::new()
+    }
+
     /// Get PWM_OFS (0-255).
     ///
     /// User-defined PWM amplitude offset (0-255).
@@ -161,6 +172,13 @@ impl Pwmconf {
         self
     }
 
+    /// Compare only the documented fields, ignoring reserved bits.
+    ///
+    /// Use this to verify a write by reading the register back.
+    pub fn eq_fields(&self, other: &Self) -> bool {
+        (self.0 ^ other.0) & Self::FIELD_MASK == 0
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -172,6 +190,125 @@ impl Pwmconf {
     }
 }
 
+/// Checked builder for [`Pwmconf`].
+///
+/// Fields not set explicitly keep their value from [`Pwmconf::DEFAULT`].
+///
+/// ```
+/// use tmc2209_uart::registers::{Pwmconf, StandstillMode};
+///
+/// let pwmconf = Pwmconf::builder()
+///     .pwm_ofs(36)
+///     .pwm_freq(1)
+///     .pwm_autoscale(true)
+///     .freewheel(StandstillMode::Freewheeling)
+///     .pwm_reg(4)
+///     .build()
+///     .unwrap();
+/// assert_eq!(pwmconf.pwm_reg(), 4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PwmconfBuilder {
+    pwm_ofs: u8,
+    pwm_grad: u8,
+    pwm_freq: u8,
+    pwm_autoscale: bool,
+    pwm_autograd: bool,
+    freewheel: StandstillMode,
+    pwm_reg: u8,
+    pwm_lim: u8,
+}
+
+impl PwmconfBuilder {
+    /// Create a builder initialized from [`Pwmconf::DEFAULT`].
+    pub fn new() -> Self {
+        let reg = Pwmconf::new();
+        Self {
+            pwm_ofs: reg.pwm_ofs(),
+            pwm_grad: reg.pwm_grad(),
+            pwm_freq: reg.pwm_freq(),
+            pwm_autoscale: reg.pwm_autoscale(),
+            pwm_autograd: reg.pwm_autograd(),
+            freewheel: reg.standstill_mode(),
+            pwm_reg: reg.pwm_reg(),
+            pwm_lim: reg.pwm_lim(),
+        }
+    }
+
+    /// Set PWM_OFS (0-255).
+    pub fn pwm_ofs(mut self, value: u8) -> Self {
+        self.pwm_ofs = value;
+        self
+    }
+
+    /// Set PWM_GRAD (0-255).
+    pub fn pwm_grad(mut self, value: u8) -> Self {
+        self.pwm_grad = value;
+        self
+    }
+
+    /// Set PWM_FREQ (0-3).
+    pub fn pwm_freq(mut self, value: u8) -> Self {
+        self.pwm_freq = value;
+        self
+    }
+
+    /// Set PWM_AUTOSCALE.
+    pub fn pwm_autoscale(mut self, value: bool) -> Self {
+        self.pwm_autoscale = value;
+        self
+    }
+
+    /// Set PWM_AUTOGRAD.
+    pub fn pwm_autograd(mut self, value: bool) -> Self {
+        self.pwm_autograd = value;
+        self
+    }
+
+    /// Set the standstill (FREEWHEEL) mode.
+    pub fn freewheel(mut self, mode: StandstillMode) -> Self {
+        self.freewheel = mode;
+        self
+    }
+
+    /// Set PWM_REG (0-15).
+    pub fn pwm_reg(mut self, value: u8) -> Self {
+        self.pwm_reg = value;
+        self
+    }
+
+    /// Set PWM_LIM (0-15).
+    pub fn pwm_lim(mut self, value: u8) -> Self {
+        self.pwm_lim = value;
+        self
+    }
+
+    /// Validate the fields and assemble the register value.
+    pub fn build(self) -> Result<Pwmconf, FieldError> {
+        FieldError::check("pwm_freq", self.pwm_freq as u32, 3)?;
+        FieldError::check("pwm_reg", self.pwm_reg as u32, 15)?;
+        FieldError::check("pwm_lim", self.pwm_lim as u32, 15)?;
+
+        let mut reg = Pwmconf::from_raw(0);
+        reg.set_pwm_ofs(self.pwm_ofs)
+            .set_pwm_grad(self.pwm_grad)
+            .set_pwm_freq(self.pwm_freq)
+            .set_pwm_autoscale(self.pwm_autoscale)
+            .set_pwm_autograd(self.pwm_autograd)
+            .set_standstill_mode(self.freewheel)
+            .set_pwm_reg(self.pwm_reg)
+            .set_pwm_lim(self.pwm_lim);
+        Ok(reg)
+    }
+}
+
+impl Default for PwmconfBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Default for Pwmconf {
     fn default() -> Self {
         Self::new()
@@ -180,6 +317,7 @@ impl Default for Pwmconf {
 
 impl Register for Pwmconf {
     const ADDRESS: Address = Address::Pwmconf;
+    const FIELD_MASK: u32 = Pwmconf::FIELD_MASK;
 }
 
 impl ReadableRegister for Pwmconf {}
@@ -196,3 +334,67 @@ impl From<Pwmconf> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eq_fields() {
+        let a = Pwmconf::new();
+        // Bits 22-23 are reserved
+        let b = Pwmconf::from_raw(a.raw() | (0x03 << 22));
+        assert_ne!(a, b);
+        assert!(a.eq_fields(&b));
+
+        let mut c = a;
+        c.set_pwm_ofs(40);
+        assert!(!a.eq_fields(&c));
+    }
+
+    #[test]
+    fn test_builder_full() {
+        let reg = Pwmconf::builder()
+            .pwm_ofs(50)
+            .pwm_grad(20)
+            .pwm_freq(2)
+            .pwm_autoscale(false)
+            .pwm_autograd(true)
+            .freewheel(StandstillMode::Braking)
+            .pwm_reg(8)
+            .pwm_lim(10)
+            .build()
+            .unwrap();
+
+        assert_eq!(reg.pwm_ofs(), 50);
+        assert_eq!(reg.pwm_grad(), 20);
+        assert_eq!(reg.pwm_freq(), 2);
+        assert!(!reg.pwm_autoscale());
+        assert!(reg.pwm_autograd());
+        assert_eq!(reg.standstill_mode(), StandstillMode::Braking);
+        assert_eq!(reg.pwm_reg(), 8);
+        assert_eq!(reg.pwm_lim(), 10);
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        assert_eq!(Pwmconf::builder().build(), Ok(Pwmconf::new()));
+    }
+
+    #[test]
+    fn test_builder_out_of_range() {
+        let err = Pwmconf::builder().pwm_freq(4).build().unwrap_err();
+        assert_eq!(
+            err,
+            FieldError {
+                field: "pwm_freq",
+                value: 4,
+                max: 3
+            }
+        );
+        assert_eq!(
+            Pwmconf::builder().pwm_lim(16).build().unwrap_err().field,
+            "pwm_lim"
+        );
+    }
+}
@@ -31,6 +31,24 @@ impl Slaveconf {
         self
     }
 
+    /// Get the reply delay in bit times for the current SENDDELAY.
+    pub fn bit_times(&self) -> u16 {
+        Self::senddelay_to_bit_times(self.senddelay())
+    }
+
+    /// Decode a SENDDELAY value (0-15) into bit times.
+    pub fn senddelay_to_bit_times(senddelay: u8) -> u16 {
+        ((senddelay.min(15) as u16 / 2) * 2 + 1) * 8
+    }
+
+    /// Get the smallest SENDDELAY giving at least `bit_times` of delay.
+    ///
+    /// Saturates at 14 (15×8 bit times).
+    pub fn senddelay_for_bit_times(bit_times: u16) -> u8 {
+        let units = bit_times.div_ceil(8).max(1);
+        ((units - 1).div_ceil(2) * 2).min(14) as u8
+    }
+
     /// Get the raw register value.
     pub fn raw(&self) -> u32 {
         self.0
@@ -59,3 +77,28 @@ impl From<Slaveconf> for u32 {
         reg.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_times() {
+        assert_eq!(Slaveconf::senddelay_to_bit_times(0), 8);
+        assert_eq!(Slaveconf::senddelay_to_bit_times(1), 8);
+        assert_eq!(Slaveconf::senddelay_to_bit_times(2), 24);
+        assert_eq!(Slaveconf::senddelay_to_bit_times(7), 56);
+        assert_eq!(Slaveconf::senddelay_to_bit_times(15), 120);
+
+        assert_eq!(Slaveconf::senddelay_for_bit_times(0), 0);
+        assert_eq!(Slaveconf::senddelay_for_bit_times(8), 0);
+        assert_eq!(Slaveconf::senddelay_for_bit_times(9), 2);
+        assert_eq!(Slaveconf::senddelay_for_bit_times(24), 2);
+        assert_eq!(Slaveconf::senddelay_for_bit_times(25), 4);
+        assert_eq!(Slaveconf::senddelay_for_bit_times(1000), 14);
+
+        let mut reg = Slaveconf::default();
+        reg.set_senddelay(5);
+        assert_eq!(reg.bit_times(), 40);
+    }
+}
@@ -10,6 +10,12 @@ use super::{Address, Register, WritableRegister};
 pub struct Vactual(u32);
 
 impl Vactual {
+    /// Largest velocity value (2^23 - 1).
+    pub const MAX: i32 = 0x7F_FFFF;
+
+    /// Smallest velocity value (-(2^23 - 1)).
+    pub const MIN: i32 = -Self::MAX;
+
     /// Create a new Vactual with velocity 0 (motor stopped, STEP input active).
     pub fn new() -> Self {
         Self(0)
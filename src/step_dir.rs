@@ -0,0 +1,166 @@
+//! STEP/DIR motion helper.
+//!
+//! Many systems configure the TMC2209 over UART but generate motion on the
+//! STEP and DIR pins. [`StepDir`] wraps the two output pins so that motion
+//! code does not have to toggle them by hand.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+/// Time STEP is held high, and then low, by [`StepDir::step`].
+///
+/// The TMC2209 needs STEP high and low for at least one clock period plus
+/// 20 ns each, about 105 ns at 12 MHz. 200 ns leaves margin for clock
+/// tolerance.
+pub const STEP_PULSE_NS: u32 = 200;
+
+/// STEP and DIR output pins.
+///
+/// Each rising edge on STEP advances the motor by one microstep in the
+/// direction selected by DIR.
+pub struct StepDir<S, D> {
+    step: S,
+    dir: D,
+    forward: bool,
+}
+
+impl<S: OutputPin, D: OutputPin> StepDir<S, D> {
+    /// Wrap the STEP and DIR pins.
+    ///
+    /// DIR is not driven until [`set_direction`](Self::set_direction) is
+    /// called; the direction is assumed to be forward (DIR low).
+    pub fn new(step: S, dir: D) -> Self {
+        Self {
+            step,
+            dir,
+            forward: true,
+        }
+    }
+
+    /// Emit one STEP pulse.
+    ///
+    /// STEP is held high and then low for [`STEP_PULSE_NS`] each, so
+    /// back-to-back calls meet the TMC2209 timing.
+    pub fn step(&mut self, delay: &mut impl DelayNs) -> Result<(), S::Error> {
+        self.step.set_high()?;
+        delay.delay_ns(STEP_PULSE_NS);
+        self.step.set_low()?;
+        delay.delay_ns(STEP_PULSE_NS);
+        Ok(())
+    }
+
+    /// Emit `count` STEP pulses, waiting `interval_ns` after each one.
+    ///
+    /// The interval sets the step rate.
+    pub fn steps(
+        &mut self,
+        count: u32,
+        delay: &mut impl DelayNs,
+        interval_ns: u32,
+    ) -> Result<(), S::Error> {
+        for _ in 0..count {
+            self.step(delay)?;
+            delay.delay_ns(interval_ns);
+        }
+        Ok(())
+    }
+
+    /// Set the direction.
+    ///
+    /// `true` drives DIR low (forward), `false` drives it high. GCONF.shaft
+    /// inverts the motor direction for both levels.
+    pub fn set_direction(&mut self, forward: bool) -> Result<(), D::Error> {
+        if forward {
+            self.dir.set_low()?;
+        } else {
+            self.dir.set_high()?;
+        }
+        self.forward = forward;
+        Ok(())
+    }
+
+    /// Get the last direction set.
+    pub fn direction(&self) -> bool {
+        self.forward
+    }
+
+    /// Release the STEP and DIR pins.
+    pub fn release(self) -> (S, D) {
+        (self.step, self.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+
+    /// Pin counting rising edges.
+    #[derive(Default)]
+    struct MockPin {
+        high: bool,
+        rising_edges: u32,
+    }
+
+    impl embedded_hal::digital::ErrorType for MockPin {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for MockPin {
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            if !self.high {
+                self.rising_edges += 1;
+            }
+            self.high = true;
+            Ok(())
+        }
+
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.high = false;
+            Ok(())
+        }
+    }
+
+    /// Delay recording the total time waited.
+    #[derive(Default)]
+    struct MockDelay {
+        ns: u64,
+    }
+
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.ns += ns as u64;
+        }
+    }
+
+    #[test]
+    fn test_steps() {
+        let mut motor = StepDir::new(MockPin::default(), MockPin::default());
+        let mut delay = MockDelay::default();
+
+        motor.step(&mut delay).unwrap();
+        assert_eq!(delay.ns, 2 * STEP_PULSE_NS as u64);
+
+        delay.ns = 0;
+        motor.steps(10, &mut delay, 1000).unwrap();
+        assert_eq!(delay.ns, 10 * (2 * STEP_PULSE_NS as u64 + 1000));
+
+        let (step, _) = motor.release();
+        assert_eq!(step.rising_edges, 11);
+        assert!(!step.high);
+    }
+
+    #[test]
+    fn test_set_direction() {
+        let mut motor = StepDir::new(MockPin::default(), MockPin::default());
+        assert!(motor.direction());
+
+        motor.set_direction(false).unwrap();
+        assert!(!motor.direction());
+        assert!(motor.dir.high);
+
+        motor.set_direction(true).unwrap();
+        assert!(motor.direction());
+        assert!(!motor.dir.high);
+    }
+}
@@ -0,0 +1,366 @@
+//! Fixed-capacity capture of driver readings.
+//!
+//! [`SgLogger`] records SG_RESULT samples into a ring buffer, e.g. from a
+//! periodic timer during a move, for offline stall threshold analysis.
+//! [`MotorState`] bundles the electrical state of the motor into a single
+//! frame, e.g. for streaming to a host-side visualizer. [`RegisterPoller`]
+//! reads one register of a list per call, spreading periodic telemetry
+//! over many ticks.
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::driver::Tmc2209;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::error::Error;
+use crate::registers::{Address, DrvStatus, Mscnt, Mscuract, SgResult, Tstep};
+
+/// Electrical state of the motor from one telemetry poll.
+///
+/// Returned by [`Tmc2209::motor_state`]. The registers are read one after
+/// another, so the fields are not sampled at exactly the same instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotorState {
+    /// Coil A current from MSCURACT (-255 to 255).
+    pub cur_a: i16,
+    /// Coil B current from MSCURACT (-255 to 255).
+    pub cur_b: i16,
+    /// Microstep counter from MSCNT (0-1023).
+    pub mscnt: u16,
+    /// Raw TSTEP value (0xFFFFF at standstill).
+    pub tstep: u32,
+    /// Velocity in microsteps per second derived from TSTEP, or `None` at
+    /// standstill.
+    pub usteps_per_sec: Option<f32>,
+    /// StallGuard result from SG_RESULT.
+    pub sg_result: u16,
+    /// Actual current scale from DRV_STATUS (0-31).
+    pub cs_actual: u8,
+    /// Raw DRV_STATUS value, see [`drv_status`](Self::drv_status).
+    pub drv_status: u32,
+}
+
+impl MotorState {
+    /// Assemble a frame from register values.
+    ///
+    /// `fclk` is the internal clock frequency used to convert TSTEP.
+    pub fn from_registers(
+        mscuract: Mscuract,
+        mscnt: Mscnt,
+        tstep: Tstep,
+        sg_result: SgResult,
+        drv_status: DrvStatus,
+        fclk: u32,
+    ) -> Self {
+        let usteps_per_sec = if tstep.is_standstill() || tstep.value() == 0 {
+            None
+        } else {
+            Some(fclk as f32 / tstep.value() as f32)
+        };
+        Self {
+            cur_a: mscuract.cur_a(),
+            cur_b: mscuract.cur_b(),
+            mscnt: mscnt.count(),
+            tstep: tstep.value(),
+            usteps_per_sec,
+            sg_result: sg_result.result(),
+            cs_actual: drv_status.cs_actual(),
+            drv_status: drv_status.raw(),
+        }
+    }
+
+    /// Get the status flags as a [`DrvStatus`] register.
+    pub fn drv_status(&self) -> DrvStatus {
+        DrvStatus::from_raw(self.drv_status)
+    }
+}
+
+/// Ring buffer of the last `N` SG_RESULT samples.
+///
+/// Once full, each new sample overwrites the oldest one.
+#[derive(Debug, Clone)]
+pub struct SgLogger<const N: usize> {
+    samples: [u16; N],
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> SgLogger<N> {
+    /// Create an empty logger.
+    pub const fn new() -> Self {
+        Self {
+            samples: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Append a sample, dropping the oldest one if full.
+    pub fn push(&mut self, sample: u16) {
+        if N == 0 {
+            return;
+        }
+        self.samples[self.head] = sample;
+        self.head = (self.head + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Get the number of samples stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if no samples are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check if the buffer is full, i.e. the next sample drops the oldest.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Get the maximum number of samples.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Discard all samples.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Get a sample by age, 0 being the oldest.
+    pub fn get(&self, index: usize) -> Option<u16> {
+        if index >= self.len {
+            return None;
+        }
+        let start = (self.head + N - self.len) % N;
+        Some(self.samples[(start + index) % N])
+    }
+
+    /// Iterate over the samples from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..self.len).filter_map(move |i| self.get(i))
+    }
+
+    /// Read SG_RESULT and append it (blocking).
+    #[cfg(feature = "blocking")]
+    pub fn record<U, E, D>(&mut self, driver: &mut Tmc2209<U, D>) -> Result<(), Error<E>>
+    where
+        U: embedded_io::Read<Error = E> + embedded_io::Write<Error = E>,
+        D: FnMut(),
+    {
+        let sample = driver.read_register::<SgResult>()?.result();
+        self.push(sample);
+        Ok(())
+    }
+
+    /// Read SG_RESULT and append it (async).
+    #[cfg(feature = "async")]
+    pub async fn record_async<U, E, D>(
+        &mut self,
+        driver: &mut Tmc2209<U, D>,
+    ) -> Result<(), Error<E>>
+    where
+        U: embedded_io_async::Read<Error = E> + embedded_io_async::Write<Error = E>,
+        D: FnMut(),
+    {
+        let sample = driver.read_register_async::<SgResult>().await?.result();
+        self.push(sample);
+        Ok(())
+    }
+}
+
+impl<const N: usize> Default for SgLogger<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Round-robin reader over a list of registers.
+///
+/// Each [`poll_next`](Self::poll_next) reads the next register in the
+/// list, wrapping around at the end, so a periodic task costs one
+/// datagram per tick instead of a burst of reads.
+#[derive(Debug, Clone)]
+pub struct RegisterPoller<'a> {
+    addresses: &'a [Address],
+    cursor: usize,
+}
+
+impl<'a> RegisterPoller<'a> {
+    /// Create a poller that starts at the first address.
+    pub const fn new(addresses: &'a [Address]) -> Self {
+        Self {
+            addresses,
+            cursor: 0,
+        }
+    }
+
+    /// Get the address read by the next poll, if the list is not empty.
+    pub fn peek(&self) -> Option<Address> {
+        self.addresses.get(self.cursor).copied()
+    }
+
+    /// Restart at the first address.
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Take the next address and advance the cursor.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn advance(&mut self) -> Option<Address> {
+        let addr = self.peek()?;
+        self.cursor = (self.cursor + 1) % self.addresses.len();
+        Some(addr)
+    }
+
+    /// Read the next register in the list (blocking).
+    ///
+    /// The cursor advances even if the read fails, so one unresponsive
+    /// register does not stall the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidArgument` if the address list is empty.
+    #[cfg(feature = "blocking")]
+    pub fn poll_next<U, E, D>(
+        &mut self,
+        driver: &mut Tmc2209<U, D>,
+    ) -> Result<(Address, u32), Error<E>>
+    where
+        U: embedded_io::Read<Error = E> + embedded_io::Write<Error = E>,
+        D: FnMut(),
+    {
+        let addr = self.advance().ok_or(Error::InvalidArgument)?;
+        Ok((addr, driver.read_raw(addr as u8)?))
+    }
+
+    /// Read the next register in the list (async).
+    ///
+    /// See [`poll_next`](Self::poll_next).
+    #[cfg(feature = "async")]
+    pub async fn poll_next_async<U, E, D>(
+        &mut self,
+        driver: &mut Tmc2209<U, D>,
+    ) -> Result<(Address, u32), Error<E>>
+    where
+        U: embedded_io_async::Read<Error = E> + embedded_io_async::Write<Error = E>,
+        D: FnMut(),
+    {
+        let addr = self.advance().ok_or(Error::InvalidArgument)?;
+        Ok((addr, driver.read_raw_async(addr as u8).await?))
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::mock::MockUart;
+    use crate::registers::Address;
+
+    #[test]
+    fn test_sg_logger_fill_and_wrap() {
+        let mut logger = SgLogger::<4>::new();
+        assert!(logger.is_empty());
+
+        for sample in [10, 20, 30] {
+            logger.push(sample);
+        }
+        assert_eq!(logger.len(), 3);
+        assert!(!logger.is_full());
+        assert!(logger.iter().eq([10, 20, 30]));
+
+        for sample in [40, 50, 60] {
+            logger.push(sample);
+        }
+        assert!(logger.is_full());
+        assert!(logger.iter().eq([30, 40, 50, 60]));
+        assert_eq!(logger.get(0), Some(30));
+        assert_eq!(logger.get(3), Some(60));
+        assert_eq!(logger.get(4), None);
+
+        logger.clear();
+        assert!(logger.is_empty());
+        assert_eq!(logger.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_sg_logger_record() {
+        let mut uart = MockUart::new();
+        for sg in [300, 280, 150] {
+            uart.script(Address::SgResult as u8, sg);
+        }
+        let mut driver = Tmc2209::new(uart, 0);
+        let mut logger = SgLogger::<2>::new();
+
+        for _ in 0..3 {
+            logger.record(&mut driver).unwrap();
+        }
+        assert!(logger.iter().eq([280, 150]));
+    }
+
+    #[test]
+    fn test_register_poller_cycles() {
+        let mut uart = MockUart::new();
+        uart.regs[Address::Gconf as usize] = 0x40;
+        uart.regs[Address::Tstep as usize] = 1234;
+        uart.regs[Address::DrvStatus as usize] = 0x8000_0000;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let list = [Address::Gconf, Address::Tstep, Address::DrvStatus];
+        let mut poller = RegisterPoller::new(&list);
+        let expected = [
+            (Address::Gconf, 0x40),
+            (Address::Tstep, 1234),
+            (Address::DrvStatus, 0x8000_0000),
+        ];
+        for round in 0..2 {
+            for &(addr, value) in &expected {
+                assert_eq!(
+                    poller.poll_next(&mut driver),
+                    Ok((addr, value)),
+                    "{}",
+                    round
+                );
+            }
+        }
+        assert_eq!(driver.uart().reads, 6);
+
+        poller.poll_next(&mut driver).unwrap();
+        assert_eq!(poller.peek(), Some(Address::Tstep));
+        poller.reset();
+        assert_eq!(poller.peek(), Some(Address::Gconf));
+    }
+
+    #[test]
+    fn test_register_poller_advances_on_error() {
+        let mut uart = MockUart::new();
+        uart.corrupt_crc = 1;
+        let mut driver = Tmc2209::new(uart, 0);
+
+        let list = [Address::Gconf, Address::Ifcnt];
+        let mut poller = RegisterPoller::new(&list);
+        assert_eq!(poller.poll_next(&mut driver), Err(Error::CrcMismatch));
+        assert_eq!(poller.poll_next(&mut driver), Ok((Address::Ifcnt, 0)));
+    }
+
+    #[test]
+    fn test_register_poller_empty() {
+        let mut driver = Tmc2209::new(MockUart::new(), 0);
+        let mut poller = RegisterPoller::new(&[]);
+        assert_eq!(poller.peek(), None);
+        assert_eq!(poller.poll_next(&mut driver), Err(Error::InvalidArgument));
+        assert!(driver.uart().tx().is_empty());
+    }
+
+    #[test]
+    fn test_sg_logger_zero_capacity() {
+        let mut logger = SgLogger::<0>::new();
+        logger.push(1);
+        assert!(logger.is_empty());
+        assert_eq!(logger.get(0), None);
+    }
+}
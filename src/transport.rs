@@ -0,0 +1,344 @@
+//! Packet-oriented transports.
+//!
+//! Some links to the TMC2209 are not plain byte streams: a USB-serial
+//! bridge with its own framing, a host-side test harness, or a remote
+//! gateway. Such a link implements [`Tmc2209Transport`], exchanging one
+//! request datagram for its reply, and [`TransportUart`] adapts it to the
+//! `embedded_io` traits so the whole [`Tmc2209`] API works over it. Links
+//! that are driven from an executor implement [`Tmc2209TransportAsync`]
+//! instead, and [`TransportUart`] then implements the `embedded_io_async`
+//! traits for the `_async` API.
+
+#[cfg(feature = "blocking")]
+use embedded_io::{ErrorType, Read, ReadReady, Write};
+#[cfg(not(feature = "blocking"))]
+use embedded_io_async::{ErrorType, ReadReady};
+
+use crate::datagram::{decode_addr, ReadRequest, ReadResponse, WriteRequest};
+use crate::driver::Tmc2209;
+
+/// A link that exchanges whole datagrams with the TMC2209.
+#[cfg(feature = "blocking")]
+pub trait Tmc2209Transport: ErrorType {
+    /// Send one request datagram and receive its reply.
+    ///
+    /// `request` is a complete 4-byte read or 8-byte write request. For a
+    /// read, the 8-byte reply is stored in `response`; writes have no
+    /// reply. The transport is responsible for removing the echo on
+    /// single-wire links. Returns the number of reply bytes stored.
+    fn transact(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// A link that exchanges whole datagrams with the TMC2209 (async).
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait Tmc2209TransportAsync: ErrorType {
+    /// Send one request datagram and receive its reply (async).
+    ///
+    /// Same contract as the blocking `Tmc2209Transport::transact`: a read
+    /// stores its 8-byte reply in `response`, a write has none, and the
+    /// echo must already be removed. Returns the number of reply bytes
+    /// stored.
+    async fn transact(&mut self, request: &[u8], response: &mut [u8])
+        -> Result<usize, Self::Error>;
+}
+
+/// Byte stream adapter for a [`Tmc2209Transport`] or [`Tmc2209TransportAsync`].
+///
+/// Transmitted bytes are collected until a request datagram is complete,
+/// which is then passed to the transport's `transact`; the reply is
+/// served to subsequent reads. No echo is produced.
+pub struct TransportUart<T> {
+    transport: T,
+    tx: [u8; WriteRequest::LEN],
+    tx_len: usize,
+    rx: [u8; ReadResponse::LEN],
+    rx_len: usize,
+    rx_pos: usize,
+}
+
+impl<T> TransportUart<T> {
+    /// Wrap a transport.
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            tx: [0; WriteRequest::LEN],
+            tx_len: 0,
+            rx: [0; ReadResponse::LEN],
+            rx_len: 0,
+            rx_pos: 0,
+        }
+    }
+
+    /// Get a reference to the transport.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Get a mutable reference to the transport.
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Release the transport.
+    pub fn release(self) -> T {
+        self.transport
+    }
+
+    /// Get the length of the request being collected, once known.
+    fn request_len(&self) -> Option<usize> {
+        if self.tx_len < 3 {
+            return None;
+        }
+        match decode_addr(self.tx[2]) {
+            (_, true) => Some(WriteRequest::LEN),
+            (_, false) => Some(ReadRequest::LEN),
+        }
+    }
+
+    /// Collect a transmitted byte.
+    ///
+    /// Returns the request length once a datagram is complete. The
+    /// collector and the previous reply are reset at that point, so the
+    /// next request starts over even if the exchange fails.
+    fn push_tx(&mut self, byte: u8) -> Option<usize> {
+        self.tx[self.tx_len] = byte;
+        self.tx_len += 1;
+        let len = self.request_len().filter(|&len| len == self.tx_len)?;
+        self.tx_len = 0;
+        self.rx_len = 0;
+        self.rx_pos = 0;
+        Some(len)
+    }
+
+    /// Serve reply bytes to a read.
+    fn pop_rx(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.rx_len - self.rx_pos);
+        buf[..n].copy_from_slice(&self.rx[self.rx_pos..self.rx_pos + n]);
+        self.rx_pos += n;
+        n
+    }
+}
+
+impl<T: ErrorType> ErrorType for TransportUart<T> {
+    type Error = T::Error;
+}
+
+#[cfg(feature = "blocking")]
+impl<T: Tmc2209Transport> Write for TransportUart<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        for &byte in buf {
+            if let Some(len) = self.push_tx(byte) {
+                let n = self.transport.transact(&self.tx[..len], &mut self.rx)?;
+                self.rx_len = n.min(self.rx.len());
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: Tmc2209Transport> Read for TransportUart<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.pop_rx(buf))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: Tmc2209TransportAsync> embedded_io_async::Write for TransportUart<T> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        for &byte in buf {
+            if let Some(len) = self.push_tx(byte) {
+                let n = self
+                    .transport
+                    .transact(&self.tx[..len], &mut self.rx)
+                    .await?;
+                self.rx_len = n.min(self.rx.len());
+            }
+        }
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: Tmc2209TransportAsync> embedded_io_async::Read for TransportUart<T> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.pop_rx(buf))
+    }
+}
+
+impl<T: ErrorType> ReadReady for TransportUart<T> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.rx_pos < self.rx_len)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: Tmc2209Transport> Tmc2209<TransportUart<T>> {
+    /// Create a driver that talks through a packet-oriented transport.
+    ///
+    /// Echo handling is disabled since the transport returns only the
+    /// reply.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slave_addr` is greater than 3.
+    pub fn new_with_transport(transport: T, slave_addr: u8) -> Self {
+        let mut driver = Self::new(TransportUart::new(transport), slave_addr);
+        driver.set_echo_enabled(false);
+        driver
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: Tmc2209TransportAsync> Tmc2209<TransportUart<T>> {
+    /// Create a driver that talks through an async packet-oriented
+    /// transport.
+    ///
+    /// See [`new_with_transport`](Self::new_with_transport).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slave_addr` is greater than 3.
+    pub fn new_with_transport_async(transport: T, slave_addr: u8) -> Self {
+        let mut driver = Self::new(TransportUart::new(transport), slave_addr);
+        driver.set_echo_enabled(false);
+        driver
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::crc;
+    use crate::datagram::{MASTER_ADDR, SYNC};
+    use crate::registers::{Address, Chopconf, Gconf};
+    use crate::Error;
+
+    /// Transport answering from a register file, as a bridge would.
+    struct MockTransport {
+        regs: [u32; 128],
+        transactions: usize,
+        fail: bool,
+    }
+
+    impl MockTransport {
+        fn new() -> Self {
+            Self {
+                regs: [0; 128],
+                transactions: 0,
+                fail: false,
+            }
+        }
+    }
+
+    impl ErrorType for MockTransport {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    #[cfg(feature = "async")]
+    impl Tmc2209TransportAsync for MockTransport {
+        async fn transact(
+            &mut self,
+            request: &[u8],
+            response: &mut [u8],
+        ) -> Result<usize, Self::Error> {
+            Tmc2209Transport::transact(self, request, response)
+        }
+    }
+
+    impl Tmc2209Transport for MockTransport {
+        fn transact(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Self::Error> {
+            if self.fail {
+                return Err(embedded_io::ErrorKind::TimedOut);
+            }
+            self.transactions += 1;
+            let (addr, is_write) = decode_addr(request[2]);
+            if is_write {
+                let data = u32::from_be_bytes([request[3], request[4], request[5], request[6]]);
+                self.regs[addr as usize] = data;
+                return Ok(0);
+            }
+            let data = self.regs[addr as usize].to_be_bytes();
+            let mut reply = [
+                SYNC,
+                MASTER_ADDR,
+                addr,
+                data[0],
+                data[1],
+                data[2],
+                data[3],
+                0,
+            ];
+            reply[7] = crc::compute(&reply[..7]);
+            response[..8].copy_from_slice(&reply);
+            Ok(8)
+        }
+    }
+
+    #[test]
+    fn test_read_through_transport() {
+        let mut transport = MockTransport::new();
+        transport.regs[Address::Gconf as usize] = 0x1C1;
+        let mut driver = Tmc2209::new_with_transport(transport, 0);
+
+        assert_eq!(driver.read_register::<Gconf>().unwrap().raw(), 0x1C1);
+        assert_eq!(driver.uart().transport().transactions, 1);
+    }
+
+    #[test]
+    fn test_write_through_transport() {
+        let mut driver = Tmc2209::new_with_transport(MockTransport::new(), 0);
+        driver.write_register(&Chopconf::new()).unwrap();
+        assert_eq!(
+            driver.uart().transport().regs[Address::Chopconf as usize],
+            Chopconf::DEFAULT
+        );
+
+        // Read-modify-write helpers work unchanged
+        driver.set_interpolation(false).unwrap();
+        let chopconf = driver.read_register::<Chopconf>().unwrap();
+        assert!(!chopconf.intpol());
+        assert_eq!(driver.uart().transport().transactions, 4);
+    }
+
+    #[test]
+    fn test_transport_error() {
+        let mut driver = Tmc2209::new_with_transport(MockTransport::new(), 0);
+        driver.uart_mut().transport_mut().fail = true;
+        assert_eq!(
+            driver.read_register::<Gconf>(),
+            Err(Error::Uart(embedded_io::ErrorKind::TimedOut))
+        );
+
+        // The next request starts cleanly
+        driver.uart_mut().transport_mut().fail = false;
+        assert!(driver.read_register::<Gconf>().is_ok());
+    }
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_transport_async() {
+        use crate::mock::block_on;
+
+        let mut transport = MockTransport::new();
+        transport.regs[Address::Gconf as usize] = 0x1C1;
+        let mut driver = Tmc2209::new_with_transport_async(transport, 0);
+
+        let gconf = block_on(driver.read_register_async::<Gconf>()).unwrap();
+        assert_eq!(gconf.raw(), 0x1C1);
+        block_on(driver.write_register_async(&Chopconf::new())).unwrap();
+        assert_eq!(
+            driver.uart().transport().regs[Address::Chopconf as usize],
+            Chopconf::DEFAULT
+        );
+        assert_eq!(driver.uart().transport().transactions, 2);
+    }
+}
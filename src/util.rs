@@ -3,15 +3,67 @@
 //! This module provides helper functions for common calculations like
 //! RMS current, velocity conversions, etc.
 
+use core::f32::consts::SQRT_2;
+
+use crate::registers::{MicrostepResolution, Vactual};
+
 /// Default sense resistor value in ohms (common value).
 pub const DEFAULT_RSENSE: f32 = 0.11;
 
 /// Internal voltage reference for current sensing (in volts).
 pub const VREF: f32 = 0.325;
 
+/// Nominal full-scale sense voltage with VSENSE=0 (in volts).
+pub const VFS_LOW: f32 = 0.325;
+
+/// Nominal full-scale sense voltage with VSENSE=1 (in volts).
+pub const VFS_HIGH: f32 = 0.180;
+
+/// Current sensing parameters of a board.
+///
+/// The plain current functions use the nominal datasheet full-scale
+/// voltages. Use this with the `*_with` functions when the actual
+/// reference has been measured and differs from nominal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CurrentSenseParams {
+    /// Full-scale sense voltage with VSENSE=0 (in volts).
+    pub vfs_low: f32,
+    /// Full-scale sense voltage with VSENSE=1 (in volts).
+    pub vfs_high: f32,
+    /// Sense resistor value in ohms.
+    pub rsense: f32,
+}
+
+impl CurrentSenseParams {
+    /// Nominal full-scale voltages with the given sense resistor.
+    pub const fn new(rsense: f32) -> Self {
+        Self {
+            vfs_low: VFS_LOW,
+            vfs_high: VFS_HIGH,
+            rsense,
+        }
+    }
+
+    /// Full-scale sense voltage for a VSENSE setting.
+    pub fn vfs(&self, vsense: bool) -> f32 {
+        if vsense {
+            self.vfs_high
+        } else {
+            self.vfs_low
+        }
+    }
+}
+
+impl Default for CurrentSenseParams {
+    fn default() -> Self {
+        Self::new(DEFAULT_RSENSE)
+    }
+}
+
 /// Round a f32 value to the nearest integer (no_std compatible).
 #[inline]
-fn round_f32(x: f32) -> f32 {
+pub(crate) fn round_f32(x: f32) -> f32 {
     // Simple rounding: add 0.5 and truncate for positive, subtract 0.5 for negative
     if x >= 0.0 {
         (x + 0.5) as i32 as f32
@@ -44,12 +96,21 @@ fn round_f32(x: f32) -> f32 {
 /// Solving for CS:
 ///   CS = (I_RMS * sqrt(2) * R_SENSE * 32 / V_FS) - 1
 pub fn current_to_cs(rms_current_ma: u16, rsense: f32, vsense: bool) -> Option<u8> {
+    current_to_cs_with(&CurrentSenseParams::new(rsense), rms_current_ma, vsense)
+}
+
+/// Calculate the CS value for a given RMS current with custom sense parameters.
+///
+/// See [`current_to_cs`].
+pub fn current_to_cs_with(
+    params: &CurrentSenseParams,
+    rms_current_ma: u16,
+    vsense: bool,
+) -> Option<u8> {
     let rms_current = rms_current_ma as f32 / 1000.0;
-    let vfs = if vsense { 0.180 } else { 0.325 };
+    let vfs = params.vfs(vsense);
 
-    // sqrt(2) ≈ 1.41421356
-    let sqrt2 = 1.41421356f32;
-    let cs_float = (rms_current * sqrt2 * rsense * 32.0 / vfs) - 1.0;
+    let cs_float = (rms_current * SQRT_2 * params.rsense * 32.0 / vfs) - 1.0;
 
     if cs_float < 0.0 {
         Some(0)
@@ -72,15 +133,40 @@ pub fn current_to_cs(rms_current_ma: u16, rsense: f32, vsense: bool) -> Option<u
 ///
 /// The RMS current in milliamps.
 pub fn cs_to_current(cs: u8, rsense: f32, vsense: bool) -> u16 {
-    let vfs = if vsense { 0.180 } else { 0.325 };
-    let sqrt2 = 1.41421356f32;
+    cs_to_current_with(&CurrentSenseParams::new(rsense), cs, vsense)
+}
+
+/// Calculate the RMS current for a given CS value with custom sense parameters.
+///
+/// See [`cs_to_current`].
+pub fn cs_to_current_with(params: &CurrentSenseParams, cs: u8, vsense: bool) -> u16 {
+    let vfs = params.vfs(vsense);
 
     let cs = (cs.min(31) + 1) as f32;
-    let rms_current = cs / 32.0 * vfs / (sqrt2 * rsense);
+    let rms_current = cs / 32.0 * vfs / (SQRT_2 * params.rsense);
 
     round_f32(rms_current * 1000.0) as u16
 }
 
+/// Microstep table value at the peak of the sine wave.
+const SINE_PEAK: f32 = 248.0;
+
+/// Convert a MSCURACT coil value to an instantaneous current.
+///
+/// The coil value (-255 to 255) is scaled by the peak current for the
+/// actual current scale `cs`: `cur / 248 * (cs + 1) / 32 * V_FS / R_SENSE`.
+/// This is an approximation: it assumes the default sine table (peak
+/// 248) and nominal full-scale voltages, and ignores the chopper ripple.
+///
+/// # Returns
+///
+/// The signed coil current in milliamps.
+pub fn coil_current_ma(cur: i16, cs: u8, rsense: f32, vsense: bool) -> i16 {
+    let params = CurrentSenseParams::new(rsense);
+    let peak = (cs.min(31) + 1) as f32 / 32.0 * params.vfs(vsense) / params.rsense;
+    round_f32(cur as f32 / SINE_PEAK * peak * 1000.0) as i16
+}
+
 /// Determine optimal VSENSE setting for a given RMS current.
 ///
 /// Returns true if high sensitivity (VSENSE=1) should be used.
@@ -91,8 +177,15 @@ pub fn cs_to_current(cs: u8, rsense: f32, vsense: bool) -> u16 {
 /// * `rms_current_ma` - Desired RMS current in milliamps
 /// * `rsense` - Sense resistor value in ohms
 pub fn optimal_vsense(rms_current_ma: u16, rsense: f32) -> bool {
+    optimal_vsense_with(&CurrentSenseParams::new(rsense), rms_current_ma)
+}
+
+/// Determine optimal VSENSE setting with custom sense parameters.
+///
+/// See [`optimal_vsense`].
+pub fn optimal_vsense_with(params: &CurrentSenseParams, rms_current_ma: u16) -> bool {
     // Calculate max current for VSENSE=1 (high sensitivity)
-    let max_current_vsense1 = cs_to_current(31, rsense, true);
+    let max_current_vsense1 = cs_to_current_with(params, 31, true);
 
     // Use high sensitivity if desired current is within range
     rms_current_ma <= max_current_vsense1
@@ -112,13 +205,91 @@ pub fn optimal_vsense(rms_current_ma: u16, rsense: f32) -> bool {
 ///
 /// A tuple of (CS, VSENSE), or None if current is too high.
 pub fn calculate_current_settings(rms_current_ma: u16, rsense: f32) -> Option<(u8, bool)> {
+    calculate_current_settings_with(&CurrentSenseParams::new(rsense), rms_current_ma)
+}
+
+/// Calculate CS and VSENSE for a target RMS current with custom sense parameters.
+///
+/// See [`calculate_current_settings`].
+pub fn calculate_current_settings_with(
+    params: &CurrentSenseParams,
+    rms_current_ma: u16,
+) -> Option<(u8, bool)> {
     // Try high sensitivity first (better for lower currents)
-    let vsense = optimal_vsense(rms_current_ma, rsense);
-    let cs = current_to_cs(rms_current_ma, rsense, vsense)?;
+    let vsense = optimal_vsense_with(params, rms_current_ma);
+    let cs = current_to_cs_with(params, rms_current_ma, vsense)?;
 
     Some((cs, vsense))
 }
 
+/// Current setting advice returned by [`current_headroom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CurrentAdvice {
+    /// Recommended VSENSE setting (true = high sensitivity).
+    pub vsense: bool,
+    /// CS value (0-31) for the requested current at this VSENSE.
+    ///
+    /// Clamped to 31 when the current is too high.
+    pub cs: u8,
+    /// Maximum achievable RMS current at this VSENSE (CS=31), in milliamps.
+    pub max_current_ma: u16,
+    /// The requested current needs CS > 31 even with low sensitivity.
+    pub too_high: bool,
+}
+
+impl CurrentAdvice {
+    /// Remaining margin between the requested current and the maximum, in milliamps.
+    pub fn headroom_ma(&self, rms_current_ma: u16) -> u16 {
+        self.max_current_ma.saturating_sub(rms_current_ma)
+    }
+}
+
+/// Advise on VSENSE and CS for a target RMS current.
+///
+/// Combines [`optimal_vsense`] and [`current_to_cs`] into one call and
+/// reports how close the setting is to clipping at CS=31.
+///
+/// # Arguments
+///
+/// * `rms_current_ma` - Desired RMS current in milliamps
+/// * `rsense` - Sense resistor value in ohms
+pub fn current_headroom(rms_current_ma: u16, rsense: f32) -> CurrentAdvice {
+    let vsense = optimal_vsense(rms_current_ma, rsense);
+    let max_current_ma = cs_to_current(31, rsense, vsense);
+
+    match current_to_cs(rms_current_ma, rsense, vsense) {
+        Some(cs) => CurrentAdvice {
+            vsense,
+            cs,
+            max_current_ma,
+            too_high: false,
+        },
+        None => CurrentAdvice {
+            vsense,
+            cs: 31,
+            max_current_ma,
+            too_high: true,
+        },
+    }
+}
+
+/// Estimate the power dissipated in one motor phase in milliwatts.
+///
+/// Computes I²R from the RMS coil current and the winding resistance.
+/// A two-phase stepper dissipates twice this with both coils energized.
+/// The resistance rises with winding temperature, so measuring it on a
+/// warm motor gives a more realistic figure.
+///
+/// # Arguments
+///
+/// * `rms_current_ma` - RMS coil current in milliamps
+/// * `coil_resistance_ohms` - Winding resistance of one phase in ohms
+pub fn estimated_power_dissipation_mw(rms_current_ma: u16, coil_resistance_ohms: f32) -> f32 {
+    let current_a = rms_current_ma as f32 / 1000.0;
+    current_a * current_a * coil_resistance_ohms * 1000.0
+}
+
 /// Convert velocity in steps/second to VACTUAL register value.
 ///
 /// # Arguments
@@ -133,12 +304,50 @@ pub fn calculate_current_settings(rms_current_ma: u16, rsense: f32) -> Option<(u
 ///
 /// # Formula
 ///
-/// VACTUAL = velocity * 2^23 / fCLK
+/// VACTUAL = velocity * 2^24 / fCLK
 /// where velocity is in microsteps/second
+///
+/// The result is clamped to [`Vactual::MIN`]..=[`Vactual::MAX`]; use
+/// [`velocity_to_vactual_checked`] to detect out-of-range velocities.
 pub fn velocity_to_vactual(steps_per_sec: f32, microsteps: u16, fclk: u32) -> i32 {
     let microsteps_per_sec = steps_per_sec * microsteps as f32;
-    let vactual = microsteps_per_sec * 8388608.0 / fclk as f32; // 2^23 = 8388608
-    round_f32(vactual) as i32
+    let vactual = microsteps_per_sec / vactual_scale(fclk);
+    (round_f32(vactual) as i32).clamp(Vactual::MIN, Vactual::MAX)
+}
+
+/// Convert velocity in steps/second to VACTUAL, if representable.
+///
+/// Like [`velocity_to_vactual`], but returns `None` instead of clamping
+/// when the result falls outside the 24-bit VACTUAL range or the input
+/// is NaN.
+pub fn velocity_to_vactual_checked(steps_per_sec: f32, microsteps: u16, fclk: u32) -> Option<i32> {
+    if steps_per_sec.is_nan() {
+        return None;
+    }
+    let microsteps_per_sec = steps_per_sec * microsteps as f32;
+    let vactual = round_f32(microsteps_per_sec / vactual_scale(fclk));
+    if vactual < Vactual::MIN as f32 || vactual > Vactual::MAX as f32 {
+        None
+    } else {
+        Some(vactual as i32)
+    }
+}
+
+/// Get the VACTUAL scaling factor for a clock frequency.
+///
+/// Returns the velocity in microsteps/second of one VACTUAL unit. The
+/// datasheet gives v = VACTUAL * (fCLK / 2) / 2^23, i.e. fCLK / 2^24, which
+/// is about 0.715 at 12 MHz.
+pub fn vactual_scale(fclk: u32) -> f32 {
+    fclk as f32 / 16777216.0 // 2^24
+}
+
+/// Convert a VACTUAL register value to microsteps/second.
+///
+/// This is the inverse of [`velocity_to_vactual`] before division by the
+/// microstep resolution. The sign gives the direction.
+pub fn vactual_to_usteps_per_sec(vactual: i32, fclk: u32) -> f32 {
+    vactual as f32 * vactual_scale(fclk)
 }
 
 /// Convert TSTEP register value to velocity in steps/second.
@@ -164,6 +373,40 @@ pub fn tstep_to_velocity(tstep: u32, microsteps: u16, fclk: u32) -> Option<f32>
     Some(steps_per_sec)
 }
 
+/// Motion state derived from TSTEP.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VelocityState {
+    /// TSTEP overflowed: the motor is at standstill (or moving very slowly).
+    Standstill,
+    /// The motor is moving.
+    Moving {
+        /// Velocity in full steps per second.
+        sps: f32,
+    },
+}
+
+impl VelocityState {
+    /// Interpret a TSTEP value.
+    ///
+    /// The overflow value 0xFFFFF (and 0, which is never a valid step time)
+    /// are reported as [`VelocityState::Standstill`].
+    pub fn from_tstep(tstep: u32, microsteps: u16, fclk: u32) -> Self {
+        if tstep >= 0xFFFFF {
+            return Self::Standstill;
+        }
+        match tstep_to_velocity(tstep, microsteps, fclk) {
+            Some(sps) => Self::Moving { sps },
+            None => Self::Standstill,
+        }
+    }
+
+    /// Check if the motor is at standstill.
+    pub fn is_standstill(&self) -> bool {
+        matches!(self, Self::Standstill)
+    }
+}
+
 /// Calculate TPWMTHRS for a given velocity threshold.
 ///
 /// TPWMTHRS sets the upper velocity limit for StealthChop.
@@ -189,6 +432,76 @@ pub fn velocity_to_tpwmthrs(steps_per_sec: f32, microsteps: u16, fclk: u32) -> u
     (tstep as u32).min(0xFFFFF)
 }
 
+/// Microstep frequency above which GCONF.multistep_filt is recommended.
+pub const MULTISTEP_FILT_THRESHOLD_HZ: f32 = 750.0;
+
+/// Calculate the microstep frequency for a velocity.
+///
+/// Returns the STEP pulse rate in Hz, i.e. `steps_per_sec` full steps per
+/// second times the microsteps per full step. The sign of the velocity is
+/// ignored. Above [`MULTISTEP_FILT_THRESHOLD_HZ`] the multistep filter
+/// should be enabled.
+pub fn microstep_frequency_hz(steps_per_sec: f32, res: MicrostepResolution) -> f32 {
+    steps_per_sec.abs() * res.microsteps() as f32
+}
+
+/// Margin between the quietest free-running SG_RESULT and the stall trip
+/// point used by [`recommend_stall_threshold`], in percent.
+pub const STALL_THRESHOLD_MARGIN_PERCENT: u32 = 20;
+
+/// Recommend an SGTHRS value from SG_RESULT samples taken without load.
+///
+/// A stall is signalled when SG_RESULT falls to 2 * SGTHRS or below. The
+/// returned threshold puts that trip point [`STALL_THRESHOLD_MARGIN_PERCENT`]
+/// below the smallest sample, so normal load variation at the quietest
+/// point does not trip it: SGTHRS = min * (100 - margin) / 200, clamped to
+/// 255. Returns `None` if `samples` is empty.
+pub fn recommend_stall_threshold(samples: &[u16]) -> Option<u8> {
+    let min = samples.iter().copied().min()? as u32;
+    let threshold = min * (100 - STALL_THRESHOLD_MARGIN_PERCENT) / 200;
+    Some(threshold.min(u8::MAX as u32) as u8)
+}
+
+/// Convert SG_RESULT to an approximate load percentage.
+///
+/// Maps 510 (no load) to 0% and 0 (stall) to 100%, rounding to the
+/// nearest percent. SG_RESULT also depends on velocity and current, so
+/// this is only a coarse gauge for display, not a calibrated torque.
+pub fn sg_to_load_percent(sg_result: u16) -> u8 {
+    let headroom = 510 - sg_result.min(510) as u32;
+    ((headroom * 100 + 255) / 510) as u8
+}
+
+/// Compute the microsteps moved between two MSCNT readings.
+///
+/// MSCNT wraps at 1024, so the raw difference is ambiguous. With the
+/// direction known, the delta is taken the way the motor turned:
+/// 0..=1023 forward, -1023..=0 backward. The readings must be sampled
+/// often enough that the motor moves less than 1024 microsteps (four
+/// full steps) in between, otherwise whole wraps are missed.
+pub fn mscnt_delta(prev: u16, curr: u16, direction_forward: bool) -> i16 {
+    let forward = curr.wrapping_sub(prev) & 0x3FF;
+    if direction_forward {
+        forward as i16
+    } else {
+        -(((1024 - forward) & 0x3FF) as i16)
+    }
+}
+
+/// Compute the shortest signed distance between two MSCNT readings.
+///
+/// For when the direction is unknown: MSCNT wraps every 1024 counts, so
+/// the delta is taken the short way round and lies in `-512..512`. Use
+/// [`mscnt_delta`] when the direction of motion is known.
+pub fn mscnt_shortest_delta(prev: u16, curr: u16) -> i16 {
+    let diff = (curr.wrapping_sub(prev) & 0x3FF) as i16;
+    if diff >= 512 {
+        diff - 1024
+    } else {
+        diff
+    }
+}
+
 /// Default TMC2209 internal clock frequency (12 MHz).
 pub const DEFAULT_FCLK: u32 = 12_000_000;
 
@@ -196,6 +509,112 @@ pub const DEFAULT_FCLK: u32 = 12_000_000;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_coil_current_ma() {
+        // Peak current at CS=31, VSENSE=0, 0.11 ohm: 0.325 / 0.11 = 2.955 A
+        assert_eq!(coil_current_ma(248, 31, 0.11, false), 2955);
+        assert_eq!(coil_current_ma(-124, 31, 0.11, false), -1477);
+        assert_eq!(coil_current_ma(0, 31, 0.11, false), 0);
+        // Half the current scale halves the current
+        assert_eq!(coil_current_ma(248, 15, 0.11, false), 1477);
+        // RMS of the peak matches cs_to_current
+        let rms = coil_current_ma(248, 20, 0.11, true) as f32 / SQRT_2;
+        assert!((rms - cs_to_current(20, 0.11, true) as f32).abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_sg_to_load_percent() {
+        assert_eq!(sg_to_load_percent(0), 100);
+        assert_eq!(sg_to_load_percent(255), 50);
+        assert_eq!(sg_to_load_percent(510), 0);
+        // SG_RESULT is 10 bits wide, values above 510 mean no load
+        assert_eq!(sg_to_load_percent(1023), 0);
+    }
+
+    #[test]
+    fn test_mscnt_delta() {
+        assert_eq!(mscnt_delta(100, 164, true), 64);
+        assert_eq!(mscnt_delta(164, 100, false), -64);
+        assert_eq!(mscnt_delta(500, 500, true), 0);
+        assert_eq!(mscnt_delta(500, 500, false), 0);
+
+        // Forward across the 1023 -> 0 wrap
+        assert_eq!(mscnt_delta(1020, 4, true), 8);
+        assert_eq!(mscnt_delta(1023, 0, true), 1);
+        // Backward across the 0 -> 1023 wrap
+        assert_eq!(mscnt_delta(4, 1020, false), -8);
+        assert_eq!(mscnt_delta(0, 1023, false), -1);
+
+        // The known direction wins over the shorter path
+        assert_eq!(mscnt_delta(0, 1023, true), 1023);
+        assert_eq!(mscnt_delta(1023, 0, false), -1023);
+    }
+
+    #[test]
+    fn test_mscnt_shortest_delta() {
+        assert_eq!(mscnt_shortest_delta(1020, 4), 8);
+        assert_eq!(mscnt_shortest_delta(4, 1020), -8);
+        assert_eq!(mscnt_shortest_delta(0, 0), 0);
+        assert_eq!(mscnt_shortest_delta(0, 511), 511);
+        assert_eq!(mscnt_shortest_delta(0, 512), -512);
+    }
+
+    #[test]
+    fn test_microstep_frequency_hz() {
+        use MicrostepResolution::*;
+
+        assert_eq!(microstep_frequency_hz(100.0, M8), 800.0);
+        assert_eq!(microstep_frequency_hz(-100.0, M8), 800.0);
+        assert_eq!(microstep_frequency_hz(46.875, M16), 750.0);
+        assert_eq!(microstep_frequency_hz(700.0, M1), 700.0);
+        assert_eq!(microstep_frequency_hz(3.0, M256), 768.0);
+
+        let above = |sps, res| microstep_frequency_hz(sps, res) > MULTISTEP_FILT_THRESHOLD_HZ;
+        assert!(above(100.0, M8));
+        assert!(!above(46.875, M16));
+        assert!(!above(700.0, M1));
+        assert!(above(3.0, M256));
+        assert!(!above(2.9, M256));
+    }
+
+    #[test]
+    fn test_velocity_to_vactual_range() {
+        // With fCLK = 2^24 one VACTUAL unit is one microstep/second
+        let fclk = 1 << 24;
+        let max = Vactual::MAX as f32;
+        assert_eq!(
+            velocity_to_vactual_checked(max, 1, fclk),
+            Some(Vactual::MAX)
+        );
+        assert_eq!(
+            velocity_to_vactual_checked(-max, 1, fclk),
+            Some(Vactual::MIN)
+        );
+        assert_eq!(velocity_to_vactual_checked(max + 1.0, 1, fclk), None);
+        assert_eq!(velocity_to_vactual_checked(-max - 1.0, 1, fclk), None);
+        assert_eq!(velocity_to_vactual_checked(f32::NAN, 1, fclk), None);
+        assert_eq!(velocity_to_vactual_checked(1.0, 16, 0), None);
+
+        assert_eq!(velocity_to_vactual(max + 1.0, 1, fclk), Vactual::MAX);
+        assert_eq!(velocity_to_vactual(1e12, 256, DEFAULT_FCLK), Vactual::MAX);
+        assert_eq!(velocity_to_vactual(-1e12, 256, DEFAULT_FCLK), Vactual::MIN);
+        assert_eq!(
+            velocity_to_vactual(100.0, 256, DEFAULT_FCLK),
+            velocity_to_vactual_checked(100.0, 256, DEFAULT_FCLK).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_recommend_stall_threshold() {
+        assert_eq!(recommend_stall_threshold(&[]), None);
+        // Trip point 2 * 96 = 192 is 20% below the quietest sample
+        assert_eq!(recommend_stall_threshold(&[300, 240, 281, 265]), Some(96));
+        assert_eq!(recommend_stall_threshold(&[241]), Some(96));
+        assert_eq!(recommend_stall_threshold(&[1]), Some(0));
+        assert_eq!(recommend_stall_threshold(&[1023, 900]), Some(255));
+        assert_eq!(recommend_stall_threshold(&[0, 500]), Some(0));
+    }
+
     #[test]
     fn test_current_to_cs() {
         // With 0.11 ohm sense resistor, VSENSE=0
@@ -215,6 +634,60 @@ mod tests {
         assert!(current > 2000); // Should be around 2.1A
     }
 
+    #[test]
+    fn test_current_settings_with_custom_vfs() {
+        let nominal = CurrentSenseParams::new(0.11);
+        assert_eq!(
+            calculate_current_settings_with(&nominal, 800),
+            calculate_current_settings(800, 0.11)
+        );
+
+        // A reference 10% above nominal needs a lower CS for the same current
+        let trimmed = CurrentSenseParams {
+            vfs_low: VFS_LOW * 1.1,
+            vfs_high: VFS_HIGH * 1.1,
+            rsense: 0.11,
+        };
+        let (cs_nominal, vsense_nominal) = calculate_current_settings_with(&nominal, 800).unwrap();
+        let (cs_trimmed, vsense_trimmed) = calculate_current_settings_with(&trimmed, 800).unwrap();
+        assert_eq!(vsense_nominal, vsense_trimmed);
+        assert!(cs_trimmed < cs_nominal);
+
+        assert!(cs_to_current_with(&trimmed, 20, true) > cs_to_current(20, 0.11, true));
+    }
+
+    #[test]
+    fn test_current_headroom() {
+        // Low current fits the high sensitivity range
+        let advice = current_headroom(300, 0.11);
+        assert!(advice.vsense);
+        assert!(!advice.too_high);
+        assert!(advice.cs < 31);
+        assert!(advice.headroom_ma(300) > 0);
+
+        // Mid current needs low sensitivity
+        let advice = current_headroom(1500, 0.11);
+        assert!(!advice.vsense);
+        assert!(!advice.too_high);
+        assert!(advice.max_current_ma > 2000);
+
+        // Over-range current is flagged
+        let advice = current_headroom(5000, 0.11);
+        assert!(!advice.vsense);
+        assert!(advice.too_high);
+        assert_eq!(advice.cs, 31);
+        assert_eq!(advice.headroom_ma(5000), 0);
+    }
+
+    #[test]
+    fn test_estimated_power_dissipation_mw() {
+        assert!((estimated_power_dissipation_mw(1000, 2.0) - 2000.0).abs() < 0.01);
+        assert!((estimated_power_dissipation_mw(500, 1.5) - 375.0).abs() < 0.01);
+        // 1.7 A through a typical 1.1 ohm NEMA17 coil
+        assert!((estimated_power_dissipation_mw(1700, 1.1) - 3179.0).abs() < 0.01);
+        assert_eq!(estimated_power_dissipation_mw(0, 2.0), 0.0);
+    }
+
     #[test]
     fn test_velocity_conversion() {
         // 100 steps/sec with 256 microsteps at 12MHz
@@ -226,4 +699,26 @@ mod tests {
         let velocity = tstep_to_velocity(tstep as u32, 256, 12_000_000);
         assert!(velocity.is_some());
     }
+
+    #[test]
+    fn test_vactual_scale() {
+        let scale = vactual_scale(12_000_000);
+        assert!((scale - 0.715).abs() < 0.001);
+
+        let vactual = velocity_to_vactual(100.0, 256, 12_000_000);
+        let usteps = vactual_to_usteps_per_sec(vactual, 12_000_000);
+        assert!((usteps - 25_600.0).abs() < scale);
+        assert!(vactual_to_usteps_per_sec(-vactual, 12_000_000) < 0.0);
+    }
+
+    #[test]
+    fn test_velocity_to_vactual_exact() {
+        // 1000 usteps/s * 2^24 / 12 MHz = 1398.1
+        assert_eq!(velocity_to_vactual(1000.0, 1, 12_000_000), 1398);
+        assert_eq!(velocity_to_vactual(-1000.0, 1, 12_000_000), -1398);
+        assert_eq!(
+            velocity_to_vactual_checked(1000.0, 1, 12_000_000),
+            Some(1398)
+        );
+    }
 }